@@ -1,105 +1,626 @@
-use tauri::command;
+use tauri::{command, AppHandle, Manager};
 use std::fs::File;
-use std::path::Path;
-use std::io::{Read, Write};
-use flate2::read::GzDecoder;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::io::{self, BufReader, Read};
+use std::sync::atomic::AtomicBool;
+use std::sync::Once;
+use bzip2::read::MultiBzDecoder;
+use chrono::TimeZone;
+use flate2::read::MultiGzDecoder;
+use serde::Serialize;
+use sevenz_rust::{Archive as SevenZArchive, BlockDecoder, SevenZArchiveEntry};
 use tar::Archive;
+use xz2::read::XzDecoder;
+use zeroize::Zeroizing;
 use zip::read::ZipArchive;
-use zip::write::FileOptions;
-use zip::CompressionMethod;
+use zip::result::ZipError;
 
-#[command]
-pub async fn extract_archive(path: String) -> Result<(), String> {
-    let file_path = Path::new(&path);
-    if !file_path.exists() {
-        return Err("File does not exist".to_string());
+use crate::commands::archive_password::{no_password, prompt_password, with_password, PasswordFn, PasswordPurpose};
+use crate::commands::batch::unique_dest_path;
+use crate::commands::compress::{create_blocking, ArchiveFormat, CreateArchiveOptions};
+use crate::commands::extract::{extract_blocking, ExtractOptions};
+use crate::commands::operation::{register_operation_with_paths, unregister_operation};
+use crate::commands::protocol::allow_dir;
+use crate::utils::magic::{detect_bytes, detect_file};
+use crate::utils::text_encoding::{decode, detect_encoding, SNIFF_BYTES};
+
+/// Cap for `read_archive_entry`, matching the on-disk preview limit.
+const MAX_ENTRY_PREVIEW_BYTES: usize = 50 * 1024 * 1024; // 50 MB
+/// 7-Zip keeps unix mode bits in the high word of the Windows attributes when this bit is set.
+const FILE_ATTRIBUTE_UNIX_EXTENSION: u32 = 0x8000;
+const ENTRY_PREVIEW_DIR_NAME: &str = "archive_previews";
+
+static ENTRY_PREVIEWS_CLEANED: Once = Once::new();
+
+/// Stream compression wrapped around a tar or a single file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Codec {
+    Gzip,
+    Bzip2,
+    Xz,
+    Zstd,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ArchiveKind {
+    Zip,
+    SevenZ,
+    Tar(Option<Codec>),
+    /// One compressed file such as "notes.txt.gz", listed as a single entry.
+    Compressed(Codec),
+}
+
+/// Names that mark a compressed stream as a tar even when its header can't be sniffed.
+pub(crate) const TAR_EXTENSIONS: &[&str] = &[
+    ".tar.gz", ".tgz", ".tar.bz2", ".tbz2", ".tbz", ".tar.xz", ".txz", ".tar.zst", ".tzst", ".tar",
+];
+
+/// Detects the format from the file's magic number. The name only decides what the bytes
+/// can't: pre-POSIX tars without a "ustar" header, and damaged files named .zip.
+pub(crate) fn archive_kind(path: &Path) -> Option<ArchiveKind> {
+    let name = path.file_name()?.to_string_lossy().to_lowercase();
+    let named_tar = TAR_EXTENSIONS.iter().any(|ext| name.ends_with(ext));
+    let codec = match detect_file(path).map(|t| t.mime) {
+        Some("application/zip") => return Some(ArchiveKind::Zip),
+        Some("application/x-7z-compressed") => return Some(ArchiveKind::SevenZ),
+        Some("application/x-tar") => return Some(ArchiveKind::Tar(None)),
+        Some("application/gzip") => Codec::Gzip,
+        Some("application/x-bzip2") => Codec::Bzip2,
+        Some("application/x-xz") => Codec::Xz,
+        Some("application/zstd") => Codec::Zstd,
+        _ if name.ends_with(".zip") => return Some(ArchiveKind::Zip),
+        _ if name.ends_with(".tar") => return Some(ArchiveKind::Tar(None)),
+        _ => return None,
+    };
+    if named_tar || decompressed_is_tar(path, codec) {
+        Some(ArchiveKind::Tar(Some(codec)))
+    } else {
+        Some(ArchiveKind::Compressed(codec))
     }
+}
 
-    let file_name = file_path.file_stem().unwrap_or_default().to_string_lossy();
-    let parent_dir = file_path.parent().unwrap_or(Path::new(""));
-    let mut dest_dir = parent_dir.join(file_name.as_ref());
+/// Decompresses the first tar block and looks for the POSIX "ustar" magic.
+fn decompressed_is_tar(path: &Path, codec: Codec) -> bool {
+    let Ok(reader) = open_stream(path, Some(codec)) else { return false };
+    let mut head = Vec::with_capacity(512);
+    reader.take(512).read_to_end(&mut head).is_ok() && head.get(257..262) == Some(b"ustar".as_slice())
+}
+
+pub(crate) const UNSUPPORTED_FORMAT: &str =
+    "Unsupported archive format. Supported formats: .zip, .7z, .tar (optionally gzip, bzip2, xz or zstd compressed) and single .gz, .bz2, .xz or .zst files";
+
+/// Archive failure, serialized as `{ kind, message }` so the UI can ask again after a wrong
+/// password instead of showing a generic error.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+pub enum ArchiveError {
+    /// Encrypted content and no way to ask for a password (no `operation_id`).
+    PasswordRequired(String),
+    WrongPassword(String),
+    /// The password prompt was dismissed or the operation cancelled.
+    Cancelled(String),
+    Unsupported(String),
+    Other(String),
+}
 
-    // If folder already exists, find a new name to avoid blind overwriting.
-    let mut counter = 1;
-    while dest_dir.exists() {
-        dest_dir = parent_dir.join(format!("{} ({})", file_name, counter));
-        counter += 1;
+impl std::fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PasswordRequired(m) | Self::WrongPassword(m) | Self::Cancelled(m) | Self::Unsupported(m) | Self::Other(m) => {
+                f.write_str(m)
+            }
+        }
     }
+}
 
-    std::fs::create_dir_all(&dest_dir).map_err(|e| e.to_string())?;
-
-    let file = File::open(&file_path).map_err(|e| e.to_string())?;
-    let path_str = path.to_lowercase();
-
-    if path_str.ends_with(".zip") {
-        let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
-        archive.extract(&dest_dir).map_err(|e| e.to_string())?;
-    } else if path_str.ends_with(".tar.gz") || path_str.ends_with(".tgz") {
-        let tar = GzDecoder::new(file);
-        let mut archive = Archive::new(tar);
-        archive.unpack(&dest_dir).map_err(|e| e.to_string())?;
-    } else if path_str.ends_with(".tar") {
-        let mut archive = Archive::new(file);
-        archive.unpack(&dest_dir).map_err(|e| e.to_string())?;
-    } else {
-        return Err("Unsupported archive format. Supported formats: .zip, .tar.gz, .tgz, .tar".to_string());
+impl From<String> for ArchiveError {
+    fn from(message: String) -> Self {
+        Self::Other(message)
     }
+}
 
-    Ok(())
+impl From<&str> for ArchiveError {
+    fn from(message: &str) -> Self {
+        Self::Other(message.to_string())
+    }
 }
 
-#[command]
-pub async fn compress_to_zip(paths: Vec<String>, dest_path: String) -> Result<(), String> {
-    println!("Compressing {} items to: {}", paths.len(), dest_path);
-    if paths.is_empty() {
-        return Err("No files selected for compression".to_string());
+pub(crate) fn zip_error(e: ZipError) -> ArchiveError {
+    match e {
+        ZipError::InvalidPassword => ArchiveError::WrongPassword("Wrong password".to_string()),
+        ZipError::UnsupportedArchive(ZipError::PASSWORD_REQUIRED) => {
+            ArchiveError::PasswordRequired("Archive is password protected".to_string())
+        }
+        ZipError::FileNotFound => ArchiveError::Other("Entry not found in archive".to_string()),
+        e => ArchiveError::Other(e.to_string()),
+    }
+}
+
+fn unsupported_format() -> ArchiveError {
+    ArchiveError::Unsupported(UNSUPPORTED_FORMAT.to_string())
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ArchiveEntry {
+    /// Path inside the archive, `/`-separated; pass to `read_archive_entry`.
+    pub path: String,
+    pub name: String,
+    pub is_dir: bool,
+    pub is_symlink: bool,
+    pub size: u64,
+    /// Per-entry compressed size; `None` for tar and 7z, where compression spans entries.
+    pub compressed_size: Option<u64>,
+    pub modified: Option<u64>,
+    pub encrypted: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ArchiveEntryContent {
+    pub path: String,
+    /// Uncompressed size of the whole entry.
+    pub size: u64,
+    /// True when only the first `max_bytes` were read.
+    pub truncated: bool,
+    pub mime: String,
+    /// Decoded text when the content isn't binary.
+    pub text: Option<String>,
+    /// Temp copy of the bytes read, servable as `sdm://file/<path>` for image/media viewers.
+    pub preview_path: String,
+}
+
+fn entry_name(path: &str) -> String {
+    path.trim_end_matches('/').rsplit('/').next().unwrap_or(path).to_string()
+}
+
+/// Zip stores local wall-clock time without a zone.
+pub(crate) fn zip_mtime(dt: zip::DateTime) -> Option<u64> {
+    let naive = chrono::NaiveDate::from_ymd_opt(dt.year().into(), dt.month().into(), dt.day().into())?
+        .and_hms_opt(dt.hour().into(), dt.minute().into(), dt.second().into())?;
+    let local = chrono::Local.from_local_datetime(&naive).earliest()?;
+    u64::try_from(local.timestamp()).ok()
+}
+
+/// Wraps a raw stream in the decompressor for `codec`. Concatenated streams (pigz, pbzip2,
+/// pixz output) are read through to the end.
+pub(crate) fn decompress(reader: Box<dyn Read>, codec: Codec) -> io::Result<Box<dyn Read>> {
+    Ok(match codec {
+        Codec::Gzip => Box::new(MultiGzDecoder::new(reader)),
+        Codec::Bzip2 => Box::new(MultiBzDecoder::new(reader)),
+        Codec::Xz => Box::new(XzDecoder::new_multi_decoder(reader)),
+        Codec::Zstd => Box::new(zstd::stream::read::Decoder::new(reader)?),
+    })
+}
+
+fn open_stream(path: &Path, codec: Option<Codec>) -> Result<Box<dyn Read>, String> {
+    let file: Box<dyn Read> = Box::new(BufReader::new(File::open(path).map_err(|e| e.to_string())?));
+    match codec {
+        Some(codec) => decompress(file, codec).map_err(|e| e.to_string()),
+        None => Ok(file),
     }
+}
 
-    let dest_file = File::create(&dest_path).map_err(|e| e.to_string())?;
-    let mut zip = zip::ZipWriter::new(dest_file);
-    let options = FileOptions::<()>::default()
-        .compression_method(CompressionMethod::Deflated)
-        .unix_permissions(0o755);
+/// Wraps the raw archive stream in the decompressor the tar needs.
+pub(crate) fn tar_from_reader(reader: Box<dyn Read>, codec: Option<Codec>) -> Result<Archive<Box<dyn Read>>, String> {
+    let reader = match codec {
+        Some(codec) => decompress(reader, codec).map_err(|e| e.to_string())?,
+        None => reader,
+    };
+    Ok(Archive::new(reader))
+}
 
-    let mut buffer = Vec::new();
+fn open_tar(path: &Path, codec: Option<Codec>) -> Result<Archive<Box<dyn Read>>, String> {
+    Ok(Archive::new(open_stream(path, codec)?))
+}
+
+/// Name of the single entry in a compressed file: "notes.txt.gz" -> "notes.txt".
+pub(crate) fn compressed_entry_name(path: &Path) -> String {
+    path.file_stem().unwrap_or_default().to_string_lossy().to_string()
+}
 
-    for path_str in paths {
-        let path = Path::new(&path_str);
-        if !path.exists() {
-            continue;
+fn sevenz_error(e: sevenz_rust::Error) -> ArchiveError {
+    match e {
+        // AES support would pull in another crypto stack for a format we only read.
+        sevenz_rust::Error::PasswordRequired | sevenz_rust::Error::MaybeBadPassword(_) => {
+            ArchiveError::Unsupported("Encrypted 7z archives are not supported".to_string())
         }
+        sevenz_rust::Error::UnsupportedCompressionMethod(method) if method.contains("AES") => {
+            ArchiveError::Unsupported("Encrypted 7z archives are not supported".to_string())
+        }
+        sevenz_rust::Error::Io(e, _) | sevenz_rust::Error::FileOpen(e, _) => ArchiveError::Other(e.to_string()),
+        e => ArchiveError::Other(format!("Invalid 7z archive: {}", e)),
+    }
+}
 
-        if path.is_file() {
-            let file_name = path.file_name().unwrap_or_default().to_string_lossy().replace("\\", "/");
-            zip.start_file(file_name, options).map_err(|e| e.to_string())?;
-            let mut f = File::open(path).map_err(|e| e.to_string())?;
-            f.read_to_end(&mut buffer).map_err(|e| e.to_string())?;
-            zip.write_all(&buffer).map_err(|e| e.to_string())?;
-            buffer.clear();
-        } else if path.is_dir() {
-            let base_path = path.parent().unwrap_or(Path::new(""));
-
-            for entry in walkdir::WalkDir::new(path) {
-                let entry = match entry {
-                    Ok(e) => e,
-                    Err(_) => continue,
-                };
-                let entry_path = entry.path();
-                let relative_path = entry_path.strip_prefix(base_path).unwrap_or(entry_path).to_string_lossy().replace("\\", "/");
-
-                if entry_path.is_file() {
-                    zip.start_file(relative_path, options).map_err(|e| e.to_string())?;
-                    let mut f = File::open(entry_path).map_err(|e| e.to_string())?;
-                    f.read_to_end(&mut buffer).map_err(|e| e.to_string())?;
-                    zip.write_all(&buffer).map_err(|e| e.to_string())?;
-                    buffer.clear();
-                } else if entry_path.is_dir() && !relative_path.is_empty() {
-                    zip.add_directory(relative_path, options).map_err(|e| e.to_string())?;
+/// Reads the 7z header; the file is returned for decoding the entries.
+pub(crate) fn open_7z(path: &Path) -> Result<(SevenZArchive, File), ArchiveError> {
+    let mut file = File::open(path).map_err(|e| e.to_string())?;
+    let len = file.metadata().map_err(|e| e.to_string())?.len();
+    let archive = SevenZArchive::read(&mut file, len, &[]).map_err(sevenz_error)?;
+    Ok((archive, file))
+}
+
+pub(crate) fn sevenz_entry_path(entry: &SevenZArchiveEntry) -> String {
+    entry.name.replace('\\', "/")
+}
+
+pub(crate) fn sevenz_mtime(entry: &SevenZArchiveEntry) -> Option<u64> {
+    entry
+        .has_last_modified_date
+        .then(|| entry.last_modified_date.to_unix_time())
+        .and_then(|t| u64::try_from(t).ok())
+}
+
+/// Unix mode bits, present in archives made by p7zip and 7-Zip on unix.
+pub(crate) fn sevenz_unix_mode(entry: &SevenZArchiveEntry) -> Option<u32> {
+    (entry.has_windows_attributes && entry.windows_attributes & FILE_ATTRIBUTE_UNIX_EXTENSION != 0)
+        .then_some(entry.windows_attributes >> 16)
+}
+
+/// Symlinks are stored as a file whose content is the link target.
+pub(crate) fn sevenz_is_symlink(entry: &SevenZArchiveEntry) -> bool {
+    sevenz_unix_mode(entry).is_some_and(|mode| mode & 0o170000 == 0o120000)
+}
+
+/// Decodes entries in archive order, handing each to `each` until it returns `Ok(false)`.
+/// Entries in a solid block share one stream, so whatever `each` leaves unread is skipped over.
+pub(crate) fn for_each_7z_entry(
+    archive: &SevenZArchive,
+    file: &mut File,
+    mut each: impl FnMut(&SevenZArchiveEntry, &mut dyn Read) -> Result<bool, ArchiveError>,
+) -> Result<(), ArchiveError> {
+    let mut failure = None;
+    let mut walk = |entry: &SevenZArchiveEntry, reader: &mut dyn Read| -> Result<bool, sevenz_rust::Error> {
+        match each(entry, reader) {
+            Ok(true) => io::copy(reader, &mut io::sink()).map(|_| true).map_err(sevenz_rust::Error::io),
+            Ok(false) => Ok(false),
+            Err(e) => {
+                failure = Some(e);
+                Ok(false)
+            }
+        }
+    };
+
+    let mut finished = true;
+    for folder in 0..archive.folders.len() {
+        if !BlockDecoder::new(folder, archive, &[], file).for_each_entries(&mut walk).map_err(sevenz_error)? {
+            finished = false;
+            break;
+        }
+    }
+    // Empty files and folders live outside any block.
+    if finished {
+        for (index, entry) in archive.files.iter().enumerate() {
+            if archive.stream_map.file_folder_index[index].is_none() && !walk(entry, &mut io::empty()).map_err(sevenz_error)? {
+                break;
+            }
+        }
+    }
+    failure.map_or(Ok(()), Err)
+}
+
+/// Lists entries without extracting. Zip and 7z read only their headers; tar has to stream
+/// through the archive (decompressing it if needed) but skips over file contents. A single
+/// compressed file is listed as one entry, decompressed once to learn its size.
+#[command]
+pub async fn list_archive(path: String) -> Result<Vec<ArchiveEntry>, ArchiveError> {
+    tokio::task::spawn_blocking(move || {
+        let p = Path::new(&path);
+        if !p.is_file() {
+            return Err("File does not exist".into());
+        }
+        match archive_kind(p).ok_or_else(unsupported_format)? {
+            ArchiveKind::Zip => list_zip(p),
+            ArchiveKind::SevenZ => list_7z(p),
+            ArchiveKind::Tar(codec) => list_tar(p, codec),
+            ArchiveKind::Compressed(codec) => list_compressed(p, codec),
+        }
+    })
+    .await
+    .map_err(|e| ArchiveError::Other(e.to_string()))?
+}
+
+fn list_zip(p: &Path) -> Result<Vec<ArchiveEntry>, ArchiveError> {
+    let file = BufReader::new(File::open(p).map_err(|e| e.to_string())?);
+    let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
+    let mut entries = Vec::with_capacity(archive.len());
+    for i in 0..archive.len() {
+        // Raw access reads metadata without needing a password for encrypted entries.
+        let entry = archive.by_index_raw(i).map_err(|e| e.to_string())?;
+        let path = entry.name().to_string();
+        entries.push(ArchiveEntry {
+            name: entry_name(&path),
+            is_dir: entry.is_dir(),
+            is_symlink: entry.is_symlink(),
+            size: entry.size(),
+            compressed_size: Some(entry.compressed_size()),
+            modified: entry.last_modified().and_then(zip_mtime),
+            encrypted: entry.encrypted(),
+            path,
+        });
+    }
+    Ok(entries)
+}
+
+fn list_7z(p: &Path) -> Result<Vec<ArchiveEntry>, ArchiveError> {
+    let (archive, _) = open_7z(p)?;
+    Ok(archive
+        .files
+        .iter()
+        .filter(|entry| !entry.is_anti_item)
+        .map(|entry| {
+            let path = sevenz_entry_path(entry);
+            ArchiveEntry {
+                name: entry_name(&path),
+                is_dir: entry.is_directory,
+                is_symlink: sevenz_is_symlink(entry),
+                size: entry.size,
+                compressed_size: None,
+                modified: sevenz_mtime(entry),
+                encrypted: false,
+                path,
+            }
+        })
+        .collect())
+}
+
+fn list_tar(p: &Path, codec: Option<Codec>) -> Result<Vec<ArchiveEntry>, ArchiveError> {
+    let mut archive = open_tar(p, codec)?;
+    let mut entries = Vec::new();
+    for entry in archive.entries().map_err(|e| e.to_string())? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let header = entry.header();
+        let path = entry.path().map_err(|e| e.to_string())?.to_string_lossy().replace('\\', "/");
+        let entry_type = header.entry_type();
+        entries.push(ArchiveEntry {
+            name: entry_name(&path),
+            is_dir: entry_type.is_dir(),
+            is_symlink: entry_type.is_symlink(),
+            size: entry.size(),
+            compressed_size: None,
+            modified: header.mtime().ok(),
+            encrypted: false,
+            path,
+        });
+    }
+    Ok(entries)
+}
+
+fn list_compressed(p: &Path, codec: Codec) -> Result<Vec<ArchiveEntry>, ArchiveError> {
+    let meta = std::fs::metadata(p).map_err(|e| e.to_string())?;
+    let size = io::copy(&mut open_stream(p, Some(codec))?, &mut io::sink()).map_err(|e| e.to_string())?;
+    let path = compressed_entry_name(p);
+    Ok(vec![ArchiveEntry {
+        name: path.clone(),
+        is_dir: false,
+        is_symlink: false,
+        size,
+        compressed_size: Some(meta.len()),
+        modified: meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs()),
+        encrypted: false,
+        path,
+    }])
+}
+
+/// Reads up to `max_bytes` of one entry into memory. Returns (bytes, full size, truncated).
+/// An encrypted zip entry is opened with `password`, asking `ask` for one when it's missing or
+/// wrong; the accepted password is left in `password`.
+pub(crate) fn read_entry_bytes(
+    path: &Path,
+    inner_path: &str,
+    max_bytes: usize,
+    password: &mut Option<Zeroizing<String>>,
+    ask: &mut PasswordFn<'_>,
+) -> Result<(Vec<u8>, u64, bool), ArchiveError> {
+    let inner_path = inner_path.trim_start_matches('/');
+    let mut data = Vec::new();
+    let size = match archive_kind(path).ok_or_else(unsupported_format)? {
+        ArchiveKind::Zip => {
+            let file = BufReader::new(File::open(path).map_err(|e| e.to_string())?);
+            let mut archive = ZipArchive::new(file).map_err(zip_error)?;
+            let index = archive.index_for_name(inner_path).ok_or("Entry not found in archive")?;
+            with_password(password, ask, |pw| open_zip_entry(&mut archive, index, pw).map(|_| ()))?;
+            let entry = open_zip_entry(&mut archive, index, password.as_deref().map(String::as_str))?;
+            if entry.is_dir() {
+                return Err("Entry is a directory".into());
+            }
+            let size = entry.size();
+            entry.take(max_bytes as u64).read_to_end(&mut data).map_err(|e| e.to_string())?;
+            size
+        }
+        ArchiveKind::SevenZ => {
+            let (archive, mut file) = open_7z(path)?;
+            let mut found = None;
+            for_each_7z_entry(&archive, &mut file, |entry, reader| {
+                if entry.is_anti_item || sevenz_entry_path(entry).trim_start_matches("./") != inner_path.trim_start_matches("./") {
+                    return Ok(true);
+                }
+                if entry.is_directory || sevenz_is_symlink(entry) {
+                    return Err("Entry is not a regular file".into());
                 }
+                reader.take(max_bytes as u64).read_to_end(&mut data).map_err(|e| e.to_string())?;
+                found = Some(entry.size);
+                Ok(false)
+            })?;
+            found.ok_or("Entry not found in archive")?
+        }
+        ArchiveKind::Compressed(codec) => {
+            if inner_path != compressed_entry_name(path) {
+                return Err("Entry not found in archive".into());
             }
+            let mut reader = open_stream(path, Some(codec))?;
+            (&mut reader).take(max_bytes as u64).read_to_end(&mut data).map_err(|e| e.to_string())?;
+            // The full size is only known once the rest of the stream is decompressed.
+            data.len() as u64 + io::copy(&mut reader, &mut io::sink()).map_err(|e| e.to_string())?
         }
+        ArchiveKind::Tar(codec) => {
+            let mut archive = open_tar(path, codec)?;
+            let mut found = None;
+            for entry in archive.entries().map_err(|e| e.to_string())? {
+                let entry = entry.map_err(|e| e.to_string())?;
+                let entry_path = entry.path().map_err(|e| e.to_string())?.to_string_lossy().replace('\\', "/");
+                if entry_path.trim_start_matches("./") != inner_path.trim_start_matches("./") {
+                    continue;
+                }
+                if !entry.header().entry_type().is_file() {
+                    return Err("Entry is not a regular file".into());
+                }
+                let size = entry.size();
+                entry.take(max_bytes as u64).read_to_end(&mut data).map_err(|e| e.to_string())?;
+                found = Some(size);
+                break;
+            }
+            found.ok_or("Entry not found in archive")?
+        }
+    };
+    let truncated = (data.len() as u64) < size;
+    Ok((data, size, truncated))
+}
+
+/// Opens a zip entry for reading, decrypting it when `password` is given.
+pub(crate) fn open_zip_entry<'z, R: Read + io::Seek>(
+    archive: &'z mut ZipArchive<R>,
+    index: usize,
+    password: Option<&str>,
+) -> Result<zip::read::ZipFile<'z>, ArchiveError> {
+    match password {
+        Some(password) => archive.by_index_decrypt(index, password.as_bytes()),
+        None => archive.by_index(index),
+    }
+    .map_err(zip_error)
+}
+
+/// Writes the bytes read for a preview into the app cache, named after the archive and entry
+/// so re-opening one overwrites it. Leftovers from earlier sessions are removed on first use.
+fn write_entry_preview(app: &AppHandle, archive: &str, inner_path: &str, ext: &str, data: &[u8]) -> Result<PathBuf, ArchiveError> {
+    let dir = app
+        .path()
+        .app_cache_dir()
+        .unwrap_or_else(|_| std::env::temp_dir().join("smart-desktop-manager"))
+        .join(ENTRY_PREVIEW_DIR_NAME);
+    ENTRY_PREVIEWS_CLEANED.call_once(|| {
+        let _ = std::fs::remove_dir_all(&dir);
+    });
+    std::fs::create_dir_all(&dir).map_err(|e| ArchiveError::Other(format!("Failed to create preview folder: {}", e)))?;
+    allow_dir(&dir);
+
+    let mut hasher = DefaultHasher::new();
+    (archive, inner_path).hash(&mut hasher);
+    let mut file = dir.join(format!("{:016x}", hasher.finish()));
+    if !ext.is_empty() {
+        file.set_extension(ext);
+    }
+    std::fs::write(&file, data).map_err(|e| ArchiveError::Other(format!("Failed to write preview: {}", e)))?;
+    Ok(file)
+}
+
+/// Reads one entry for preview without extracting it next to the archive. Text content is
+/// decoded (UTF-8/UTF-16/Latin-1); the bytes are also written to a temp file in the app cache
+/// that image/media viewers load over the `sdm://` scheme.
+///
+/// With an `operation_id`, an encrypted entry raises "archive_password_required" and waits for
+/// `provide_archive_password`; without one it fails with `password_required`.
+#[command]
+pub async fn read_archive_entry(
+    app: AppHandle,
+    path: String,
+    inner_path: String,
+    max_bytes: Option<usize>,
+    operation_id: Option<String>,
+) -> Result<ArchiveEntryContent, ArchiveError> {
+    let cancel_flag = operation_id.clone().map(|id| register_operation_with_paths(id, [&path]));
+    let op_id = operation_id.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let max_bytes = max_bytes.unwrap_or(MAX_ENTRY_PREVIEW_BYTES).min(MAX_ENTRY_PREVIEW_BYTES);
+        let mut password = None;
+        let (data, size, truncated) = match (&op_id, &cancel_flag) {
+            (Some(op_id), Some(cancel)) => {
+                let mut ask = |attempt: u32, wrong: bool| {
+                    prompt_password(&app, op_id, &path, PasswordPurpose::Extract, cancel, attempt, wrong)
+                };
+                read_entry_bytes(Path::new(&path), &inner_path, max_bytes, &mut password, &mut ask)?
+            }
+            _ => read_entry_bytes(Path::new(&path), &inner_path, max_bytes, &mut password, &mut no_password)?,
+        };
+
+        let sniff = &data[..data.len().min(SNIFF_BYTES)];
+        let detected = detect_bytes(&data);
+        let mime = detected
+            .map(|t| t.mime.to_string())
+            .unwrap_or_else(|| mime_guess::from_path(&inner_path).first_or_octet_stream().to_string());
+        let encoding = detect_encoding(sniff);
+        // NUL bytes mean binary, except in UTF-16 where half the bytes of ASCII text are NUL.
+        let text = (detected.is_none() && (encoding.is_utf16() || !sniff.contains(&0)))
+            .then(|| decode(&data[encoding.bom_len().min(data.len())..], encoding));
+
+        // The protocol picks the Content-Type from the extension.
+        let ext = detected
+            .and_then(|t| t.extensions.first().copied())
+            .filter(|e| !e.is_empty())
+            .map(str::to_string)
+            .or_else(|| Path::new(&inner_path).extension().map(|e| e.to_string_lossy().to_string()))
+            .unwrap_or_default();
+        let preview = write_entry_preview(&app, &path, &inner_path, &ext, &data)?;
+
+        Ok(ArchiveEntryContent {
+            preview_path: preview.to_string_lossy().to_string(),
+            path: inner_path,
+            size,
+            truncated,
+            mime,
+            text,
+        })
+    })
+    .await
+    .map_err(|e| ArchiveError::Other(e.to_string()));
+
+    if let Some(operation_id) = &operation_id {
+        unregister_operation(operation_id);
     }
+    result?
+}
 
-    zip.finish().map_err(|e| e.to_string())?;
-    Ok(())
+/// Extracts everything into a new folder next to the archive. Same safety checks as
+/// `start_extract`, without progress events or password prompts: encrypted archives fail with
+/// `password_required`.
+#[command]
+pub async fn extract_archive(path: String) -> Result<(), ArchiveError> {
+    tokio::task::spawn_blocking(move || {
+        let cancel = AtomicBool::new(false);
+        let summary = extract_blocking(
+            Path::new(&path),
+            &ExtractOptions::default(),
+            &cancel,
+            &mut |_, _| {},
+            &mut no_password,
+        )?;
+        if summary.skipped.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("{} entr(ies) were skipped", summary.skipped.len()).into())
+        }
+    })
+    .await
+    .map_err(|e| ArchiveError::Other(e.to_string()))?
+}
+
+/// Zips `paths` into `dest_path` with default options, as "name (1).zip" when it exists;
+/// `create_archive` is the configurable, cancellable version.
+#[command]
+pub async fn compress_to_zip(paths: Vec<String>, dest_path: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let cancel = AtomicBool::new(false);
+        let options = CreateArchiveOptions { format: Some(ArchiveFormat::Zip), ..Default::default() };
+        let dest = Path::new(&dest_path);
+        let dest = unique_dest_path(dest.parent().unwrap_or(Path::new("")), dest.file_name().unwrap_or_default());
+        create_blocking(&paths, &dest, &options, &cancel, &mut |_, _| {}, &mut no_password)
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
 }
@@ -0,0 +1,117 @@
+use lazy_static::lazy_static;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use zeroize::Zeroizing;
+
+use crate::commands::archive::ArchiveError;
+
+/// Wrong passwords accepted before giving up with `ArchiveError::WrongPassword`.
+const MAX_PASSWORD_ATTEMPTS: u32 = 3;
+/// How often a waiting job checks whether its operation was cancelled.
+const PROMPT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+lazy_static! {
+    /// Jobs blocked on a password prompt, keyed by operation id. Passwords pass through the
+    /// channel once and are never kept here.
+    static ref PENDING_PROMPTS: Mutex<HashMap<String, mpsc::Sender<Option<Zeroizing<String>>>>> =
+        Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Serialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum PasswordPurpose {
+    Extract,
+    Create,
+}
+
+/// Emitted on "archive_password_required"; answer with `provide_archive_password`.
+#[derive(Serialize, Clone)]
+pub struct PasswordPrompt {
+    pub operation_id: String,
+    pub archive: String,
+    pub purpose: PasswordPurpose,
+    /// 1-based; at most [MAX_PASSWORD_ATTEMPTS].
+    pub attempt: u32,
+    /// The previous password didn't open the archive.
+    pub wrong_password: bool,
+}
+
+/// Supplies the password for an attempt (1-based); the flag is set when the last one was wrong.
+pub(crate) type PasswordFn<'a> = dyn FnMut(u32, bool) -> Result<Zeroizing<String>, ArchiveError> + 'a;
+
+/// The [PasswordFn] for callers that can't prompt.
+pub(crate) fn no_password(_attempt: u32, _wrong: bool) -> Result<Zeroizing<String>, ArchiveError> {
+    Err(ArchiveError::PasswordRequired("Archive is password protected".to_string()))
+}
+
+/// Answers a pending "archive_password_required" prompt; `None` dismisses it and cancels the
+/// step that needed the password.
+#[tauri::command]
+pub async fn provide_archive_password(operation_id: String, password: Option<String>) -> Result<(), String> {
+    let sender = PENDING_PROMPTS
+        .lock()
+        .unwrap()
+        .remove(&operation_id)
+        .ok_or("No password prompt is pending for this operation")?;
+    sender
+        .send(password.map(Zeroizing::new))
+        .map_err(|_| "The operation is no longer waiting for a password".to_string())
+}
+
+/// Emits a prompt and blocks until the frontend answers or the operation is cancelled.
+pub(crate) fn prompt_password(
+    app: &AppHandle,
+    operation_id: &str,
+    archive: &str,
+    purpose: PasswordPurpose,
+    cancel: &AtomicBool,
+    attempt: u32,
+    wrong_password: bool,
+) -> Result<Zeroizing<String>, ArchiveError> {
+    let (sender, receiver) = mpsc::channel();
+    PENDING_PROMPTS.lock().unwrap().insert(operation_id.to_string(), sender);
+    let _ = app.emit("archive_password_required", PasswordPrompt {
+        operation_id: operation_id.to_string(),
+        archive: archive.to_string(),
+        purpose,
+        attempt,
+        wrong_password,
+    });
+
+    let answer = loop {
+        match receiver.recv_timeout(PROMPT_POLL_INTERVAL) {
+            Ok(answer) => break answer,
+            Err(RecvTimeoutError::Timeout) if !cancel.load(Ordering::Relaxed) => continue,
+            Err(_) => break None,
+        }
+    };
+    PENDING_PROMPTS.lock().unwrap().remove(operation_id);
+    answer
+        .filter(|password| !password.is_empty())
+        .ok_or_else(|| ArchiveError::Cancelled("Password entry was cancelled".to_string()))
+}
+
+/// Runs `attempt_with` (given `None` until a password is known) and, while it fails for lack
+/// of a password or with a wrong one, asks `ask` for another and retries. The accepted
+/// password stays in `password` so later encrypted entries reuse it without asking.
+pub(crate) fn with_password<T>(
+    password: &mut Option<Zeroizing<String>>,
+    ask: &mut PasswordFn<'_>,
+    mut attempt_with: impl FnMut(Option<&str>) -> Result<T, ArchiveError>,
+) -> Result<T, ArchiveError> {
+    let mut attempt = 0;
+    loop {
+        match attempt_with(password.as_deref().map(String::as_str)) {
+            Err(ArchiveError::PasswordRequired(_) | ArchiveError::WrongPassword(_)) if attempt < MAX_PASSWORD_ATTEMPTS => {
+                attempt += 1;
+                *password = Some(ask(attempt, password.is_some())?);
+            }
+            result => return result,
+        }
+    }
+}
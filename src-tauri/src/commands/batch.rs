@@ -7,10 +7,11 @@ use tauri::{AppHandle, Emitter, Window};
 use walkdir::WalkDir;
 use rayon::prelude::*;
 
-use crate::commands::operation::{register_operation, unregister_operation};
+use crate::commands::operation::{register_operation_with_paths, unregister_operation};
+use crate::utils::exclusion::DeletionGuard;
 
 /// Returns a path under dest_dir that does not exist. If file_name exists, tries "stem (1).ext", "stem (2).ext", etc.
-fn unique_dest_path(dest_dir: &Path, file_name: &std::ffi::OsStr) -> PathBuf {
+pub(crate) fn unique_dest_path(dest_dir: &Path, file_name: &std::ffi::OsStr) -> PathBuf {
     let path = dest_dir.join(file_name);
     if !path.exists() {
         return path;
@@ -47,11 +48,52 @@ pub struct BatchItemCompleted {
     pub path: String,
 }
 
+#[derive(Serialize, Clone)]
+pub struct BatchFailure {
+    pub path: String,
+    /// Why it failed, e.g. the protection rule that refused it.
+    pub reason: String,
+}
+
 /// Emitted when the batch finishes (with or without errors).
 #[derive(Serialize, Clone)]
 pub struct BatchFinished {
     pub operation_id: String,
     pub failed_paths: Vec<String>,
+    /// One entry per failed path, in the same order.
+    pub failures: Vec<BatchFailure>,
+}
+
+/// Emits "batch_finished" and builds the command's error from the first failure.
+fn finish_batch(app: &AppHandle, operation_id: &str, failures: Vec<BatchFailure>, verb: &str) -> Result<(), String> {
+    let _ = app.emit("batch_finished", BatchFinished {
+        operation_id: operation_id.to_string(),
+        failed_paths: failures.iter().map(|f| f.path.clone()).collect(),
+        failures: failures.clone(),
+    });
+    unregister_operation(operation_id);
+
+    match failures.first() {
+        None => Ok(()),
+        Some(first) => Err(format!("{} item(s) failed to {}: {}", failures.len(), verb, first.reason)),
+    }
+}
+
+/// Moves one file or folder: a rename when possible, otherwise copy then trash the source.
+fn move_item(src_path: &Path, target_path: &Path) -> Result<(), String> {
+    if std::fs::rename(src_path, target_path).is_ok() {
+        return Ok(());
+    }
+    if src_path.is_dir() {
+        let mut opts = fs_extra::dir::CopyOptions::new();
+        opts.overwrite = false;
+        fs_extra::dir::copy(src_path, target_path, &opts).map_err(|e| e.to_string())?;
+        trash::delete(src_path).map_err(|e| e.to_string())
+    } else {
+        let mut opts = fs_extra::file::CopyOptions::new();
+        opts.overwrite = false;
+        fs_extra::file::move_file(src_path, target_path, &opts).map(|_| ()).map_err(|e| e.to_string())
+    }
 }
 
 #[tauri::command]
@@ -68,16 +110,17 @@ pub async fn delete_items(
     paths: Vec<String>,
 ) -> Result<(), String> {
     let total_items = paths.len();
-    let cancel_flag = register_operation(operation_id.clone());
+    let cancel_flag = register_operation_with_paths(operation_id.clone(), &paths);
     let processed_count = std::sync::atomic::AtomicUsize::new(0);
     let last_emit = std::sync::Arc::new(std::sync::Mutex::new(std::time::Instant::now()));
-    let failed_paths = std::sync::Arc::new(std::sync::Mutex::new(Vec::<String>::new()));
+    let failures = std::sync::Arc::new(std::sync::Mutex::new(Vec::<BatchFailure>::new()));
 
     let op_id = operation_id.clone();
     let app_clone = app.clone();
-    let failed_clone = failed_paths.clone();
+    let failed_clone = failures.clone();
 
     tokio::task::spawn_blocking(move || {
+        let guard = DeletionGuard::new();
         paths.par_iter().for_each(|path| {
             if cancel_flag.load(Ordering::Relaxed) {
                 return;
@@ -100,29 +143,23 @@ pub async fn delete_items(
             }
             drop(last_emit_lock);
 
-            if trash::delete(&p).is_ok() {
-                let _ = app_clone.emit("batch_item_completed", BatchItemCompleted {
-                    operation_id: op_id.clone(),
-                    path: path.clone(),
-                });
-            } else {
-                failed_clone.lock().unwrap().push(path.clone());
+            match guard.check(&p).and_then(|()| trash::delete(&p).map_err(|e| e.to_string())) {
+                Ok(()) => {
+                    let _ = app_clone.emit("batch_item_completed", BatchItemCompleted {
+                        operation_id: op_id.clone(),
+                        path: path.clone(),
+                    });
+                }
+                Err(reason) => failed_clone.lock().unwrap().push(BatchFailure { path: path.clone(), reason }),
             }
         });
-    }).await.map_err(|e| e.to_string())?;
+    }).await.map_err(|e| {
+        unregister_operation(&operation_id);
+        e.to_string()
+    })?;
 
-    let failed = failed_paths.lock().unwrap().clone();
-    let _ = app.emit("batch_finished", BatchFinished {
-        operation_id: operation_id.clone(),
-        failed_paths: failed.clone(),
-    });
-    unregister_operation(&operation_id);
-
-    if failed.is_empty() {
-        Ok(())
-    } else {
-        Err(format!("{} item(s) failed to delete", failed.len()))
-    }
+    let failures = failures.lock().unwrap().clone();
+    finish_batch(&app, &operation_id, failures, "delete")
 }
 
 #[tauri::command]
@@ -133,7 +170,7 @@ pub async fn batch_copy(
     destination_dir: String,
 ) -> Result<(), String> {
     let total_items = sources.len();
-    let cancel_flag = register_operation(operation_id.clone());
+    let cancel_flag = register_operation_with_paths(operation_id.clone(), sources.iter().chain([&destination_dir]));
     let dest_path = PathBuf::from(&destination_dir);
 
     let processed_count = std::sync::atomic::AtomicUsize::new(0);
@@ -177,7 +214,10 @@ pub async fn batch_copy(
             }
             Ok(())
         })
-    }).await.map_err(|e| e.to_string())?;
+    }).await.map_err(|e| {
+        unregister_operation(&operation_id);
+        e.to_string()
+    })?;
 
     unregister_operation(&operation_id);
     if res.is_ok() {
@@ -194,18 +234,19 @@ pub async fn batch_move(
     destination_dir: String,
 ) -> Result<(), String> {
     let total_items = sources.len();
-    let cancel_flag = register_operation(operation_id.clone());
+    let cancel_flag = register_operation_with_paths(operation_id.clone(), sources.iter().chain([&destination_dir]));
     let dest_path = PathBuf::from(&destination_dir);
 
     let processed_count = std::sync::atomic::AtomicUsize::new(0);
     let last_emit = std::sync::Arc::new(std::sync::Mutex::new(std::time::Instant::now()));
-    let failed_paths = std::sync::Arc::new(std::sync::Mutex::new(Vec::<String>::new()));
+    let failures = std::sync::Arc::new(std::sync::Mutex::new(Vec::<BatchFailure>::new()));
 
     let op_id = operation_id.clone();
     let app_clone = app.clone();
-    let failed_clone = failed_paths.clone();
+    let failed_clone = failures.clone();
 
     tokio::task::spawn_blocking(move || {
+        let guard = DeletionGuard::new();
         sources.par_iter().for_each(|src| {
             if cancel_flag.load(Ordering::Relaxed) {
                 return;
@@ -230,51 +271,23 @@ pub async fn batch_move(
             }
             drop(last_emit_lock);
 
-            let ok = if src_path.is_dir() {
-                if std::fs::rename(&src_path, &target_path).is_ok() {
-                    true
-                } else {
-                    let mut opts = fs_extra::dir::CopyOptions::new();
-                    opts.overwrite = false;
-                    if fs_extra::dir::copy(&src_path, &target_path, &opts).is_ok() {
-                        trash::delete(&src_path).is_ok()
-                    } else {
-                        false
-                    }
-                }
-            } else {
-                if std::fs::rename(&src_path, &target_path).is_ok() {
-                    true
-                } else {
-                    let mut opts = fs_extra::file::CopyOptions::new();
-                    opts.overwrite = false;
-                    fs_extra::file::move_file(&src_path, &target_path, &opts).is_ok()
+            match guard.check(&src_path).and_then(|()| move_item(&src_path, &target_path)) {
+                Ok(()) => {
+                    let _ = app_clone.emit("batch_item_completed", BatchItemCompleted {
+                        operation_id: op_id.clone(),
+                        path: src.clone(),
+                    });
                 }
-            };
-
-            if ok {
-                let _ = app_clone.emit("batch_item_completed", BatchItemCompleted {
-                    operation_id: op_id.clone(),
-                    path: src.clone(),
-                });
-            } else {
-                failed_clone.lock().unwrap().push(src.clone());
+                Err(reason) => failed_clone.lock().unwrap().push(BatchFailure { path: src.clone(), reason }),
             }
         });
-    }).await.map_err(|e| e.to_string())?;
+    }).await.map_err(|e| {
+        unregister_operation(&operation_id);
+        e.to_string()
+    })?;
 
-    let failed = failed_paths.lock().unwrap().clone();
-    let _ = app.emit("batch_finished", BatchFinished {
-        operation_id: operation_id.clone(),
-        failed_paths: failed.clone(),
-    });
-    unregister_operation(&operation_id);
-
-    if failed.is_empty() {
-        Ok(())
-    } else {
-        Err(format!("{} item(s) failed to move", failed.len()))
-    }
+    let failures = failures.lock().unwrap().clone();
+    finish_batch(&app, &operation_id, failures, "move")
 }
 
 #[tauri::command]
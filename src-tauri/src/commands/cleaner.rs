@@ -4,6 +4,8 @@ use serde::{Deserialize, Serialize};
 use tauri::{Emitter, Runtime};
 use std::time::Instant;
 use super::settings::ConfigSection;
+use crate::utils::mountinfo::MountFilter;
+use crate::utils::exclusion::{exclusions, DeletionGuard, Exclusions};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CleanProgressEvent {
@@ -14,11 +16,106 @@ pub struct CleanProgressEvent {
     pub elapsed_ms: u64,
 }
 
+/// Why a path was reported by the cleaner scan.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CleanReason {
+    EmptyFolder,
+    /// Folder holds nothing but OS junk (`.DS_Store`, `Thumbs.db`, ...).
+    JunkOnlyFolder,
+    ZeroByteFile,
+    BrokenSymlink,
+    /// Symlink that cannot be resolved (ELOOP) or points back at one of its own ancestors.
+    SymlinkLoop,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct EmptyFolder {
+pub struct CleanFinding {
     pub path: String,
     pub name: String,
     pub parent_path: String,
+    pub is_dir: bool,
+    pub reason: CleanReason,
+}
+
+/// Which kinds of findings `find_empty_folders` should report.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct CleanScanOptions {
+    pub empty_folders: bool,
+    pub zero_byte_files: bool,
+    pub broken_symlinks: bool,
+    pub symlink_loops: bool,
+    /// Count folders containing only junk files as empty.
+    pub junk_as_empty: bool,
+}
+
+impl Default for CleanScanOptions {
+    fn default() -> Self {
+        Self {
+            empty_folders: true,
+            zero_byte_files: false,
+            broken_symlinks: false,
+            symlink_loops: false,
+            junk_as_empty: false,
+        }
+    }
+}
+
+/// OS-generated clutter that does not make a folder worth keeping.
+pub(crate) fn is_junk_name(name: &str) -> bool {
+    name == ".DS_Store"
+        || name == "__MACOSX"
+        || name.eq_ignore_ascii_case("thumbs.db")
+        || name.eq_ignore_ascii_case("desktop.ini")
+}
+
+#[cfg(unix)]
+fn is_loop_error(e: &std::io::Error) -> bool {
+    e.raw_os_error() == Some(libc::ELOOP)
+}
+
+#[cfg(not(unix))]
+fn is_loop_error(_e: &std::io::Error) -> bool {
+    false
+}
+
+enum LinkState {
+    Ok,
+    Broken,
+    Loop,
+}
+
+fn classify_symlink(link: &Path) -> LinkState {
+    match fs::metadata(link) {
+        Ok(meta) => {
+            // A directory link that resolves to one of its own ancestors makes walkers cycle.
+            if meta.is_dir() {
+                if let (Ok(target), Some(Ok(parent))) = (
+                    fs::canonicalize(link),
+                    link.parent().map(fs::canonicalize),
+                ) {
+                    if parent.starts_with(&target) {
+                        return LinkState::Loop;
+                    }
+                }
+            }
+            LinkState::Ok
+        }
+        Err(e) if is_loop_error(&e) => LinkState::Loop,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => LinkState::Broken,
+        Err(_) => LinkState::Ok,
+    }
+}
+
+fn finding(path: &Path, is_dir: bool, reason: CleanReason) -> CleanFinding {
+    CleanFinding {
+        path: path.to_string_lossy().to_string(),
+        name: path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+        parent_path: path.parent().map(|p| p.to_string_lossy().to_string()).unwrap_or_else(|| "/".to_string()),
+        is_dir,
+        reason,
+    }
 }
 
 #[tauri::command]
@@ -26,20 +123,30 @@ pub async fn find_empty_folders<R: Runtime>(
     app: tauri::AppHandle<R>,
     paths: Vec<String>,
     settings: ConfigSection,
-) -> Result<Vec<EmptyFolder>, String> {
+    options: Option<CleanScanOptions>,
+) -> Result<Vec<CleanFinding>, String> {
     let start_time = Instant::now();
+    let options = options.unwrap_or_default();
     let mut empty_folders = Vec::new();
     let mut scanned_count = 0;
+    let walk = CleanWalk {
+        settings: &settings,
+        options: &options,
+        mounts: MountFilter::new(&settings),
+        rules: exclusions(),
+        app: &app,
+        start_time,
+    };
 
     for start_path in paths {
         let path = PathBuf::from(&start_path);
-        if !path.exists() || !path.is_dir() {
+        if !path.exists() || !path.is_dir() || walk.rules.excluded(&path).is_some() {
             continue;
         }
 
         // We use a simple recursive function to find empty folders.
         // A folder is empty if it contains no files and all its subfolders are also empty.
-        find_recursive(&path, &settings, &mut empty_folders, &mut scanned_count, &app, start_time).await;
+        find_recursive(&path, &walk, &mut empty_folders, &mut scanned_count).await;
     }
 
     let _ = app.emit("clean-progress", CleanProgressEvent {
@@ -53,14 +160,23 @@ pub async fn find_empty_folders<R: Runtime>(
     Ok(empty_folders)
 }
 
+/// What every level of a clean scan shares.
+struct CleanWalk<'a, R: Runtime> {
+    settings: &'a ConfigSection,
+    options: &'a CleanScanOptions,
+    mounts: MountFilter,
+    rules: std::sync::Arc<Exclusions>,
+    app: &'a tauri::AppHandle<R>,
+    start_time: Instant,
+}
+
 async fn find_recursive<R: Runtime>(
     path: &Path,
-    settings: &ConfigSection,
-    results: &mut Vec<EmptyFolder>,
+    walk: &CleanWalk<'_, R>,
+    results: &mut Vec<CleanFinding>,
     scanned_count: &mut usize,
-    app: &tauri::AppHandle<R>,
-    start_time: Instant,
 ) -> bool {
+    let CleanWalk { settings, options, app, start_time, .. } = *walk;
     *scanned_count += 1;
     
     // Periodic progress update
@@ -80,6 +196,7 @@ async fn find_recursive<R: Runtime>(
     };
 
     let mut is_empty = true;
+    let mut has_junk = false;
     for entry in entries {
         let entry = match entry {
             Ok(e) => e,
@@ -92,14 +209,22 @@ async fn find_recursive<R: Runtime>(
         if name == "__MACOSX" {
             continue;
         }
+
+        if options.junk_as_empty && is_junk_name(&name) {
+            has_junk = true;
+            continue;
+        }
         
         // Respect hidden/system toggles
         if !settings.show_hidden_files && name.starts_with('.') {
             continue;
         }
         
-        // System files check can be more complex, but we'll stick to basic visibility for now
-        // to match the requested "all" (hidden/system) logic.
+        // Excluded entries aren't inspected and count as content, so their parent is kept
+        if walk.rules.excluded(&entry.path()).is_some() {
+            is_empty = false;
+            continue;
+        }
 
         let file_type = match entry.file_type() {
             Ok(ft) => ft,
@@ -108,92 +233,233 @@ async fn find_recursive<R: Runtime>(
 
         if file_type.is_file() {
             is_empty = false;
+            if options.zero_byte_files && entry.metadata().map(|m| m.len() == 0).unwrap_or(false) {
+                results.push(finding(&entry.path(), false, CleanReason::ZeroByteFile));
+            }
         } else if file_type.is_dir() {
-            let child_is_empty = Box::pin(find_recursive(&entry.path(), settings, results, scanned_count, app, start_time)).await;
+            // A mount the walk may not enter counts as content, so its parent is never removed
+            if !walk.mounts.allows(&entry.path()) {
+                is_empty = false;
+                continue;
+            }
+            let child_is_empty = Box::pin(find_recursive(&entry.path(), walk, results, scanned_count)).await;
             if !child_is_empty {
                 is_empty = false;
             }
         } else {
-            // Symlinks etc. - treat as non-empty or skip?
-            // Usually we don't want to delete folders containing symlinks.
+            // Folders containing symlinks (even dead ones) are never reported as empty;
+            // the links themselves are reported when the matching mode is on.
             is_empty = false;
+            if file_type.is_symlink() && (options.broken_symlinks || options.symlink_loops) {
+                match classify_symlink(&entry.path()) {
+                    LinkState::Broken if options.broken_symlinks => {
+                        results.push(finding(&entry.path(), false, CleanReason::BrokenSymlink));
+                    }
+                    LinkState::Loop if options.symlink_loops => {
+                        results.push(finding(&entry.path(), false, CleanReason::SymlinkLoop));
+                    }
+                    _ => {}
+                }
+            }
         }
     }
 
-    if is_empty {
-        results.push(EmptyFolder {
-            path: path.to_string_lossy().to_string(),
-            name: path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
-            parent_path: path.parent().map(|p| p.to_string_lossy().to_string()).unwrap_or_else(|| "/".to_string()),
-        });
+    if is_empty && options.empty_folders {
+        let reason = if has_junk { CleanReason::JunkOnlyFolder } else { CleanReason::EmptyFolder };
+        results.push(finding(path, true, reason));
     }
 
     is_empty
 }
 
+/// Options for `delete_empty_folders`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct DeleteEmptyOptions {
+    /// Folders the scan started from. Parent cleanup never climbs to or above these;
+    /// when empty, no parent cleanup happens at all.
+    pub roots: Vec<String>,
+    /// Remove permanently instead of moving to the trash.
+    pub permanent: bool,
+    /// Same meaning as [CleanScanOptions::junk_as_empty].
+    pub junk_as_empty: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeleteStatus {
+    Deleted,
+    /// The folder or file gained content, or the link resolves again, since the scan;
+    /// left untouched.
+    NotEmpty,
+    /// Not a folder, file or symlink (a device, socket or pipe); never removed.
+    Unsupported,
+    Protected,
+    Missing,
+    Failed,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeleteOutcome {
+    pub path: String,
+    pub status: DeleteStatus,
+    pub error: Option<String>,
+    /// Ancestors that became empty and were removed as well.
+    pub removed_parents: Vec<String>,
+}
+
+/// Removes cleaner findings: empty folders, zero-byte files and dead or looping symlinks.
+/// Each one is re-checked first, so anything that changed since the scan is kept.
 #[tauri::command]
-pub async fn delete_empty_folders(paths: Vec<String>) -> Result<(), String> {
-    for path_str in paths {
-        let path = PathBuf::from(&path_str);
-        if !path.exists() || !path.is_dir() {
-            continue;
+pub async fn delete_empty_folders(
+    paths: Vec<String>,
+    options: Option<DeleteEmptyOptions>,
+) -> Result<Vec<DeleteOutcome>, String> {
+    let options = options.unwrap_or_default();
+
+    tokio::task::spawn_blocking(move || {
+        let guard = DeletionGuard::new();
+        let roots: Vec<PathBuf> = options.roots.iter()
+            .map(|r| fs::canonicalize(r).unwrap_or_else(|_| PathBuf::from(r)))
+            .collect();
+
+        paths.into_iter()
+            .map(|path_str| delete_one(path_str, &options, &roots, &guard))
+            .collect()
+    }).await.map_err(|e| e.to_string())
+}
+
+fn delete_one(path_str: String, options: &DeleteEmptyOptions, roots: &[PathBuf], guard: &DeletionGuard) -> DeleteOutcome {
+    let mut outcome = DeleteOutcome {
+        path: path_str.clone(),
+        status: DeleteStatus::Deleted,
+        error: None,
+        removed_parents: Vec::new(),
+    };
+    let path = PathBuf::from(&path_str);
+
+    // symlink_metadata: never follow a link and delete its target folder.
+    let meta = match fs::symlink_metadata(&path) {
+        Ok(meta) => meta,
+        Err(_) => {
+            outcome.status = DeleteStatus::Missing;
+            return outcome;
         }
+    };
+    let file_type = meta.file_type();
+    if !file_type.is_dir() && !file_type.is_file() && !file_type.is_symlink() {
+        outcome.status = DeleteStatus::Unsupported;
+        outcome.error = Some("Not a folder, file or symlink".to_string());
+        return outcome;
+    }
 
-        // Delete the directory and any allowed hidden contents
-        if let Err(e) = fs::remove_dir_all(&path) {
-            // Fallback for tricky macOS folders or permissions
-            #[cfg(unix)]
-            let fallback_status = std::process::Command::new("rm")
-                .arg("-rf")
-                .arg(&path)
-                .status();
-
-            #[cfg(windows)]
-            let fallback_status = std::process::Command::new("cmd")
-                .arg("/c")
-                .arg("rmdir")
-                .arg("/s")
-                .arg("/q")
-                .arg(&path)
-                .status();
-
-            // If fallback failed or the directory still exists, return the original error
-            if fallback_status.is_err() || !fallback_status.unwrap().success() || path.exists() {
-                return Err(format!("Failed to delete {}: {}", path_str, e));
-            }
+    if guard.rule_for(&path).is_some() {
+        outcome.status = DeleteStatus::Protected;
+        return outcome;
+    }
+
+    // Re-verify: the scan result may be stale, and hidden files count as content here.
+    let changed = if file_type.is_dir() {
+        (!verify_empty(&path, options.junk_as_empty)).then_some("Folder is no longer empty")
+    } else if file_type.is_file() {
+        (meta.len() > 0).then_some("File is no longer empty")
+    } else {
+        matches!(classify_symlink(&path), LinkState::Ok).then_some("Link resolves again")
+    };
+    if let Some(reason) = changed {
+        outcome.status = DeleteStatus::NotEmpty;
+        outcome.error = Some(reason.to_string());
+        return outcome;
+    }
+
+    let removed = if file_type.is_dir() {
+        remove_empty_dir(&path, options)
+    } else {
+        remove_entry(&path, options)
+    };
+    if let Err(e) = removed {
+        outcome.status = DeleteStatus::Failed;
+        outcome.error = Some(e);
+        return outcome;
+    }
+
+    // Parent cleanup, bounded by the scan roots.
+    let canonical_parent = path.parent().and_then(|p| fs::canonicalize(p).ok());
+    let mut current = canonical_parent.as_deref();
+    while let Some(parent) = current {
+        let inside_root = roots.iter().any(|r| parent.starts_with(r) && parent != r.as_path());
+        if !inside_root || guard.rule_for(parent).is_some() || !verify_empty(parent, options.junk_as_empty) {
+            break;
+        }
+        if remove_empty_dir(parent, options).is_err() {
+            break;
         }
+        outcome.removed_parents.push(parent.to_string_lossy().to_string());
+        current = parent.parent();
+    }
 
-        // Recursive parent cleanup
-        let mut current_parent = path.parent();
-        while let Some(parent) = current_parent {
-            // Check if parent is now empty (or only contains hidden/__MACOSX files)
-            if is_dir_empty(parent) {
-                if let Err(_) = fs::remove_dir_all(parent) {
-                    // Stop if we hit permissions or root
-                    break;
+    outcome
+}
+
+/// A folder is empty when it holds only `__MACOSX`, junk files (if enabled) and
+/// subfolders that are themselves empty. Dotfiles and symlinks count as content.
+fn verify_empty(path: &Path, junk_as_empty: bool) -> bool {
+    let entries = match fs::read_dir(path) {
+        Ok(e) => e,
+        Err(_) => return false,
+    };
+    for entry in entries {
+        let entry = match entry {
+            Ok(e) => e,
+            Err(_) => return false,
+        };
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name == "__MACOSX" || (junk_as_empty && is_junk_name(&name)) {
+            continue;
+        }
+        match entry.file_type() {
+            Ok(ft) if ft.is_dir() => {
+                if !verify_empty(&entry.path(), junk_as_empty) {
+                    return false;
                 }
-                current_parent = parent.parent();
-            } else {
-                break;
             }
+            _ => return false,
         }
     }
-    Ok(())
+    true
 }
 
-fn is_dir_empty(path: &Path) -> bool {
-    match fs::read_dir(path) {
-        Ok(entries) => {
-            for entry in entries {
-                if let Ok(e) = entry {
-                    let name = e.file_name().to_string_lossy().to_string();
-                    if name != "__MACOSX" && !name.starts_with('.') {
-                        return false;
-                    }
-                }
-            }
-            true
-        },
-        Err(_) => false,
+fn remove_empty_dir(path: &Path, options: &DeleteEmptyOptions) -> Result<(), String> {
+    if options.permanent {
+        remove_bottom_up(path, options.junk_as_empty).map_err(|e| format!("Failed to delete {}: {}", path.display(), e))
+    } else {
+        trash::delete(path).map_err(|e| format!("Failed to move {} to trash: {}", path.display(), e))
+    }
+}
+
+/// Removes a zero-byte file or a symlink (never its target).
+fn remove_entry(path: &Path, options: &DeleteEmptyOptions) -> Result<(), String> {
+    if options.permanent {
+        fs::remove_file(path).map_err(|e| format!("Failed to delete {}: {}", path.display(), e))
+    } else {
+        trash::delete(path).map_err(|e| format!("Failed to move {} to trash: {}", path.display(), e))
+    }
+}
+
+/// Removes only what [verify_empty] allowed, then the folder itself with `remove_dir`,
+/// so anything created after verification makes the delete fail instead of vanishing.
+fn remove_bottom_up(path: &Path, junk_as_empty: bool) -> std::io::Result<()> {
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let ft = entry.file_type()?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if ft.is_dir() && name == "__MACOSX" {
+            fs::remove_dir_all(entry.path())?;
+        } else if ft.is_dir() {
+            remove_bottom_up(&entry.path(), junk_as_empty)?;
+        } else if ft.is_file() && junk_as_empty && is_junk_name(&name) {
+            fs::remove_file(entry.path())?;
+        }
     }
+    fs::remove_dir(path)
 }
@@ -0,0 +1,653 @@
+use chrono::{Datelike, Timelike};
+use flate2::write::GzEncoder;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Instant, SystemTime};
+use tauri::{AppHandle, Emitter};
+use walkdir::WalkDir;
+use xz2::write::XzEncoder;
+use zeroize::Zeroizing;
+use zip::write::SimpleFileOptions;
+use zip::{AesMode, CompressionMethod, ZipWriter};
+
+use crate::commands::archive::ArchiveError;
+use crate::commands::archive_password::{prompt_password, PasswordFn, PasswordPurpose};
+use crate::commands::extract::SkippedEntry;
+use crate::commands::operation::{register_operation_with_paths, unregister_operation};
+
+const COPY_CHUNK_BYTES: usize = 1024 * 1024; // 1 MB
+const PROGRESS_INTERVAL_MS: u128 = 100;
+/// Smallest volume size accepted, so a typo can't produce thousands of files.
+const MIN_VOLUME_BYTES: u64 = 64 * 1024; // 64 KB
+/// Zip entries at least this big need ZIP64 headers.
+const ZIP64_THRESHOLD: u64 = 0xFFFF_FFFF;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveFormat {
+    Zip,
+    Tar,
+    TarGz,
+    TarXz,
+    TarZst,
+}
+
+impl ArchiveFormat {
+    fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+        [
+            (".zip", Self::Zip),
+            (".tar", Self::Tar),
+            (".tar.gz", Self::TarGz),
+            (".tgz", Self::TarGz),
+            (".tar.xz", Self::TarXz),
+            (".txz", Self::TarXz),
+            (".tar.zst", Self::TarZst),
+            (".tzst", Self::TarZst),
+        ]
+        .into_iter()
+        .find(|(ext, _)| name.ends_with(ext))
+        .map(|(_, format)| format)
+    }
+}
+
+/// Per-entry compression for zip output.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ZipMethod {
+    Store,
+    #[default]
+    Deflate,
+    Zstd,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct CreateArchiveOptions {
+    /// Inferred from the destination's extension when omitted, else zip.
+    pub format: Option<ArchiveFormat>,
+    pub zip_method: ZipMethod,
+    /// Compression level; the codec's default when omitted. Deflate, gzip and xz take 0-9,
+    /// zstd 1-22.
+    pub level: Option<i64>,
+    /// Globs matched against each entry's path inside the archive and against its name, so
+    /// "*.log" and "node_modules" both work at any depth.
+    pub exclude: Vec<String>,
+    /// Split the output into "name.001", "name.002", ... of at most this many bytes. The parts
+    /// concatenated give the complete archive; 7-Zip opens the set from ".001".
+    pub volume_size: Option<u64>,
+    /// Encrypt zip file contents with AES-256. The password is asked for through
+    /// "archive_password_required" once the job starts.
+    pub encrypt: bool,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ArchiveProgress {
+    pub operation_id: String,
+    pub current_item: String,
+    pub processed_items: usize,
+    pub total_items: usize,
+    /// Source bytes read so far.
+    pub processed_bytes: u64,
+    pub total_bytes: u64,
+    pub progress: f64,
+}
+
+/// Returned by the job and emitted on "archive_finished".
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct CreateArchiveSummary {
+    pub operation_id: String,
+    /// Files written: the archive itself, or its volumes in order. Empty when cancelled.
+    pub volumes: Vec<String>,
+    pub added: usize,
+    /// Sources that couldn't be read and were left out.
+    pub skipped: Vec<SkippedEntry>,
+    pub cancelled: bool,
+}
+
+/// Packs `paths` into a new archive at `dest_path`, streaming file data so memory use stays
+/// flat. Emits "archive_progress" and honours `cancel_operation(operation_id)`; a cancelled
+/// or failed job removes its partial output. Refuses to overwrite an existing `dest_path`
+/// (or its first volume).
+#[tauri::command]
+pub async fn create_archive(
+    app: AppHandle,
+    operation_id: String,
+    paths: Vec<String>,
+    dest_path: String,
+    options: Option<CreateArchiveOptions>,
+) -> Result<CreateArchiveSummary, ArchiveError> {
+    let options = options.unwrap_or_default();
+    let cancel_flag = register_operation_with_paths(operation_id.clone(), paths.iter().chain([&dest_path]));
+
+    let op_id = operation_id.clone();
+    let app_clone = app.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let mut last_emit = Instant::now();
+        let mut emit = |p: &ProgressState, force: bool| {
+            if force || last_emit.elapsed().as_millis() > PROGRESS_INTERVAL_MS {
+                let _ = app_clone.emit("archive_progress", ArchiveProgress {
+                    operation_id: op_id.clone(),
+                    current_item: p.current_item.clone(),
+                    processed_items: p.processed_items,
+                    total_items: p.total_items,
+                    processed_bytes: p.processed_bytes,
+                    total_bytes: p.total_bytes,
+                    progress: if p.total_bytes == 0 {
+                        100.0
+                    } else {
+                        (p.processed_bytes as f64 / p.total_bytes as f64) * 100.0
+                    },
+                });
+                last_emit = Instant::now();
+            }
+        };
+        let mut ask = |attempt: u32, wrong: bool| {
+            prompt_password(&app_clone, &op_id, &dest_path, PasswordPurpose::Create, &cancel_flag, attempt, wrong)
+        };
+        create_blocking(&paths, Path::new(&dest_path), &options, &cancel_flag, &mut emit, &mut ask)
+    })
+    .await
+    .map_err(|e| ArchiveError::Other(e.to_string()));
+
+    unregister_operation(&operation_id);
+    let mut summary = result??;
+    summary.operation_id = operation_id;
+    let _ = app.emit("archive_finished", summary.clone());
+    Ok(summary)
+}
+
+pub(crate) struct ProgressState {
+    pub current_item: String,
+    pub processed_items: usize,
+    pub total_items: usize,
+    pub processed_bytes: u64,
+    pub total_bytes: u64,
+}
+
+type ProgressFn<'a> = dyn FnMut(&ProgressState, bool) + 'a;
+
+pub(crate) fn create_blocking(
+    paths: &[String],
+    dest: &Path,
+    options: &CreateArchiveOptions,
+    cancel: &AtomicBool,
+    progress: &mut ProgressFn<'_>,
+    ask: &mut PasswordFn<'_>,
+) -> Result<CreateArchiveSummary, ArchiveError> {
+    if paths.is_empty() {
+        return Err("No files selected for compression".into());
+    }
+    let format = options.format.or_else(|| ArchiveFormat::from_path(dest)).unwrap_or(ArchiveFormat::Zip);
+    if options.encrypt && format != ArchiveFormat::Zip {
+        return Err(ArchiveError::Unsupported("Only zip archives can be encrypted".to_string()));
+    }
+    let exclude = build_exclude_set(&options.exclude)?;
+    let volume_size = options.volume_size.map(|size| size.max(MIN_VOLUME_BYTES));
+    let first_volume = volume_path(dest, volume_size, 0);
+    if first_volume.symlink_metadata().is_ok() {
+        return Err(format!("{} already exists", first_volume.display()).into());
+    }
+
+    let mut summary = CreateArchiveSummary::default();
+    let password = match options.encrypt.then(|| ask(1, false)).transpose() {
+        Ok(password) => password,
+        Err(ArchiveError::Cancelled(_)) => {
+            summary.cancelled = true;
+            return Ok(summary);
+        }
+        Err(e) => return Err(e),
+    };
+    let sources = collect_sources(paths, dest, &exclude, &mut summary.skipped);
+    let mut reporter = Reporter {
+        state: ProgressState {
+            current_item: String::new(),
+            processed_items: 0,
+            total_items: sources.len(),
+            processed_bytes: 0,
+            total_bytes: sources.iter().filter(|s| s.kind == SourceKind::File).map(|s| s.meta.len()).sum(),
+        },
+        cancel,
+        progress,
+    };
+
+    // Volumes are only ever created, never opened for overwrite, so cleanup only touches these.
+    let created = Arc::new(AtomicUsize::new(0));
+    let out = BufWriter::with_capacity(COPY_CHUNK_BYTES, VolumeWriter::new(dest, volume_size, created.clone()));
+    let written = match format {
+        ArchiveFormat::Zip => write_zip(out, &sources, options, password.as_ref(), &mut reporter, &mut summary),
+        format => write_tar(out, format, &sources, options.level, &mut reporter, &mut summary),
+    };
+    let finished = written.and_then(|out| out.into_inner().map_err(|e| e.into_error().to_string()));
+
+    if cancel.load(Ordering::Relaxed) {
+        drop(finished);
+        remove_volumes(dest, volume_size, created.load(Ordering::Relaxed));
+        summary.cancelled = true;
+        return Ok(summary);
+    }
+    match finished.and_then(|writer| writer.finish().map_err(|e| e.to_string())) {
+        Ok(volumes) => {
+            summary.volumes = volumes.iter().map(|v| v.to_string_lossy().to_string()).collect();
+            (reporter.progress)(&reporter.state, true);
+            Ok(summary)
+        }
+        Err(e) => {
+            remove_volumes(dest, volume_size, created.load(Ordering::Relaxed));
+            Err(e.into())
+        }
+    }
+}
+
+fn build_exclude_set(patterns: &[String]) -> Result<GlobSet, String> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+        let glob = Glob::new(pattern).map_err(|e| format!("Invalid exclude pattern '{}': {}", pattern, e))?;
+        builder.add(glob);
+    }
+    builder.build().map_err(|e| e.to_string())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SourceKind {
+    Dir,
+    File,
+    Symlink(PathBuf),
+}
+
+struct Source {
+    path: PathBuf,
+    /// `/`-separated path inside the archive.
+    name: String,
+    kind: SourceKind,
+    /// Metadata of the entry itself, not of a symlink's target.
+    meta: fs::Metadata,
+}
+
+/// Walks every selected path without following symlinks. Each selection keeps its own name as
+/// the top-level entry, like `compress_to_zip` always did.
+fn collect_sources(paths: &[String], dest: &Path, exclude: &GlobSet, skipped: &mut Vec<SkippedEntry>) -> Vec<Source> {
+    let excluded = |name: &str| {
+        let file_name = name.rsplit('/').next().unwrap_or(name);
+        exclude.is_match(name) || exclude.is_match(file_name)
+    };
+    let mut sources = Vec::new();
+    for root in paths.iter().map(Path::new) {
+        let base = root.parent().unwrap_or(Path::new(""));
+        let walker = WalkDir::new(root).follow_links(false).into_iter().filter_entry(|entry| {
+            let name = archive_name(base, entry.path());
+            !excluded(&name) && !is_output(dest, entry.path())
+        });
+        for entry in walker {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    let path = e.path().unwrap_or(root).to_string_lossy().to_string();
+                    skipped.push(SkippedEntry { path, reason: e.to_string() });
+                    continue;
+                }
+            };
+            let meta = match entry.path().symlink_metadata() {
+                Ok(meta) => meta,
+                Err(e) => {
+                    skipped.push(SkippedEntry { path: entry.path().to_string_lossy().to_string(), reason: e.to_string() });
+                    continue;
+                }
+            };
+            let kind = if meta.file_type().is_symlink() {
+                match fs::read_link(entry.path()) {
+                    Ok(target) => SourceKind::Symlink(target),
+                    Err(e) => {
+                        skipped.push(SkippedEntry { path: entry.path().to_string_lossy().to_string(), reason: e.to_string() });
+                        continue;
+                    }
+                }
+            } else if meta.is_dir() {
+                SourceKind::Dir
+            } else if meta.is_file() {
+                SourceKind::File
+            } else {
+                // Sockets, FIFOs and devices have no content to archive.
+                continue;
+            };
+            sources.push(Source { name: archive_name(base, entry.path()), path: entry.into_path(), kind, meta });
+        }
+    }
+    sources
+}
+
+fn archive_name(base: &Path, path: &Path) -> String {
+    path.strip_prefix(base).unwrap_or(path).to_string_lossy().replace('\\', "/")
+}
+
+/// True for the archive being written (or one of its volumes), when it sits inside a source.
+fn is_output(dest: &Path, path: &Path) -> bool {
+    if path == dest {
+        return true;
+    }
+    let (Some(name), Some(dest_name)) = (path.file_name(), dest.file_name()) else { return false };
+    let (name, dest_name) = (name.to_string_lossy(), dest_name.to_string_lossy());
+    path.parent() == dest.parent()
+        && name
+            .strip_prefix(dest_name.as_ref())
+            .and_then(|rest| rest.strip_prefix('.'))
+            .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Permission bits to store; `None` lets the writer pick its default.
+#[cfg(unix)]
+fn entry_mode(meta: &fs::Metadata) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+    Some(meta.permissions().mode() & 0o7777)
+}
+
+#[cfg(not(unix))]
+fn entry_mode(_meta: &fs::Metadata) -> Option<u32> {
+    None
+}
+
+/// Zip stores local wall-clock time; dates before 1980 can't be represented.
+fn zip_datetime(modified: SystemTime) -> Option<zip::DateTime> {
+    let local = chrono::DateTime::<chrono::Local>::from(modified);
+    zip::DateTime::from_date_and_time(
+        u16::try_from(local.year()).ok()?,
+        local.month() as u8,
+        local.day() as u8,
+        local.hour() as u8,
+        local.minute() as u8,
+        local.second() as u8,
+    )
+    .ok()
+}
+
+struct Reporter<'a, 'p> {
+    state: ProgressState,
+    cancel: &'a AtomicBool,
+    progress: &'a mut ProgressFn<'p>,
+}
+
+impl Reporter<'_, '_> {
+    fn start_item(&mut self, name: &str) -> bool {
+        self.state.current_item = name.to_string();
+        !self.cancel.load(Ordering::Relaxed)
+    }
+
+    fn finish_item(&mut self, summary: &mut CreateArchiveSummary) {
+        self.state.processed_items += 1;
+        summary.added += 1;
+        (self.progress)(&self.state, false);
+    }
+}
+
+/// Counts source bytes as the archive writer pulls them, and stops the copy on cancel.
+struct Tracked<'r, 'a, 'p, R> {
+    inner: R,
+    reporter: &'r mut Reporter<'a, 'p>,
+}
+
+impl<R: Read> Read for Tracked<'_, '_, '_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.reporter.cancel.load(Ordering::Relaxed) {
+            return Err(io::Error::other("Cancelled"));
+        }
+        let n = self.inner.read(buf)?;
+        self.reporter.state.processed_bytes += n as u64;
+        (self.reporter.progress)(&self.reporter.state, false);
+        Ok(n)
+    }
+}
+
+/// Opens a source file, or records why it can't be archived.
+fn open_source(source: &Source, summary: &mut CreateArchiveSummary) -> Option<BufReader<File>> {
+    match File::open(&source.path) {
+        Ok(file) => Some(BufReader::with_capacity(COPY_CHUNK_BYTES, file)),
+        Err(e) => {
+            summary.skipped.push(SkippedEntry { path: source.path.to_string_lossy().to_string(), reason: e.to_string() });
+            None
+        }
+    }
+}
+
+fn write_zip<W: Write + Seek>(
+    out: W,
+    sources: &[Source],
+    options: &CreateArchiveOptions,
+    password: Option<&Zeroizing<String>>,
+    reporter: &mut Reporter<'_, '_>,
+    summary: &mut CreateArchiveSummary,
+) -> Result<W, String> {
+    let (method, level) = match options.zip_method {
+        ZipMethod::Store => (CompressionMethod::Stored, None),
+        ZipMethod::Deflate => (CompressionMethod::Deflated, options.level),
+        ZipMethod::Zstd => (CompressionMethod::Zstd, options.level),
+    };
+    let mut zip = ZipWriter::new(out);
+
+    for source in sources {
+        if !reporter.start_item(&source.name) {
+            break;
+        }
+        let mut entry_options = SimpleFileOptions::default().compression_method(method).compression_level(level);
+        if let Some(modified) = source.meta.modified().ok().and_then(zip_datetime) {
+            entry_options = entry_options.last_modified_time(modified);
+        }
+        if let Some(mode) = entry_mode(&source.meta) {
+            entry_options = entry_options.unix_permissions(mode);
+        }
+
+        match &source.kind {
+            SourceKind::Dir => zip.add_directory(source.name.as_str(), entry_options).map_err(|e| e.to_string())?,
+            SourceKind::Symlink(target) => zip
+                .add_symlink(source.name.as_str(), target.to_string_lossy().replace('\\', "/"), entry_options)
+                .map_err(|e| e.to_string())?,
+            SourceKind::File => {
+                let Some(file) = open_source(source, summary) else { continue };
+                let entry_options = entry_options.large_file(source.meta.len() >= ZIP64_THRESHOLD);
+                let started = match password {
+                    Some(password) => {
+                        zip.start_file(source.name.as_str(), entry_options.with_aes_encryption(AesMode::Aes256, password))
+                    }
+                    None => zip.start_file(source.name.as_str(), entry_options),
+                };
+                started.map_err(|e| e.to_string())?;
+                let copied = io::copy(&mut Tracked { inner: file, reporter: &mut *reporter }, &mut zip);
+                if reporter.cancel.load(Ordering::Relaxed) {
+                    break;
+                }
+                copied.map_err(|e| format!("Failed to read {}: {}", source.path.display(), e))?;
+            }
+        }
+        reporter.finish_item(summary);
+    }
+    zip.finish().map_err(|e| e.to_string())
+}
+
+/// Compression around the tar stream, finished explicitly so the trailer gets written.
+enum TarSink<W: Write> {
+    Plain(W),
+    Gz(GzEncoder<W>),
+    Xz(XzEncoder<W>),
+    Zstd(zstd::stream::write::Encoder<'static, W>),
+}
+
+impl<W: Write> TarSink<W> {
+    fn new(out: W, format: ArchiveFormat, level: Option<i64>) -> io::Result<Self> {
+        let clamped = |min: i64, max: i64, default: i64| level.unwrap_or(default).clamp(min, max);
+        Ok(match format {
+            ArchiveFormat::TarGz => Self::Gz(GzEncoder::new(out, flate2::Compression::new(clamped(0, 9, 6) as u32))),
+            ArchiveFormat::TarXz => Self::Xz(XzEncoder::new(out, clamped(0, 9, 6) as u32)),
+            ArchiveFormat::TarZst => Self::Zstd(zstd::stream::write::Encoder::new(out, clamped(1, 22, 3) as i32)?),
+            _ => Self::Plain(out),
+        })
+    }
+
+    fn finish(self) -> io::Result<W> {
+        match self {
+            Self::Plain(w) => Ok(w),
+            Self::Gz(e) => e.finish(),
+            Self::Xz(e) => e.finish(),
+            Self::Zstd(e) => e.finish(),
+        }
+    }
+}
+
+impl<W: Write> Write for TarSink<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(w) => w.write(buf),
+            Self::Gz(e) => e.write(buf),
+            Self::Xz(e) => e.write(buf),
+            Self::Zstd(e) => e.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(w) => w.flush(),
+            Self::Gz(e) => e.flush(),
+            Self::Xz(e) => e.flush(),
+            Self::Zstd(e) => e.flush(),
+        }
+    }
+}
+
+fn write_tar<W: Write>(
+    out: W,
+    format: ArchiveFormat,
+    sources: &[Source],
+    level: Option<i64>,
+    reporter: &mut Reporter<'_, '_>,
+    summary: &mut CreateArchiveSummary,
+) -> Result<W, String> {
+    let sink = TarSink::new(out, format, level).map_err(|e| e.to_string())?;
+    let mut tar = tar::Builder::new(sink);
+    tar.follow_symlinks(false);
+
+    for source in sources {
+        if !reporter.start_item(&source.name) {
+            break;
+        }
+        // Mode, owner and mtime come from the entry itself; the type is set from the metadata.
+        let mut header = tar::Header::new_gnu();
+        header.set_metadata_in_mode(&source.meta, tar::HeaderMode::Complete);
+
+        match &source.kind {
+            SourceKind::Dir => tar.append_data(&mut header, &source.name, io::empty()).map_err(|e| e.to_string())?,
+            SourceKind::Symlink(target) => {
+                header.set_size(0);
+                tar.append_link(&mut header, &source.name, target).map_err(|e| e.to_string())?;
+            }
+            SourceKind::File => {
+                let Some(file) = open_source(source, summary) else { continue };
+                // The header already promises this size; never write more than that.
+                let reader = Tracked { inner: file.take(source.meta.len()), reporter: &mut *reporter };
+                let appended = tar.append_data(&mut header, &source.name, reader);
+                if reporter.cancel.load(Ordering::Relaxed) {
+                    break;
+                }
+                appended.map_err(|e| format!("Failed to read {}: {}", source.path.display(), e))?;
+            }
+        }
+        reporter.finish_item(summary);
+    }
+    let sink = tar.into_inner().map_err(|e| e.to_string())?;
+    sink.finish().map_err(|e| e.to_string())
+}
+
+/// One logical output stream spread over "name.001", "name.002", ... of `volume_size` bytes
+/// each, or written to `base` alone when unsplit. It is seekable so zip can patch local
+/// headers after each entry, even when they landed in an earlier volume.
+struct VolumeWriter {
+    base: PathBuf,
+    volume_size: Option<u64>,
+    files: Vec<File>,
+    /// Number of volumes created so far, readable after the writer is gone.
+    created: Arc<AtomicUsize>,
+    pos: u64,
+    len: u64,
+}
+
+impl VolumeWriter {
+    fn new(base: &Path, volume_size: Option<u64>, created: Arc<AtomicUsize>) -> Self {
+        Self { base: base.to_path_buf(), volume_size, files: Vec::new(), created, pos: 0, len: 0 }
+    }
+
+    /// Opens volumes up to `index`; fails rather than truncate a file that already exists.
+    fn volume(&mut self, index: usize) -> io::Result<&mut File> {
+        while self.files.len() <= index {
+            let path = volume_path(&self.base, self.volume_size, self.files.len());
+            let file = fs::OpenOptions::new().read(true).write(true).create_new(true).open(&path).map_err(|e| {
+                io::Error::new(e.kind(), format!("Failed to create {}: {}", path.display(), e))
+            })?;
+            self.files.push(file);
+            self.created.store(self.files.len(), Ordering::Relaxed);
+        }
+        Ok(&mut self.files[index])
+    }
+
+    /// Syncs every volume and returns their paths in order.
+    fn finish(mut self) -> io::Result<Vec<PathBuf>> {
+        self.volume(0)?;
+        for file in &self.files {
+            file.sync_all()?;
+        }
+        Ok((0..self.files.len()).map(|i| volume_path(&self.base, self.volume_size, i)).collect())
+    }
+}
+
+impl Write for VolumeWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let size = self.volume_size.unwrap_or(u64::MAX);
+        let index = (self.pos / size) as usize;
+        let offset = self.pos % size;
+        let room = usize::try_from(size - offset).unwrap_or(usize::MAX);
+        let file = self.volume(index)?;
+        file.seek(SeekFrom::Start(offset))?;
+        let n = file.write(&buf[..buf.len().min(room)])?;
+        self.pos += n as u64;
+        self.len = self.len.max(self.pos);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.files.iter_mut().try_for_each(|f| f.flush())
+    }
+}
+
+impl Seek for VolumeWriter {
+    fn seek(&mut self, to: SeekFrom) -> io::Result<u64> {
+        let pos = match to {
+            SeekFrom::Start(pos) => Some(pos),
+            SeekFrom::Current(delta) => self.pos.checked_add_signed(delta),
+            SeekFrom::End(delta) => self.len.checked_add_signed(delta),
+        };
+        self.pos = pos.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Invalid seek"))?;
+        Ok(self.pos)
+    }
+}
+
+fn volume_path(base: &Path, volume_size: Option<u64>, index: usize) -> PathBuf {
+    match volume_size {
+        Some(_) => {
+            let mut name = base.as_os_str().to_os_string();
+            name.push(format!(".{:03}", index + 1));
+            PathBuf::from(name)
+        }
+        None => base.to_path_buf(),
+    }
+}
+
+/// Removes the first `count` volumes, i.e. the ones this run created.
+fn remove_volumes(base: &Path, volume_size: Option<u64>, count: usize) {
+    for index in 0..count {
+        let _ = fs::remove_file(volume_path(base, volume_size, index));
+    }
+}
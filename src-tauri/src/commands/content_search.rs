@@ -1,4 +1,4 @@
-use std::path::{PathBuf, Path};
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
@@ -6,40 +6,41 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 use tauri::{Emitter, Runtime};
+use walkdir::WalkDir;
 
 use super::settings::ConfigSection;
 use super::dedupe::ProgressEvent;
-use crate::utils::file_types::{get_file_category, is_category_enabled, FileCategory};
+use crate::commands::operation::{register_operation_with_paths, unregister_operation};
+use crate::commands::protocol::allow_tree;
+use crate::utils::categories::{self, categorize_for, is_enabled, TypeDetection};
+use crate::utils::exclusion::exclusions;
+use crate::utils::mountinfo::MountFilter;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ContentGroup {
     pub category: String,
     pub paths: Vec<String>,
+    /// Types of each entry in `paths`, when the section has `detect_content_type` on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detected_types: Option<Vec<TypeDetection>>,
 }
 
-fn is_system_path(path: &Path) -> bool {
-    let path_str = path.to_string_lossy();
-    #[cfg(target_os = "macos")]
-    {
-        path_str == "/System" || path_str.starts_with("/System/") ||
-        path_str == "/Library" || path_str.starts_with("/Library/") ||
-        path_str == "/private" || path_str.starts_with("/private/") ||
-        path_str == "/Applications" || path_str.starts_with("/Applications/") ||
-        path_str == "/bin" || path_str == "/usr" || path_str == "/sbin" ||
-        path_str == "/dev" || path_str == "/etc"
-    }
-    #[cfg(target_os = "windows")]
-    {
-        let p_upper = path_str.to_uppercase();
-        p_upper.contains("WINDOWS") || p_upper.contains("PROGRAM FILES") ||
-        p_upper.contains("PROGRAM DATA") || p_upper.contains("RECYCLE.BIN") ||
-        p_upper.contains("SYSTEM VOLUME INFORMATION")
-    }
-    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
-    {
-        path_str.starts_with("/proc") || path_str.starts_with("/sys") ||
-        path_str.starts_with("/boot") || path_str.starts_with("/dev")
-    }
+/// A file whose content doesn't match its extension.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExtensionMismatch {
+    pub path: String,
+    pub extension: String,
+    pub detected: TypeDetection,
+}
+
+/// Progress event emitted on "verify-progress" while checking extensions.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VerifyProgressEvent {
+    pub scanned: usize,
+    pub mismatches_found: usize,
+    pub current_path: String,
+    pub status: String,
+    pub elapsed_ms: u64,
 }
 
 #[tauri::command]
@@ -49,10 +50,11 @@ pub async fn find_content_by_category<R: Runtime>(
     settings: ConfigSection,
 ) -> Result<Vec<ContentGroup>, String> {
     let start_time = Instant::now();
+    let rules = exclusions();
     
     let mut unique_paths: Vec<PathBuf> = paths.into_iter()
         .map(PathBuf::from)
-        .filter(|p| p.exists() && !is_system_path(p))
+        .filter(|p| p.exists() && rules.excluded(p).is_none())
         .collect();
     
     unique_paths.sort();
@@ -63,6 +65,8 @@ pub async fn find_content_by_category<R: Runtime>(
         }
     }
 
+    cleaned_paths.iter().for_each(|p| allow_tree(p));
+
     let scanned_count = Arc::new(AtomicUsize::new(0));
     let progress_active = Arc::new(std::sync::atomic::AtomicBool::new(true));
     let last_path_shared = Arc::new(std::sync::Mutex::new(String::new()));
@@ -91,11 +95,19 @@ pub async fn find_content_by_category<R: Runtime>(
         }
     });
 
-    let mut groups: HashMap<String, Vec<String>> = HashMap::new();
+    let mut groups: HashMap<String, (Vec<String>, Vec<TypeDetection>)> = HashMap::new();
+    let categories = categories::registry();
 
+    let mounts = Arc::new(MountFilter::new(&settings));
     for start_path in &cleaned_paths {
         let mut walker = ignore::WalkBuilder::new(start_path);
         walker.follow_links(false);
+        let mounts = mounts.clone();
+        let rules = rules.clone();
+        walker.filter_entry(move |e| {
+            rules.excluded(e.path()).is_none()
+                && (e.depth() == 0 || !e.file_type().is_some_and(|t| t.is_dir()) || mounts.allows(e.path()))
+        });
         for result in walker.build() {
             let entry = match result {
                 Ok(e) => e,
@@ -107,7 +119,6 @@ pub async fn find_content_by_category<R: Runtime>(
             }
 
             let path = entry.path().to_path_buf();
-            if is_system_path(&path) { continue; }
             
             let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
 
@@ -125,23 +136,16 @@ pub async fn find_content_by_category<R: Runtime>(
                 continue;
             }
 
-            let file_cat = get_file_category(&extension);
-            if !is_category_enabled(file_cat, &settings) {
+            let (category, detection) = categorize_for(&categories, &settings, &path, &extension);
+            if !is_enabled(&settings, category) {
                 continue;
             }
 
-            let category = match file_cat {
-                FileCategory::Image => Some("Images"),
-                FileCategory::Video => Some("Videos"),
-                FileCategory::Audio => Some("Audio"),
-                FileCategory::Document => Some("Documents"),
-                FileCategory::Archive => Some("Compressed"),
-                _ => None,
-            };
-
             if let Some(cat) = category {
                 let path_str = path.to_string_lossy().to_string();
-                groups.entry(cat.to_string()).or_default().push(path_str.clone());
+                let group = groups.entry(cat.name.clone()).or_default();
+                group.0.push(path_str.clone());
+                group.1.extend(detection);
                 
                 scanned_count.fetch_add(1, Ordering::Relaxed);
                 if let Ok(mut p) = last_path_shared.lock() {
@@ -154,7 +158,11 @@ pub async fn find_content_by_category<R: Runtime>(
     progress_active.store(false, Ordering::Relaxed);
 
     let result: Vec<ContentGroup> = groups.into_iter()
-        .map(|(category, paths)| ContentGroup { category, paths })
+        .map(|(category, (paths, detections))| ContentGroup {
+            category,
+            paths,
+            detected_types: settings.detect_content_type.then_some(detections),
+        })
         .collect();
 
     // Final progress event
@@ -171,3 +179,85 @@ pub async fn find_content_by_category<R: Runtime>(
 
     Ok(result)
 }
+
+/// Checks every file under `paths` against its content and returns those whose extension is
+/// wrong for it. Visibility and block lists apply; category toggles don't. Stops early on
+/// `cancel_operation(operation_id)`, returning the mismatches found so far.
+#[tauri::command]
+pub async fn verify_extensions<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    operation_id: String,
+    paths: Vec<String>,
+    settings: ConfigSection,
+) -> Result<Vec<ExtensionMismatch>, String> {
+    let cancel_flag = register_operation_with_paths(operation_id.clone(), &paths);
+    let result = tokio::task::spawn_blocking(move || {
+        let start_time = Instant::now();
+        let categories = categories::registry();
+        let mut scanned = 0usize;
+        let mut mismatches = Vec::new();
+        let progress = |scanned: usize, found: usize, current_path: String, status: &str| {
+            let _ = app.emit("verify-progress", VerifyProgressEvent {
+                scanned,
+                mismatches_found: found,
+                current_path,
+                status: status.to_string(),
+                elapsed_ms: start_time.elapsed().as_millis() as u64,
+            });
+        };
+
+        let mounts = MountFilter::new(&settings);
+        let rules = exclusions();
+        'walk: for start_path in paths.iter().map(PathBuf::from).filter(|p| p.exists()) {
+            let walker = WalkDir::new(&start_path)
+                .follow_links(false)
+                .into_iter()
+                .filter_entry(|e| {
+                    // Hidden folders are pruned, not just their files skipped, so `.git` and
+                    // friends are never walked.
+                    let hidden = e.depth() > 0 && e.file_name().to_string_lossy().starts_with('.');
+                    rules.excluded(e.path()).is_none()
+                        && (settings.show_hidden_files || !hidden)
+                        && (e.depth() == 0 || !e.file_type().is_dir() || mounts.allows(e.path()))
+                });
+            for entry in walker.filter_map(|e| e.ok()) {
+                if cancel_flag.load(Ordering::Relaxed) {
+                    break 'walk;
+                }
+                if !entry.file_type().is_file() {
+                    continue;
+                }
+                let path = entry.path();
+                let name = entry.file_name().to_string_lossy().to_string();
+                let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+                if settings.blocked_extensions.contains(&extension) || settings.blocked_names.contains(&name) {
+                    continue;
+                }
+
+                scanned += 1;
+                let (_, detected) = categories.classify(path, &extension);
+                if detected.is_mismatch() {
+                    mismatches.push(ExtensionMismatch {
+                        path: path.to_string_lossy().to_string(),
+                        extension,
+                        detected,
+                    });
+                }
+                if scanned.is_multiple_of(200) {
+                    progress(scanned, mismatches.len(), path.to_string_lossy().to_string(), "Checking files...");
+                }
+            }
+        }
+
+        if cancel_flag.load(Ordering::Relaxed) {
+            progress(scanned, mismatches.len(), "Scan cancelled".to_string(), "Cancelled");
+        } else {
+            progress(scanned, mismatches.len(), "Scan complete".to_string(), "Done");
+        }
+        mismatches
+    })
+    .await
+    .map_err(|e| e.to_string());
+    unregister_operation(&operation_id);
+    result
+}
@@ -3,8 +3,8 @@ use std::path::Path;
 use tokio::fs::File;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tauri::{AppHandle, Emitter};
-use std::sync::atomic::Ordering;
-use crate::commands::operation::{register_operation, unregister_operation};
+use std::sync::atomic::{AtomicBool, Ordering};
+use crate::commands::operation::{register_operation_with_paths, unregister_operation};
 
 #[derive(Serialize, Clone)]
 pub struct CopyProgress {
@@ -31,8 +31,24 @@ pub async fn start_copy(
     let metadata = tokio::fs::metadata(src_path).await.map_err(|e| e.to_string())?;
     let total_bytes = metadata.len();
     
-    let cancel_flag = register_operation(operation_id.clone());
+    let cancel_flag = register_operation_with_paths(operation_id.clone(), [&source, &destination]);
+    // Every exit, errors included, unregisters; a stale entry would keep the volume "in use".
+    let result = copy_file(&app, &operation_id, src_path, dst_path, total_bytes, &cancel_flag).await;
+    unregister_operation(&operation_id);
+    result?;
+
+    let _ = app.emit("copy_completed", operation_id);
+    Ok(())
+}
 
+async fn copy_file(
+    app: &AppHandle,
+    operation_id: &str,
+    src_path: &Path,
+    dst_path: &Path,
+    total_bytes: u64,
+    cancel_flag: &AtomicBool,
+) -> Result<(), String> {
     let mut src_file = File::open(src_path).await.map_err(|e| e.to_string())?;
     let mut dst_file = File::create(dst_path).await.map_err(|e| e.to_string())?;
 
@@ -41,7 +57,7 @@ pub async fn start_copy(
 
     while bytes_written < total_bytes {
         if cancel_flag.load(Ordering::Relaxed) {
-            unregister_operation(&operation_id);
+            drop(dst_file);
             let _ = tokio::fs::remove_file(dst_path).await;
             return Err("Operation cancelled".to_string());
         }
@@ -54,14 +70,11 @@ pub async fn start_copy(
 
         let progress = (bytes_written as f64 / total_bytes as f64) * 100.0;
         let _ = app.emit("copy_progress", CopyProgress {
-            operation_id: operation_id.clone(),
+            operation_id: operation_id.to_string(),
             bytes_written,
             total_bytes,
             progress,
         });
     }
-
-    unregister_operation(&operation_id);
-    let _ = app.emit("copy_completed", operation_id);
     Ok(())
 }
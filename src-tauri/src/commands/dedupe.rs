@@ -14,8 +14,11 @@ use moka::sync::Cache;
 use lazy_static::lazy_static;
 
 use super::settings::ConfigSection;
-use crate::commands::operation::{register_operation, unregister_operation};
-use crate::utils::path_visibility::is_hidden_or_system;
+use crate::commands::operation::{register_operation_with_paths, unregister_operation};
+use crate::commands::protocol::allow_tree;
+use crate::utils::categories::{self, categorize_for, is_enabled};
+use crate::utils::exclusion::exclusions;
+use crate::utils::mountinfo::MountFilter;
 use crate::utils::text_like::is_text_like_extension;
 
 lazy_static! {
@@ -65,16 +68,18 @@ pub async fn find_duplicates<R: Runtime>(
     settings: ConfigSection,
 ) -> Result<(), String> {
     let start_time = Instant::now();
-    let _cancel_flag = register_operation("dedupe".to_string());
+    let _cancel_flag = register_operation_with_paths("dedupe".to_string(), &paths);
     
     let root_paths: Vec<PathBuf> = paths.into_iter()
         .map(PathBuf::from)
-        .filter(|p| p.exists() && !is_system_path(p))
+        .filter(|p| p.exists() && exclusions().excluded(p).is_none())
         .collect();
 
     if root_paths.is_empty() {
+        unregister_operation("dedupe");
         return Err("No valid paths provided for deduplication".to_string());
     }
+    root_paths.iter().for_each(|p| allow_tree(p));
 
     let scanned_count = Arc::new(AtomicUsize::new(0));
     let dups_found = Arc::new(AtomicUsize::new(0));
@@ -150,31 +155,6 @@ pub async fn find_duplicates<R: Runtime>(
     Ok(())
 }
 
-fn is_system_path(path: &Path) -> bool {
-    let path_str = path.to_string_lossy();
-    #[cfg(target_os = "macos")]
-    {
-        path_str == "/System" || path_str.starts_with("/System/") ||
-        path_str == "/Library" || path_str.starts_with("/Library/") ||
-        path_str == "/private" || path_str.starts_with("/private/") ||
-        path_str == "/Applications" || path_str.starts_with("/Applications/") ||
-        path_str == "/bin" || path_str == "/usr" || path_str == "/sbin" ||
-        path_str == "/dev" || path_str == "/etc"
-    }
-    #[cfg(target_os = "windows")]
-    {
-        let p_upper = path_str.to_uppercase();
-        p_upper.contains("WINDOWS") || p_upper.contains("PROGRAM FILES") ||
-        p_upper.contains("PROGRAM DATA") || p_upper.contains("RECYCLE.BIN") ||
-        p_upper.contains("SYSTEM VOLUME INFORMATION")
-    }
-    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
-    {
-        path_str.starts_with("/proc") || path_str.starts_with("/sys") ||
-        path_str.starts_with("/boot") || path_str.starts_with("/dev")
-    }
-}
-
 fn walk_and_discover(
     roots: &[PathBuf], 
     settings: &ConfigSection,
@@ -182,10 +162,17 @@ fn walk_and_discover(
     last_path: &std::sync::Mutex<String>
 ) -> Vec<PathBuf> {
     let mut files = Vec::new();
+    let categories = categories::registry();
+    let mounts = MountFilter::new(settings);
+    let rules = exclusions();
     for root in roots {
         let walker = WalkDir::new(root)
             .follow_links(false)
             .into_iter()
+            .filter_entry(|e| {
+                rules.excluded(e.path()).is_none()
+                    && (e.depth() == 0 || !e.file_type().is_dir() || mounts.allows(e.path()))
+            })
             .filter_map(|e| e.ok());
             
         for entry in walker {
@@ -193,25 +180,23 @@ fn walk_and_discover(
             if !entry.file_type().is_file() { continue; }
             
             let path = entry.path().to_path_buf();
-            if is_system_path(&path) { continue; }
             
             let name = path.file_name().unwrap_or_default().to_string_lossy();
             let name_str = name.to_string();
             
             if !settings.show_hidden_files && name.starts_with('.') { continue; }
             
-            if !settings.show_system_files && is_hidden_or_system(&name_str, &path) {
-                continue;
-            }
-            
             let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
             if settings.blocked_extensions.contains(&extension) { continue; }
             if settings.blocked_names.contains(&name_str) { continue; }
 
-            // Filter by 4 core types
-            if !settings.preview_enabled.is_extension_enabled(&extension) { continue; }
+            // Filter by the categories enabled for dedupe
+            let (category, detection) = categorize_for(&categories, settings, &path, &extension);
+            if !is_enabled(settings, category) { continue; }
 
-            if !settings.include_plain_text_in_duplicate_scan && is_text_like_extension(&extension) {
+            // A text-like extension on identified binary content is a misnamed file, not text
+            let binary = detection.is_some_and(|d| d.content_mime.is_some());
+            if !settings.include_plain_text_in_duplicate_scan && is_text_like_extension(&extension) && !binary {
                 continue;
             }
 
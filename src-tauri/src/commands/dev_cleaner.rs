@@ -0,0 +1,233 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Instant, UNIX_EPOCH};
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Runtime};
+use walkdir::WalkDir;
+
+use super::settings::{get_settings_path, read_settings_file, ArtifactRule, ConfigSection};
+use crate::utils::exclusion::{exclusions, Exclusions};
+use crate::utils::mountinfo::MountFilter;
+
+/// Progress event emitted on "artifact-progress" while scanning for build artifacts.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ArtifactProgressEvent {
+    pub scanned_folders: usize,
+    pub artifacts_found: usize,
+    pub current_path: String,
+    pub status: String,
+    pub elapsed_ms: u64,
+}
+
+/// A regenerable build/cache folder inside a recognized project.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BuildArtifact {
+    pub path: String,
+    pub name: String,
+    pub project_root: String,
+    pub project_name: String,
+    /// Name of the [ArtifactRule] that matched (e.g. "Rust", "Node.js").
+    pub rule: String,
+    pub size: u64,
+    /// Newest mtime (secs) of the project's own files, ignoring artifact folders and `.git`.
+    pub last_activity: Option<u64>,
+}
+
+/// Candidate found during the walk; sizes and activity are filled in afterwards.
+struct Found {
+    path: PathBuf,
+    project_root: PathBuf,
+    rule: String,
+}
+
+struct ScanState<'a, R: Runtime> {
+    app: &'a tauri::AppHandle<R>,
+    start_time: Instant,
+    mounts: MountFilter,
+    exclusions: std::sync::Arc<Exclusions>,
+    scanned: usize,
+    found: Vec<Found>,
+    /// Project root -> newest mtime seen outside artifact folders.
+    activity: Vec<(PathBuf, u64)>,
+}
+
+/// Scan `paths` for project roots (by marker files) and report their artifact folders.
+/// `rules` and `settings` default to the saved `artifact_rules` and clean section.
+/// Nothing is deleted here; the frontend removes selections through `delete_items`.
+#[tauri::command]
+pub async fn find_build_artifacts<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    paths: Vec<String>,
+    rules: Option<Vec<ArtifactRule>>,
+    settings: Option<ConfigSection>,
+) -> Result<Vec<BuildArtifact>, String> {
+    let saved = get_settings_path(&app)
+        .and_then(|path| read_settings_file(&path))
+        .map(|(saved, _)| saved)
+        .unwrap_or_default();
+    let rules = rules.unwrap_or(saved.artifact_rules);
+    let mounts = MountFilter::new(&settings.unwrap_or(saved.clean));
+    let app_clone = app.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let mut state = ScanState {
+            app: &app_clone,
+            start_time: Instant::now(),
+            mounts,
+            exclusions: exclusions(),
+            scanned: 0,
+            found: Vec::new(),
+            activity: Vec::new(),
+        };
+
+        for start_path in &paths {
+            let path = PathBuf::from(start_path);
+            if !path.is_dir() || state.exclusions.excluded(&path).is_some() {
+                continue;
+            }
+            walk_projects(&path, &rules, &[], &mut state);
+        }
+
+        let activity = state.activity;
+        let mut results: Vec<BuildArtifact> = state
+            .found
+            .into_par_iter()
+            .map(|f| BuildArtifact {
+                path: f.path.to_string_lossy().to_string(),
+                name: file_name_of(&f.path),
+                project_name: file_name_of(&f.project_root),
+                size: dir_size(&f.path),
+                last_activity: activity
+                    .iter()
+                    .find(|(root, _)| *root == f.project_root)
+                    .map(|(_, t)| *t),
+                project_root: f.project_root.to_string_lossy().to_string(),
+                rule: f.rule,
+            })
+            .collect();
+
+        // Biggest wins first
+        results.sort_by_key(|a| std::cmp::Reverse(a.size));
+
+        let _ = app_clone.emit("artifact-progress", ArtifactProgressEvent {
+            scanned_folders: state.scanned,
+            artifacts_found: results.len(),
+            current_path: "Scan complete".to_string(),
+            status: "Done".to_string(),
+            elapsed_ms: state.start_time.elapsed().as_millis() as u64,
+        });
+
+        results
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Recursive walk. `active` holds (project root, rule) pairs of enclosing projects, so nested
+/// artifacts such as `pkg/sub/__pycache__` are attributed to the nearest project root.
+/// `artifact_dirs` only match directly inside a root, so a `build` source package deeper
+/// down is left alone.
+/// Returns the newest file mtime seen in this subtree (outside artifact folders).
+fn walk_projects<R: Runtime>(
+    dir: &Path,
+    rules: &[ArtifactRule],
+    active: &[(PathBuf, &ArtifactRule)],
+    state: &mut ScanState<'_, R>,
+) -> u64 {
+    state.scanned += 1;
+    if state.scanned.is_multiple_of(50) {
+        let _ = state.app.emit("artifact-progress", ArtifactProgressEvent {
+            scanned_folders: state.scanned,
+            artifacts_found: state.found.len(),
+            current_path: dir.to_string_lossy().to_string(),
+            status: "Scanning...".to_string(),
+            elapsed_ms: state.start_time.elapsed().as_millis() as u64,
+        });
+    }
+
+    let entries: Vec<fs::DirEntry> = match fs::read_dir(dir) {
+        Ok(rd) => rd.filter_map(|e| e.ok()).collect(),
+        Err(_) => return 0,
+    };
+
+    let names: Vec<String> = entries
+        .iter()
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+
+    // A folder may be the root of several projects at once (e.g. Cargo.toml + package.json).
+    let mut scope: Vec<(PathBuf, &ArtifactRule)> = active.to_vec();
+    let mut is_root = false;
+    for rule in rules {
+        if rule.markers.iter().any(|m| names.contains(m)) {
+            scope.retain(|(_, r)| r.name != rule.name);
+            scope.push((dir.to_path_buf(), rule));
+            is_root = true;
+        }
+    }
+
+    let mut newest = 0u64;
+    for (entry, name) in entries.iter().zip(names.iter()) {
+        let file_type = match entry.file_type() {
+            Ok(ft) => ft,
+            Err(_) => continue,
+        };
+        if state.exclusions.excluded(&entry.path()).is_some() {
+            continue;
+        }
+
+        if file_type.is_dir() {
+            if name == ".git" {
+                continue;
+            }
+            // Innermost project wins when two enclosing rules claim the same folder name.
+            if let Some((root, rule)) = scope.iter().rev().find(|(root, r)| {
+                (root == dir && r.artifact_dirs.contains(name)) || r.nested_dirs.contains(name)
+            }) {
+                state.found.push(Found {
+                    path: entry.path(),
+                    project_root: root.clone(),
+                    rule: rule.name.clone(),
+                });
+                continue;
+            }
+            if !state.mounts.allows(&entry.path()) {
+                continue;
+            }
+            newest = newest.max(walk_projects(&entry.path(), rules, &scope, state));
+        } else if file_type.is_file() {
+            let mtime = entry
+                .metadata()
+                .ok()
+                .and_then(|m| m.modified().ok())
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            newest = newest.max(mtime);
+        }
+    }
+
+    if is_root {
+        state.activity.push((dir.to_path_buf(), newest));
+    }
+    newest
+}
+
+fn dir_size(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+fn file_name_of(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string_lossy().to_string())
+}
@@ -3,8 +3,11 @@ use serde::Serialize;
 use tauri::{AppHandle, Manager};
 use std::fs;
 
-use super::settings::ConfigSection;
-use crate::utils::path_visibility::is_hidden_or_system;
+use super::folder_overrides::{resolve_folder_override, SortField, SortOrder, ViewMode};
+use super::settings::{get_settings_path, read_settings_file, ConfigSection};
+use crate::commands::protocol::allow_dir;
+use crate::utils::categories::{self, categorize_for, is_enabled, TypeDetection};
+use crate::utils::exclusion::{exclusions, DeletionGuard};
 
 #[derive(Serialize)]
 pub struct FileEntry {
@@ -15,6 +18,9 @@ pub struct FileEntry {
     pub size: Option<u64>,
     pub modified: Option<u64>,
     pub extension: Option<String>,
+    /// Extension- and content-derived types, when the section has `detect_content_type` on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detected_type: Option<TypeDetection>,
 }
 
 #[derive(Serialize)]
@@ -22,39 +28,74 @@ pub struct DirectoryResponse {
     pub entries: Vec<FileEntry>,
     pub total: usize,
     pub has_more: bool,
+    pub view: FolderView,
 }
 
+/// How the listing was ordered and should be shown, after folder overrides.
+#[derive(Serialize)]
+pub struct FolderView {
+    pub sort_by: SortField,
+    pub order: SortOrder,
+    /// `None` leaves the view mode to the caller.
+    pub view_mode: Option<ViewMode>,
+    /// Nearest folder whose override applies here.
+    pub override_source: Option<String>,
+    pub has_own_override: bool,
+}
+
+/// Lists one page of `path`. Folder overrides take precedence over both `settings` (which
+/// defaults to the saved explorer settings) and the requested sort.
 #[tauri::command]
 pub async fn read_dir_chunked(
-    _app: AppHandle,
+    app: AppHandle,
     path: String,
-    settings: ConfigSection,
+    settings: Option<ConfigSection>,
     offset: usize,
     limit: usize,
+    sort_by: Option<SortField>,
+    order: Option<SortOrder>,
 ) -> Result<DirectoryResponse, String> {
     let root = Path::new(&path);
     if !root.exists() {
         return Err("Path does not exist".to_string());
     }
+    allow_dir(root);
+
+    let mut settings = match settings {
+        Some(settings) => settings,
+        None => read_settings_file(&get_settings_path(&app)?)?.0.explorer,
+    };
+    let resolved = resolve_folder_override(&app, root)?;
+    resolved.effective.apply_to(&mut settings);
+    let view = FolderView {
+        sort_by: resolved.effective.sort_by.or(sort_by).unwrap_or_default(),
+        order: resolved.effective.order.or(order).unwrap_or_default(),
+        view_mode: resolved.effective.view_mode,
+        override_source: resolved.source,
+        has_own_override: resolved.own.is_some(),
+    };
 
     let mut entries = Vec::new();
+    let categories = categories::registry();
     let read_ptr = fs::read_dir(root).map_err(|e| e.to_string())?;
 
     let mut all_entries: Vec<_> = read_ptr.filter_map(|e| e.ok()).collect();
 
-    // Sort entries: directories first, then alphabetically
-    all_entries.sort_by(|a, b| {
-        let a_is_dir = a.file_type().map(|t| t.is_dir()).unwrap_or(false);
-        let b_is_dir = b.file_type().map(|t| t.is_dir()).unwrap_or(false);
-        if a_is_dir != b_is_dir {
-            b_is_dir.cmp(&a_is_dir)
-        } else {
-            a.file_name().cmp(&b.file_name())
-        }
-    });
+    // Sort by the chosen field (name breaks ties), then put directories first
+    all_entries.sort_by_key(|e| e.file_name());
+    match view.sort_by {
+        SortField::Name => {}
+        SortField::Size => all_entries.sort_by_cached_key(|e| e.metadata().map(|m| m.len()).unwrap_or(0)),
+        SortField::Modified => all_entries.sort_by_cached_key(|e| e.metadata().and_then(|m| m.modified()).ok()),
+    }
+    if view.order == SortOrder::Desc {
+        all_entries.reverse();
+    }
+    all_entries.sort_by_cached_key(|e| !e.file_type().map(|t| t.is_dir()).unwrap_or(false));
 
     let total = all_entries.len();
     let paged_entries = all_entries.into_iter().skip(offset);
+    let rules = exclusions();
 
     for entry in paged_entries {
         if entries.len() >= limit {
@@ -68,7 +109,7 @@ pub async fn read_dir_chunked(
             continue;
         }
 
-        if !settings.show_system_files && is_hidden_or_system(&name, &entry.path()) {
+        if !settings.show_system_files && rules.system(&entry.path()).is_some() {
             continue;
         }
 
@@ -83,17 +124,15 @@ pub async fn read_dir_chunked(
             path_buf.extension().map(|e| e.to_string_lossy().to_string().to_lowercase())
         };
 
-        // Filter by extension if preview settings are active for this section
+        // Only files in categories enabled for this section are listed
+        let mut detected_type = None;
         if !is_dir {
-            if let Some(ref ext) = extension {
-                if !settings.preview_enabled.is_extension_enabled(ext) {
-                    continue;
-                }
-            } else {
-                if !settings.preview_enabled.is_extension_enabled("") {
-                    continue;
-                }
+            let (category, detection) =
+                categorize_for(&categories, &settings, &path_buf, extension.as_deref().unwrap_or(""));
+            if !is_enabled(&settings, category) {
+                continue;
             }
+            detected_type = detection;
         }
 
         // Blocked list filters
@@ -119,6 +158,7 @@ pub async fn read_dir_chunked(
                 .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
                 .map(|d| d.as_secs()),
             extension,
+            detected_type,
         });
     }
 
@@ -128,6 +168,7 @@ pub async fn read_dir_chunked(
         entries,
         total,
         has_more,
+        view,
     })
 }
 
@@ -150,6 +191,7 @@ pub async fn rename_item(old_path: String, new_path: String) -> Result<(), Strin
     if new.exists() {
         return Err("Destination already exists".to_string());
     }
+    DeletionGuard::new().check(old)?;
     fs::rename(old, new).map_err(|e| e.to_string())
 }
 
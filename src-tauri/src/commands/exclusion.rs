@@ -0,0 +1,22 @@
+use serde::Serialize;
+use std::path::Path;
+
+use crate::utils::exclusion::{exclusions, DeletionGuard, ExclusionRule};
+
+/// What [explain_exclusion] reports for a path; `None` means no rule applies.
+#[derive(Debug, Serialize, Clone)]
+pub struct ExclusionReport {
+    pub path: String,
+    /// Rule that makes scans and searches skip the path.
+    pub excluded_by: Option<ExclusionRule>,
+    /// Rule that makes delete, move and rename refuse the path.
+    pub protected_by: Option<ExclusionRule>,
+}
+
+#[tauri::command]
+pub fn explain_exclusion(path: String) -> ExclusionReport {
+    let target = Path::new(&path);
+    let excluded_by = exclusions().excluded(target).cloned();
+    let protected_by = DeletionGuard::new().rule_for(target).cloned();
+    ExclusionReport { path, excluded_by, protected_by }
+}
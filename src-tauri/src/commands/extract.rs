@@ -0,0 +1,812 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter};
+use zeroize::Zeroizing;
+use zip::read::ZipArchive;
+
+use crate::commands::archive::{
+    archive_kind, compressed_entry_name, decompress, for_each_7z_entry, open_7z, open_zip_entry, sevenz_entry_path,
+    sevenz_is_symlink, sevenz_mtime, sevenz_unix_mode, tar_from_reader, zip_error, zip_mtime, ArchiveError, ArchiveKind,
+    Codec, TAR_EXTENSIONS, UNSUPPORTED_FORMAT,
+};
+use crate::commands::archive_password::{prompt_password, with_password, PasswordFn, PasswordPurpose};
+use crate::commands::batch::unique_dest_path;
+use crate::commands::operation::{register_operation_with_paths, unregister_operation};
+
+const COPY_CHUNK_BYTES: usize = 1024 * 1024; // 1 MB
+const PROGRESS_INTERVAL_MS: u128 = 100;
+
+/// What to do when an extracted file would replace something already on disk.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    Overwrite,
+    Skip,
+    /// Write "name (1).ext" next to the existing file.
+    #[default]
+    Rename,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ExtractOptions {
+    /// Target folder; defaults to a new folder named after the archive, next to it. A single
+    /// compressed file (e.g. "notes.txt.gz") is written next to the archive instead.
+    pub destination: Option<String>,
+    /// Entry paths (as returned by `list_archive`) to extract; a directory selects its contents.
+    /// `None` extracts everything.
+    pub entries: Option<Vec<String>>,
+    pub conflict_policy: ConflictPolicy,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ExtractProgress {
+    pub operation_id: String,
+    pub current_item: String,
+    pub processed_items: usize,
+    /// Uncompressed bytes written (zip, 7z) or archive bytes consumed (compressed streams).
+    pub processed_bytes: u64,
+    pub total_bytes: u64,
+    pub progress: f64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SkippedEntry {
+    pub path: String,
+    pub reason: String,
+}
+
+/// Returned by the job and emitted on "extract_finished".
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct ExtractSummary {
+    pub operation_id: String,
+    pub destination: String,
+    pub extracted: usize,
+    pub skipped: Vec<SkippedEntry>,
+    pub cancelled: bool,
+}
+
+/// Extract all or some entries of an archive into a destination folder, emitting
+/// "extract_progress" and honouring `cancel_operation(operation_id)`.
+///
+/// Entries that would land outside the destination (absolute paths, `..`, links pointing out,
+/// or paths routed through a symlinked folder) are skipped and reported, never written.
+///
+/// Encrypted zip entries raise "archive_password_required"; the job waits for
+/// `provide_archive_password` and asks again after a wrong password.
+#[tauri::command]
+pub async fn start_extract(
+    app: AppHandle,
+    operation_id: String,
+    path: String,
+    options: Option<ExtractOptions>,
+) -> Result<ExtractSummary, ArchiveError> {
+    let options = options.unwrap_or_default();
+    let cancel_flag = register_operation_with_paths(operation_id.clone(), [Some(&path), options.destination.as_ref()].into_iter().flatten());
+
+    let op_id = operation_id.clone();
+    let app_clone = app.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let mut last_emit = Instant::now();
+        let mut emit = |p: &ProgressState, force: bool| {
+            if force || last_emit.elapsed().as_millis() > PROGRESS_INTERVAL_MS {
+                let _ = app_clone.emit("extract_progress", ExtractProgress {
+                    operation_id: op_id.clone(),
+                    current_item: p.current_item.clone(),
+                    processed_items: p.processed_items,
+                    processed_bytes: p.processed_bytes,
+                    total_bytes: p.total_bytes,
+                    progress: if p.total_bytes == 0 {
+                        100.0
+                    } else {
+                        (p.processed_bytes as f64 / p.total_bytes as f64) * 100.0
+                    },
+                });
+                last_emit = Instant::now();
+            }
+        };
+        let mut ask = |attempt: u32, wrong: bool| {
+            prompt_password(&app_clone, &op_id, &path, PasswordPurpose::Extract, &cancel_flag, attempt, wrong)
+        };
+        extract_blocking(Path::new(&path), &options, &cancel_flag, &mut emit, &mut ask)
+    })
+    .await
+    .map_err(|e| ArchiveError::Other(e.to_string()));
+
+    unregister_operation(&operation_id);
+    let mut summary = result??;
+    summary.operation_id = operation_id;
+    let _ = app.emit("extract_finished", summary.clone());
+    Ok(summary)
+}
+
+/// Progress reported to the caller's callback.
+pub(crate) struct ProgressState {
+    pub current_item: String,
+    pub processed_items: usize,
+    pub processed_bytes: u64,
+    pub total_bytes: u64,
+}
+
+type ProgressFn<'a> = dyn FnMut(&ProgressState, bool) + 'a;
+
+/// The folder `extract_archive` uses: the archive's stem next to it, made unique.
+pub(crate) fn default_destination(archive: &Path) -> PathBuf {
+    let parent = archive.parent().unwrap_or(Path::new(""));
+    let name = archive.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let lower = name.to_lowercase();
+    // "photos.tar.gz" -> "photos", not "photos.tar".
+    let stem = TAR_EXTENSIONS
+        .iter()
+        .chain(&[".zip", ".7z"])
+        .find(|ext| lower.ends_with(*ext))
+        .map(|ext| name[..name.len() - ext.len()].to_string())
+        .unwrap_or_else(|| archive.file_stem().unwrap_or_default().to_string_lossy().to_string());
+    let mut dest = parent.join(&stem);
+    let mut counter = 1;
+    while dest.exists() {
+        dest = parent.join(format!("{} ({})", stem, counter));
+        counter += 1;
+    }
+    dest
+}
+
+pub(crate) fn extract_blocking(
+    archive: &Path,
+    options: &ExtractOptions,
+    cancel: &AtomicBool,
+    progress: &mut ProgressFn<'_>,
+    ask: &mut PasswordFn<'_>,
+) -> Result<ExtractSummary, ArchiveError> {
+    if !archive.is_file() {
+        return Err("File does not exist".into());
+    }
+    let kind = archive_kind(archive).ok_or_else(|| ArchiveError::Unsupported(UNSUPPORTED_FORMAT.to_string()))?;
+    let dest = options
+        .destination
+        .as_ref()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| match kind {
+            ArchiveKind::Compressed(_) => archive.parent().map(Path::to_path_buf).unwrap_or_else(|| PathBuf::from(".")),
+            _ => default_destination(archive),
+        });
+    fs::create_dir_all(&dest).map_err(|e| format!("Failed to create destination: {}", e))?;
+    let root = fs::canonicalize(&dest).map_err(|e| e.to_string())?;
+
+    let mut job = Job {
+        root,
+        selection: options.entries.as_ref().map(|entries| {
+            entries.iter().map(|e| normalize_entry_name(e)).filter(|e| !e.is_empty()).collect()
+        }),
+        conflict: options.conflict_policy,
+        cancel,
+        progress,
+        state: ProgressState { current_item: String::new(), processed_items: 0, processed_bytes: 0, total_bytes: 0 },
+        summary: ExtractSummary { destination: dest.to_string_lossy().to_string(), ..Default::default() },
+        dir_times: Vec::new(),
+        consumed: None,
+        password: None,
+        ask,
+    };
+
+    let result = match kind {
+        ArchiveKind::Zip => job.run_zip(archive),
+        ArchiveKind::SevenZ => job.run_7z(archive),
+        ArchiveKind::Tar(codec) => job.run_tar(archive, codec),
+        ArchiveKind::Compressed(codec) => job.run_compressed(archive, codec),
+    };
+    match result {
+        // Dismissing the password prompt stops the job like a cancel would.
+        Err(ArchiveError::Cancelled(_)) => job.summary.cancelled = true,
+        result => result?,
+    }
+
+    // Directory mtimes last: writing children would bump them again.
+    for (dir, mtime) in job.dir_times.drain(..).rev() {
+        let _ = filetime::set_file_mtime(&dir, mtime);
+    }
+    (job.progress)(&job.state, true);
+    Ok(job.summary)
+}
+
+/// "./a//b/" -> "a/b".
+fn normalize_entry_name(name: &str) -> String {
+    name.replace('\\', "/")
+        .split('/')
+        .filter(|part| !part.is_empty() && *part != ".")
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Relative path for an entry, or `None` when it tries to leave the destination. Leading `/`
+/// and drive prefixes are dropped (as GNU tar does); any `..` component rejects the entry.
+fn sanitize_entry_path(name: &str) -> Option<PathBuf> {
+    let mut out = PathBuf::new();
+    for component in Path::new(&name.replace('\\', "/")).components() {
+        match component {
+            Component::Normal(part) => out.push(part),
+            Component::CurDir | Component::RootDir | Component::Prefix(_) => {}
+            Component::ParentDir => return None,
+        }
+    }
+    (!out.as_os_str().is_empty()).then_some(out)
+}
+
+/// Lexically resolves `.`/`..` so link targets can be checked without touching the disk.
+fn normalize_lexically(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                out.pop();
+            }
+            Component::CurDir => {}
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+struct Job<'a, 'p> {
+    /// Canonical destination; every write must stay below it.
+    root: PathBuf,
+    selection: Option<Vec<String>>,
+    conflict: ConflictPolicy,
+    cancel: &'a AtomicBool,
+    progress: &'a mut ProgressFn<'p>,
+    state: ProgressState,
+    summary: ExtractSummary,
+    dir_times: Vec<(PathBuf, filetime::FileTime)>,
+    /// Compressed bytes read so far, for streams whose uncompressed size isn't known up front.
+    consumed: Option<Arc<AtomicU64>>,
+    /// Accepted zip password, reused for every encrypted entry; dropped (and wiped) with the job.
+    password: Option<Zeroizing<String>>,
+    ask: &'a mut PasswordFn<'p>,
+}
+
+enum EntryKind<'r> {
+    Dir,
+    File(&'r mut dyn Read),
+    Symlink(PathBuf),
+    /// Tar hard link to an earlier entry (path inside the archive).
+    HardLink(String),
+}
+
+struct EntryMeta {
+    name: String,
+    mode: Option<u32>,
+    mtime: Option<u64>,
+}
+
+impl Job<'_, '_> {
+    fn cancelled(&mut self) -> bool {
+        if self.cancel.load(Ordering::Relaxed) {
+            self.summary.cancelled = true;
+        }
+        self.summary.cancelled
+    }
+
+    fn is_selected(&self, name: &str) -> bool {
+        let Some(selection) = &self.selection else { return true };
+        selection.iter().any(|s| name == s || name.starts_with(&format!("{}/", s)))
+    }
+
+    fn skip(&mut self, name: &str, reason: impl Into<String>) {
+        self.summary.skipped.push(SkippedEntry { path: name.to_string(), reason: reason.into() });
+    }
+
+    /// Creates the parent folders of `rel` and returns where the entry goes on disk; `None`
+    /// when a symlink already on the way leads out of the destination.
+    fn prepare_target(&self, rel: &Path) -> io::Result<Option<PathBuf>> {
+        let Some(file_name) = rel.file_name() else { return Ok(None) };
+        let parent = self.create_dirs_within(rel.parent().unwrap_or(Path::new("")))?;
+        Ok(parent.map(|parent| parent.join(file_name)))
+    }
+
+    /// Creates `rel` below the destination one folder at a time, checking each component
+    /// before using it, so no folder is ever created through a symlink that points outside.
+    /// Returns the folder's on-disk path (symlinks inside the destination resolved).
+    fn create_dirs_within(&self, rel: &Path) -> io::Result<Option<PathBuf>> {
+        let mut dir = self.root.clone();
+        for component in rel.components() {
+            let next = dir.join(component);
+            match fs::symlink_metadata(&next) {
+                Ok(meta) if meta.file_type().is_symlink() => {
+                    let resolved = fs::canonicalize(&next)?;
+                    if !resolved.starts_with(&self.root) {
+                        return Ok(None);
+                    }
+                    dir = resolved;
+                }
+                Ok(meta) if meta.is_dir() => dir = next,
+                Ok(_) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::AlreadyExists,
+                        format!("{} exists and is not a folder", next.display()),
+                    ))
+                }
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                    fs::create_dir(&next)?;
+                    dir = next;
+                }
+                Err(e) => return Err(e),
+            }
+            if !dir.is_dir() {
+                return Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("{} is not a folder", dir.display())));
+            }
+        }
+        Ok(Some(dir))
+    }
+
+    /// Follows a symlink's target from its (on-disk) parent the way the OS will, looking at
+    /// each component on disk. `..` is only taken through real folders: after a symlink or a
+    /// path that doesn't exist yet, a later entry could change what it refers to.
+    fn link_stays_inside(&self, parent: &Path, link: &Path) -> bool {
+        let mut resolved = parent.to_path_buf();
+        let mut on_disk = true;
+        for component in link.components() {
+            match component {
+                Component::Normal(part) => {
+                    resolved.push(part);
+                    match fs::symlink_metadata(&resolved) {
+                        Ok(meta) if meta.file_type().is_symlink() => {
+                            let Ok(target) = fs::canonicalize(&resolved) else { return false };
+                            resolved = target;
+                            on_disk = false;
+                        }
+                        Ok(meta) => on_disk &= meta.is_dir(),
+                        Err(_) => on_disk = false,
+                    }
+                }
+                Component::ParentDir if on_disk => {
+                    resolved.pop();
+                }
+                Component::CurDir => {}
+                _ => return false,
+            }
+            if !resolved.starts_with(&self.root) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Applies the conflict policy; `None` means skip this entry.
+    fn resolve_conflict(&self, target: PathBuf) -> io::Result<Option<PathBuf>> {
+        let Ok(existing) = fs::symlink_metadata(&target) else { return Ok(Some(target)) };
+        match self.conflict {
+            ConflictPolicy::Skip => Ok(None),
+            ConflictPolicy::Rename => {
+                let parent = target.parent().unwrap_or(&self.root).to_path_buf();
+                Ok(Some(unique_dest_path(&parent, target.file_name().unwrap_or_default())))
+            }
+            ConflictPolicy::Overwrite => {
+                if existing.is_dir() {
+                    // Never replace a whole folder with a file.
+                    return Ok(None);
+                }
+                fs::remove_file(&target)?;
+                Ok(Some(target))
+            }
+        }
+    }
+
+    fn extract_entry(&mut self, meta: EntryMeta, kind: EntryKind<'_>) -> Result<(), String> {
+        let name = normalize_entry_name(&meta.name);
+        if name.is_empty() || !self.is_selected(&name) {
+            return Ok(());
+        }
+        self.state.current_item = name.clone();
+        let Some(rel) = sanitize_entry_path(&name) else {
+            self.skip(&name, "Path escapes the destination folder");
+            return Ok(());
+        };
+        let target = match self.prepare_target(&rel) {
+            Ok(Some(t)) => t,
+            Ok(None) => {
+                self.skip(&name, "Path escapes the destination folder through a symlink");
+                return Ok(());
+            }
+            Err(e) => {
+                self.skip(&name, e.to_string());
+                return Ok(());
+            }
+        };
+        let mtime = meta.mtime.map(|t| filetime::FileTime::from_unix_time(t as i64, 0));
+
+        match kind {
+            EntryKind::Dir => {
+                // An existing symlinked folder in the destination must not be chmod-ed through.
+                let target = match self.create_dirs_within(&rel) {
+                    Ok(Some(dir)) => dir,
+                    Ok(None) => {
+                        self.skip(&name, "Path escapes the destination folder through a symlink");
+                        return Ok(());
+                    }
+                    Err(e) => {
+                        self.skip(&name, e.to_string());
+                        return Ok(());
+                    }
+                };
+                // Owner must keep rwx on folders or the rest of the archive can't be written.
+                set_permissions(&target, meta.mode.map(|m| (m & 0o777) | 0o700));
+                if let Some(mtime) = mtime {
+                    self.dir_times.push((target, mtime));
+                }
+            }
+            EntryKind::File(reader) => {
+                let Some(target) = self.resolve_conflict(target).map_err(|e| e.to_string())? else {
+                    self.skip(&name, "Already exists");
+                    return Ok(());
+                };
+                match self.write_file(&target, reader) {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        // Cancelled mid-file: don't leave a truncated file behind.
+                        let _ = fs::remove_file(&target);
+                        return Ok(());
+                    }
+                    Err(e) => {
+                        let _ = fs::remove_file(&target);
+                        self.skip(&name, e.to_string());
+                        return Ok(());
+                    }
+                }
+                // Setuid/setgid/sticky bits are never restored.
+                set_permissions(&target, meta.mode.map(|m| m & 0o777));
+                if let Some(mtime) = mtime {
+                    let _ = filetime::set_file_mtime(&target, mtime);
+                }
+            }
+            EntryKind::Symlink(link) => {
+                let parent = target.parent().unwrap_or(&self.root);
+                let resolved = normalize_lexically(&parent.join(&link));
+                if link.is_absolute() || !resolved.starts_with(&self.root) || !self.link_stays_inside(parent, &link) {
+                    self.skip(&name, "Symlink points outside the destination folder");
+                    return Ok(());
+                }
+                let Some(target) = self.resolve_conflict(target).map_err(|e| e.to_string())? else {
+                    self.skip(&name, "Already exists");
+                    return Ok(());
+                };
+                if let Err(e) = create_symlink(&link, &target) {
+                    self.skip(&name, e.to_string());
+                    return Ok(());
+                }
+            }
+            EntryKind::HardLink(source) => {
+                let Some(source) = sanitize_entry_path(&source).map(|s| self.root.join(s)) else {
+                    self.skip(&name, "Hard link points outside the destination folder");
+                    return Ok(());
+                };
+                let inside = fs::canonicalize(&source).map(|s| s.starts_with(&self.root)).unwrap_or(false);
+                if !inside {
+                    self.skip(&name, "Hard link target was not extracted");
+                    return Ok(());
+                }
+                let Some(target) = self.resolve_conflict(target).map_err(|e| e.to_string())? else {
+                    self.skip(&name, "Already exists");
+                    return Ok(());
+                };
+                // A copy instead of a link, so the extracted files stay independent.
+                if let Err(e) = fs::copy(&source, &target) {
+                    self.skip(&name, e.to_string());
+                    return Ok(());
+                }
+            }
+        }
+        self.state.processed_items += 1;
+        self.summary.extracted += 1;
+        (self.progress)(&self.state, false);
+        Ok(())
+    }
+
+    /// Streams one file in chunks. Returns `Ok(false)` when cancelled.
+    fn write_file(&mut self, target: &Path, reader: &mut dyn Read) -> io::Result<bool> {
+        let mut out = io::BufWriter::new(File::create(target)?);
+        let mut buffer = vec![0u8; COPY_CHUNK_BYTES];
+        loop {
+            if self.cancelled() {
+                return Ok(false);
+            }
+            let n = reader.read(&mut buffer)?;
+            if n == 0 {
+                break;
+            }
+            out.write_all(&buffer[..n])?;
+            match &self.consumed {
+                Some(consumed) => self.state.processed_bytes = consumed.load(Ordering::Relaxed),
+                None => self.state.processed_bytes += n as u64,
+            }
+            (self.progress)(&self.state, false);
+        }
+        out.flush()?;
+        Ok(true)
+    }
+
+    fn run_zip(&mut self, archive: &Path) -> Result<(), ArchiveError> {
+        let file = BufReader::new(File::open(archive).map_err(|e| e.to_string())?);
+        let mut zip = ZipArchive::new(file).map_err(zip_error)?;
+
+        for i in 0..zip.len() {
+            let entry = zip.by_index_raw(i).map_err(|e| e.to_string())?;
+            if !entry.is_dir() && self.is_selected(&normalize_entry_name(entry.name())) {
+                self.state.total_bytes += entry.size();
+            }
+        }
+
+        for i in 0..zip.len() {
+            if self.cancelled() {
+                break;
+            }
+            let name = zip.name_for_index(i).unwrap_or_default().to_string();
+            if !self.is_selected(&normalize_entry_name(&name)) {
+                continue;
+            }
+            let encrypted = zip.by_index_raw(i).map(|e| e.encrypted()).unwrap_or(false);
+            if encrypted {
+                // Checked before anything is written, so a wrong password never leaves a partial file.
+                let Job { password, ask, .. } = self;
+                with_password(password, &mut **ask, |pw| open_zip_entry(&mut zip, i, pw).map(|_| ()))?;
+            }
+            let password = if encrypted { self.password.as_deref().map(String::as_str) } else { None };
+            let mut entry = match open_zip_entry(&mut zip, i, password) {
+                Ok(entry) => entry,
+                Err(e) => {
+                    self.skip(&name, e.to_string());
+                    continue;
+                }
+            };
+            let meta = EntryMeta {
+                name: entry.name().to_string(),
+                mode: entry.unix_mode(),
+                mtime: entry.last_modified().and_then(zip_mtime),
+            };
+            if entry.is_dir() {
+                self.extract_entry(meta, EntryKind::Dir)?;
+            } else if entry.is_symlink() {
+                let mut link = String::new();
+                entry.read_to_string(&mut link).map_err(|e| e.to_string())?;
+                self.extract_entry(meta, EntryKind::Symlink(PathBuf::from(link)))?;
+            } else {
+                self.extract_entry(meta, EntryKind::File(&mut entry))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Opens the archive for streaming, measuring progress on the archive bytes consumed so
+    /// no pre-pass over a compressed stream is needed.
+    fn counted_stream(&mut self, archive: &Path) -> Result<Box<dyn Read>, String> {
+        let file = File::open(archive).map_err(|e| e.to_string())?;
+        self.state.total_bytes = file.metadata().map(|m| m.len()).unwrap_or(0);
+        let consumed = Arc::new(AtomicU64::new(0));
+        self.consumed = Some(consumed.clone());
+        Ok(Box::new(CountingReader { inner: BufReader::new(file), count: consumed }))
+    }
+
+    fn run_7z(&mut self, archive: &Path) -> Result<(), ArchiveError> {
+        let (sevenz, mut file) = open_7z(archive)?;
+        self.state.total_bytes = sevenz
+            .files
+            .iter()
+            .filter(|e| !e.is_directory && self.is_selected(&normalize_entry_name(&sevenz_entry_path(e))))
+            .map(|e| e.size)
+            .sum();
+
+        for_each_7z_entry(&sevenz, &mut file, |entry, reader| {
+            if self.cancelled() {
+                return Ok(false);
+            }
+            if entry.is_anti_item {
+                return Ok(true);
+            }
+            let meta = EntryMeta {
+                name: sevenz_entry_path(entry),
+                mode: sevenz_unix_mode(entry),
+                mtime: sevenz_mtime(entry),
+            };
+            if entry.is_directory {
+                self.extract_entry(meta, EntryKind::Dir)?;
+            } else if sevenz_is_symlink(entry) {
+                let mut link = String::new();
+                reader.read_to_string(&mut link).map_err(|e| e.to_string())?;
+                self.extract_entry(meta, EntryKind::Symlink(PathBuf::from(link)))?;
+            } else {
+                self.extract_entry(meta, EntryKind::File(reader))?;
+            }
+            Ok(!self.cancelled())
+        })
+    }
+
+    fn run_compressed(&mut self, archive: &Path, codec: Codec) -> Result<(), ArchiveError> {
+        let mtime = fs::metadata(archive)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs());
+        let mut reader = decompress(self.counted_stream(archive)?, codec).map_err(|e| e.to_string())?;
+        let meta = EntryMeta { name: compressed_entry_name(archive), mode: None, mtime };
+        Ok(self.extract_entry(meta, EntryKind::File(&mut reader))?)
+    }
+
+    fn run_tar(&mut self, archive: &Path, codec: Option<Codec>) -> Result<(), ArchiveError> {
+        let stream = self.counted_stream(archive)?;
+        let consumed = self.consumed.clone().unwrap_or_default();
+        let mut tar = tar_from_reader(stream, codec)?;
+
+        for entry in tar.entries().map_err(|e| e.to_string())? {
+            if self.cancelled() {
+                break;
+            }
+            let mut entry = entry.map_err(|e| e.to_string())?;
+            let name = entry.path().map_err(|e| e.to_string())?.to_string_lossy().to_string();
+            let header = entry.header();
+            let entry_type = header.entry_type();
+            let meta = EntryMeta { name: name.clone(), mode: header.mode().ok(), mtime: header.mtime().ok() };
+
+            if entry_type.is_dir() {
+                self.extract_entry(meta, EntryKind::Dir)?;
+            } else if entry_type.is_symlink() {
+                match entry.link_name() {
+                    Ok(Some(link)) => {
+                        let link = link.to_path_buf();
+                        self.extract_entry(meta, EntryKind::Symlink(link))?;
+                    }
+                    _ => self.skip(&name, "Symlink without a target"),
+                }
+            } else if entry_type.is_hard_link() {
+                match entry.link_name() {
+                    Ok(Some(link)) => {
+                        let link = link.to_string_lossy().to_string();
+                        self.extract_entry(meta, EntryKind::HardLink(link))?;
+                    }
+                    _ => self.skip(&name, "Hard link without a target"),
+                }
+            } else if entry_type.is_file() {
+                self.extract_entry(meta, EntryKind::File(&mut entry))?;
+            } else if !entry_type.is_pax_global_extensions() && !entry_type.is_pax_local_extensions() {
+                // Devices, FIFOs and the like are never created.
+                self.skip(&name, "Unsupported entry type");
+            }
+            self.state.processed_bytes = consumed.load(Ordering::Relaxed);
+        }
+        Ok(())
+    }
+}
+
+struct CountingReader<R> {
+    inner: R,
+    count: Arc<AtomicU64>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count.fetch_add(n as u64, Ordering::Relaxed);
+        Ok(n)
+    }
+}
+
+#[cfg(unix)]
+fn set_permissions(path: &Path, mode: Option<u32>) {
+    use std::os::unix::fs::PermissionsExt;
+    if let Some(mode) = mode {
+        let _ = fs::set_permissions(path, fs::Permissions::from_mode(mode));
+    }
+}
+
+#[cfg(not(unix))]
+fn set_permissions(_path: &Path, _mode: Option<u32>) {}
+
+#[cfg(unix)]
+fn create_symlink(link: &Path, target: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(link, target)
+}
+
+#[cfg(not(unix))]
+fn create_symlink(_link: &Path, _target: &Path) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "Symlinks are not extracted on this platform"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::archive_password::no_password;
+
+    #[test]
+    fn sanitize_entry_path_drops_roots_and_rejects_parent_dirs() {
+        assert_eq!(sanitize_entry_path("a/b.txt"), Some(PathBuf::from("a/b.txt")));
+        assert_eq!(sanitize_entry_path("/etc/passwd"), Some(PathBuf::from("etc/passwd")));
+        assert_eq!(sanitize_entry_path("./a/./b"), Some(PathBuf::from("a/b")));
+        assert_eq!(sanitize_entry_path("a\\b"), Some(PathBuf::from("a/b")));
+        assert_eq!(sanitize_entry_path("../evil"), None);
+        assert_eq!(sanitize_entry_path("a/../../evil"), None);
+        assert_eq!(sanitize_entry_path("a\\..\\evil"), None);
+        assert_eq!(sanitize_entry_path("/"), None);
+    }
+
+    #[test]
+    fn normalize_lexically_resolves_dots() {
+        assert_eq!(normalize_lexically(Path::new("/d/a/../b/./c")), PathBuf::from("/d/b/c"));
+        assert_eq!(normalize_lexically(Path::new("/d/a/../../..")), PathBuf::from("/"));
+        assert_eq!(normalize_lexically(Path::new("a/./b")), PathBuf::from("a/b"));
+    }
+
+    /// A tar of `(path, Some(link target))` symlinks and `(path, None)` one-byte files.
+    #[cfg(unix)]
+    fn write_tar(path: &Path, entries: &[(&str, Option<&str>)]) {
+        let mut builder = tar::Builder::new(File::create(path).unwrap());
+        for (name, link) in entries {
+            let mut header = tar::Header::new_gnu();
+            header.set_mode(0o755);
+            match link {
+                Some(link) => {
+                    header.set_entry_type(tar::EntryType::Symlink);
+                    header.set_size(0);
+                    builder.append_link(&mut header, name, link).unwrap();
+                }
+                None => {
+                    header.set_entry_type(tar::EntryType::Regular);
+                    header.set_size(1);
+                    builder.append_data(&mut header, name, &b"x"[..]).unwrap();
+                }
+            }
+        }
+        builder.finish().unwrap();
+    }
+
+    /// Extracts the entries into `<tmp>/<case>/dest`; returns the case folder and the summary.
+    #[cfg(unix)]
+    fn extract_case(case: &str, entries: &[(&str, Option<&str>)]) -> (PathBuf, ExtractSummary) {
+        let base = std::env::temp_dir().join(format!("extract-test-{}-{}", std::process::id(), case));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base).unwrap();
+        let archive = base.join("attack.tar");
+        write_tar(&archive, entries);
+        let options = ExtractOptions {
+            destination: Some(base.join("dest").to_string_lossy().to_string()),
+            entries: None,
+            conflict_policy: ConflictPolicy::Overwrite,
+        };
+        let summary =
+            extract_blocking(&archive, &options, &AtomicBool::new(false), &mut |_, _| {}, &mut no_password).unwrap();
+        (base, summary)
+    }
+
+    #[cfg(unix)]
+    fn skipped(summary: &ExtractSummary) -> Vec<&str> {
+        summary.skipped.iter().map(|s| s.path.as_str()).collect()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlink_chain_cannot_escape_through_parent_dir() {
+        let (base, summary) =
+            extract_case("chain", &[("a", Some(".")), ("b", Some("a/..")), ("b/x/y/file", None)]);
+        assert_eq!(skipped(&summary), ["b"]);
+        assert!(!base.join("x").exists());
+        let b = fs::symlink_metadata(base.join("dest/b")).unwrap();
+        assert!(b.is_dir());
+        assert!(base.join("dest/b/x/y/file").is_file());
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlink_through_skipped_link_cannot_escape() {
+        let (base, summary) = extract_case(
+            "nested",
+            &[("a", Some(".")), ("b", Some("a/..")), ("c", Some("b/..")), ("c/file", None)],
+        );
+        assert_eq!(skipped(&summary), ["b", "c"]);
+        assert!(!base.join("file").exists());
+        assert!(base.join("dest/c/file").is_file());
+        fs::remove_dir_all(&base).unwrap();
+    }
+}
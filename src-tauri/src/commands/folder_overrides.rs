@@ -0,0 +1,213 @@
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+use super::settings::{get_settings_path, write_atomic, ConfigSection};
+
+/// Stored next to settings.json.
+const OVERRIDES_FILE: &str = "folder_overrides.json";
+
+lazy_static! {
+    /// Overrides keyed by canonical folder path; loaded on first use.
+    static ref OVERRIDES: Mutex<Option<BTreeMap<String, FolderOverride>>> = Mutex::new(None);
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SortField {
+    #[default]
+    Name,
+    Size,
+    Modified,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ViewMode {
+    List,
+    Grid,
+}
+
+/// View and filter settings for one folder; unset fields fall through to the parent
+/// override (when it inherits) and then to the explorer settings.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct FolderOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort_by: Option<SortField>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<SortOrder>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub show_hidden_files: Option<bool>,
+    /// Replaces the explorer's blocked extensions inside this folder.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blocked_extensions: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub view_mode: Option<ViewMode>,
+    /// Also applies to subfolders, below any override they have of their own.
+    #[serde(default)]
+    pub inherit: bool,
+}
+
+impl FolderOverride {
+    fn is_empty(&self) -> bool {
+        self.sort_by.is_none()
+            && self.order.is_none()
+            && self.show_hidden_files.is_none()
+            && self.blocked_extensions.is_none()
+            && self.view_mode.is_none()
+    }
+
+    /// Fields set in `other` replace ours.
+    fn overlay(&mut self, other: &FolderOverride) {
+        self.sort_by = other.sort_by.or(self.sort_by);
+        self.order = other.order.or(self.order);
+        self.show_hidden_files = other.show_hidden_files.or(self.show_hidden_files);
+        if other.blocked_extensions.is_some() {
+            self.blocked_extensions = other.blocked_extensions.clone();
+        }
+        self.view_mode = other.view_mode.or(self.view_mode);
+    }
+
+    /// Applies the filter fields to the section a listing runs with.
+    pub fn apply_to(&self, section: &mut ConfigSection) {
+        if let Some(show) = self.show_hidden_files {
+            section.show_hidden_files = show;
+        }
+        if let Some(blocked) = &self.blocked_extensions {
+            section.blocked_extensions = blocked.clone();
+        }
+    }
+}
+
+/// What applies to a folder and where it came from.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct ResolvedOverride {
+    /// The folder's own override, if it has one.
+    pub own: Option<FolderOverride>,
+    /// Own and inherited overrides combined, nearest folder winning field by field.
+    pub effective: FolderOverride,
+    /// Nearest folder that contributed to `effective`.
+    pub source: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct OverridesFile {
+    #[serde(default)]
+    folders: BTreeMap<String, FolderOverride>,
+}
+
+fn overrides_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(get_settings_path(app)?.with_file_name(OVERRIDES_FILE))
+}
+
+/// The key a folder is stored under; falls back to the path as given if it can't be resolved.
+fn folder_key(path: &Path) -> String {
+    fs::canonicalize(path)
+        .unwrap_or_else(|_| path.to_path_buf())
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Runs `f` on the loaded overrides. An unreadable file is set aside as
+/// `folder_overrides.corrupt.json` and replaced by an empty set.
+fn with_overrides<T>(
+    app: &AppHandle,
+    f: impl FnOnce(&mut BTreeMap<String, FolderOverride>) -> T,
+) -> Result<T, String> {
+    let mut guard = OVERRIDES.lock().unwrap();
+    if guard.is_none() {
+        let path = overrides_path(app)?;
+        let folders = match fs::read_to_string(&path) {
+            Ok(content) => match serde_json::from_str::<OverridesFile>(&content) {
+                Ok(file) => file.folders,
+                Err(_) => {
+                    let _ = fs::rename(&path, path.with_file_name("folder_overrides.corrupt.json"));
+                    BTreeMap::new()
+                }
+            },
+            Err(_) => BTreeMap::new(),
+        };
+        *guard = Some(folders);
+    }
+    Ok(f(guard.as_mut().unwrap()))
+}
+
+/// Writes the overrides file; call with the [OVERRIDES] lock held.
+fn save_overrides(app: &AppHandle, folders: &BTreeMap<String, FolderOverride>) -> Result<(), String> {
+    let file = OverridesFile { folders: folders.clone() };
+    let content = serde_json::to_string_pretty(&file).map_err(|e| e.to_string())?;
+    write_atomic(&overrides_path(app)?, &content).map_err(|e| format!("Failed to save folder overrides: {e}"))
+}
+
+/// Combines the folder's own override with inheriting ones from its ancestors.
+pub(crate) fn resolve_folder_override(app: &AppHandle, path: &Path) -> Result<ResolvedOverride, String> {
+    let key = folder_key(path);
+    with_overrides(app, |folders| {
+        let mut resolved = ResolvedOverride::default();
+        let ancestors: Vec<&Path> = Path::new(&key).ancestors().collect();
+        for folder in ancestors.into_iter().rev() {
+            let folder_key = folder.to_string_lossy();
+            let Some(found) = folders.get(folder_key.as_ref()) else { continue };
+            let own = folder_key == key;
+            if own || found.inherit {
+                resolved.effective.overlay(found);
+                resolved.source = Some(folder_key.into_owned());
+            }
+            if own {
+                resolved.own = Some(found.clone());
+            }
+        }
+        resolved
+    })
+}
+
+#[tauri::command]
+pub fn get_folder_override(app: AppHandle, path: String) -> Result<ResolvedOverride, String> {
+    resolve_folder_override(&app, Path::new(&path))
+}
+
+/// Stores the override for `path`; `None` or an override with no fields set removes it.
+#[tauri::command]
+pub fn set_folder_override(
+    app: AppHandle,
+    path: String,
+    folder_override: Option<FolderOverride>,
+) -> Result<(), String> {
+    let key = folder_key(Path::new(&path));
+    let folder_override = folder_override.filter(|o| !o.is_empty()).map(|mut o| {
+        if let Some(blocked) = o.blocked_extensions.as_mut() {
+            let mut seen = HashSet::new();
+            blocked.retain_mut(|ext| {
+                *ext = ext.trim().trim_start_matches('.').to_lowercase();
+                !ext.is_empty() && seen.insert(ext.clone())
+            });
+        }
+        o
+    });
+
+    // Saved under the lock, so concurrent updates can't write their snapshots out of order.
+    with_overrides(&app, |folders| {
+        match folder_override {
+            Some(o) => folders.insert(key, o),
+            None => folders.remove(&key),
+        };
+        save_overrides(&app, folders)
+    })?
+}
+
+#[tauri::command]
+pub fn list_folder_overrides(app: AppHandle) -> Result<BTreeMap<String, FolderOverride>, String> {
+    with_overrides(&app, |folders| folders.clone())
+}
@@ -0,0 +1,180 @@
+use memmap2::Mmap;
+use serde::Serialize;
+use std::fs::File;
+use std::path::Path;
+use std::sync::atomic::Ordering;
+
+use crate::commands::operation::{register_operation_with_paths, unregister_operation};
+use crate::utils::magic::{detect_bytes, MagicType};
+
+const DEFAULT_BYTES_PER_ROW: usize = 16;
+const MAX_BYTES_PER_ROW: usize = 64;
+/// Upper bound for one `read_hex_range` call; the viewer pages through larger files.
+const MAX_HEX_RANGE_BYTES: usize = 1024 * 1024; // 1 MB
+const DEFAULT_MAX_MATCHES: usize = 1_000;
+/// Search works through the map in windows this size, checking for cancellation in between.
+const SEARCH_WINDOW_BYTES: usize = 64 * 1024 * 1024; // 64 MB
+
+#[derive(Debug, Serialize, Clone)]
+pub struct HexRow {
+    pub offset: u64,
+    /// Space-separated byte pairs, e.g. "89 50 4e 47".
+    pub hex: String,
+    /// Printable ASCII, with `.` for everything else.
+    pub ascii: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct HexChunk {
+    pub rows: Vec<HexRow>,
+    /// Offset of the first row (the requested offset rounded down to a row boundary).
+    pub offset: u64,
+    pub next_offset: u64,
+    pub file_size: u64,
+    /// Type detected from the file's magic number, independent of its extension.
+    pub detected_type: Option<MagicType>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct HexSearchResult {
+    /// Offsets where the pattern starts, ascending.
+    pub matches: Vec<u64>,
+    /// Where to resume when `truncated` (results hit `max_matches`).
+    pub next_offset: u64,
+    pub truncated: bool,
+    pub cancelled: bool,
+}
+
+fn map_file(p: &Path) -> Result<Option<Mmap>, String> {
+    let file = File::open(p).map_err(|e| format!("Failed to open file: {}", e))?;
+    let meta = file.metadata().map_err(|e| format!("Failed to read file: {}", e))?;
+    if !meta.is_file() {
+        return Err("Not a file".to_string());
+    }
+    if meta.len() == 0 {
+        return Ok(None);
+    }
+    // SAFETY: read-only view; see `text_preview::map_file`.
+    unsafe { Mmap::map(&file) }
+        .map(Some)
+        .map_err(|e| format!("Failed to map file: {}", e))
+}
+
+fn hex_row(offset: usize, bytes: &[u8]) -> HexRow {
+    HexRow {
+        offset: offset as u64,
+        hex: bytes.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(" "),
+        ascii: bytes
+            .iter()
+            .map(|b| if b.is_ascii_graphic() || *b == b' ' { *b as char } else { '.' })
+            .collect(),
+    }
+}
+
+/// Rows of hex + ASCII starting at `offset` (jump-to-offset is just a different `offset`).
+/// Files of any size are supported; only the requested window is touched.
+#[tauri::command]
+pub async fn read_hex_range(
+    path: String,
+    offset: u64,
+    length: usize,
+    bytes_per_row: Option<usize>,
+) -> Result<HexChunk, String> {
+    tokio::task::spawn_blocking(move || {
+        let map = map_file(Path::new(&path))?;
+        let bytes: &[u8] = map.as_deref().unwrap_or(&[]);
+        let per_row = bytes_per_row.unwrap_or(DEFAULT_BYTES_PER_ROW).clamp(1, MAX_BYTES_PER_ROW);
+
+        let start = (offset.min(bytes.len() as u64) as usize / per_row) * per_row;
+        let end = start.saturating_add(length.clamp(1, MAX_HEX_RANGE_BYTES)).min(bytes.len());
+        let rows = bytes[start..end]
+            .chunks(per_row)
+            .enumerate()
+            .map(|(i, chunk)| hex_row(start + i * per_row, chunk))
+            .collect();
+
+        Ok(HexChunk {
+            rows,
+            offset: start as u64,
+            next_offset: end as u64,
+            file_size: bytes.len() as u64,
+            detected_type: detect_bytes(bytes),
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Parses "89 50 4E 47", "89504e47" or "0x89 0x50"; whitespace and commas are ignored.
+fn parse_hex_pattern(pattern: &str) -> Result<Vec<u8>, String> {
+    let digits: String = pattern
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .map(|part| part.trim_start_matches("0x").trim_start_matches("0X"))
+        .collect();
+    if digits.is_empty() || !digits.len().is_multiple_of(2) {
+        return Err("Hex pattern must contain an even number of hex digits".to_string());
+    }
+    hex::decode(&digits).map_err(|e| format!("Invalid hex pattern: {}", e))
+}
+
+/// Finds `pattern` (hex bytes, or literal text when `as_text` is set) from `start_offset`.
+/// Long searches can be cancelled with `cancel_operation(operation_id)`.
+#[tauri::command]
+pub async fn search_hex(
+    operation_id: String,
+    path: String,
+    pattern: String,
+    as_text: Option<bool>,
+    start_offset: Option<u64>,
+    max_matches: Option<usize>,
+) -> Result<HexSearchResult, String> {
+    let needle = if as_text.unwrap_or(false) {
+        pattern.into_bytes()
+    } else {
+        parse_hex_pattern(&pattern)?
+    };
+    if needle.is_empty() {
+        return Err("Search pattern is empty".to_string());
+    }
+
+    let cancel_flag = register_operation_with_paths(operation_id.clone(), [&path]);
+    let result = tokio::task::spawn_blocking(move || {
+        let map = map_file(Path::new(&path))?;
+        let bytes: &[u8] = map.as_deref().unwrap_or(&[]);
+        let max_matches = max_matches.unwrap_or(DEFAULT_MAX_MATCHES).max(1);
+        let finder = memchr::memmem::Finder::new(&needle);
+
+        let mut matches = Vec::new();
+        let mut pos = start_offset.unwrap_or(0).min(bytes.len() as u64) as usize;
+        while pos < bytes.len() {
+            if cancel_flag.load(Ordering::Relaxed) {
+                return Ok(HexSearchResult { matches, next_offset: pos as u64, truncated: false, cancelled: true });
+            }
+            // Windows overlap by the pattern length so matches across a boundary aren't missed.
+            let window_end = pos.saturating_add(SEARCH_WINDOW_BYTES).min(bytes.len());
+            let search_end = (window_end + needle.len() - 1).min(bytes.len());
+            for found in finder.find_iter(&bytes[pos..search_end]) {
+                let at = pos + found;
+                if at >= window_end {
+                    break;
+                }
+                matches.push(at as u64);
+                if matches.len() >= max_matches {
+                    return Ok(HexSearchResult {
+                        matches,
+                        next_offset: at as u64 + 1,
+                        truncated: true,
+                        cancelled: false,
+                    });
+                }
+            }
+            pos = window_end;
+        }
+        Ok(HexSearchResult { matches, next_offset: bytes.len() as u64, truncated: false, cancelled: false })
+    })
+    .await
+    .map_err(|e| e.to_string());
+
+    unregister_operation(&operation_id);
+    result?
+}
@@ -0,0 +1,491 @@
+use lazy_static::lazy_static;
+use serde::Serialize;
+use serde_json::Value;
+use std::io::Read;
+use std::path::Path;
+use std::process::Stdio;
+use std::time::UNIX_EPOCH;
+
+use crate::commands::thumbnails::renderer_paths_to_try;
+use crate::utils::file_types::{get_file_category, FileCategory};
+use crate::utils::image_decode::read_exif;
+
+const METADATA_CACHE_ENTRIES: u64 = 4096;
+/// The PDF fallback parser only looks at this much of the file.
+const MAX_PDF_SCAN_BYTES: u64 = 64 * 1024 * 1024; // 64 MB
+/// Office metadata parts larger than this are skipped.
+const MAX_ZIP_METADATA_BYTES: u64 = 4 * 1024 * 1024; // 4 MB
+/// Document info keys read by the PDF fallback parser.
+const PDF_INFO_KEYS: [&str; 7] = ["Title", "Author", "Subject", "Creator", "Producer", "CreationDate", "ModDate"];
+
+lazy_static! {
+    /// Keyed by path + size + mtime, so edited files are re-read.
+    static ref METADATA_CACHE: moka::sync::Cache<String, FileMetadata> =
+        moka::sync::Cache::new(METADATA_CACHE_ENTRIES);
+    /// The root /Pages node has the largest /Count.
+    static ref PDF_PAGE_COUNT_RE: regex::Regex =
+        regex::Regex::new(r"/Type\s*/Pages\b[^>]*?/Count\s+(\d+)|/Count\s+(\d+)[^>]*?/Type\s*/Pages\b").unwrap();
+    static ref PDF_INFO_RES: std::collections::HashMap<&'static str, regex::Regex> = PDF_INFO_KEYS
+        .iter()
+        .map(|&key| (key, regex::Regex::new(&format!(r"/{}\s*\(((?:\\.|[^\\)])*)\)", key)).unwrap()))
+        .collect();
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct FileMetadata {
+    pub path: String,
+    pub category: FileCategory,
+    pub size: u64,
+    pub modified: Option<u64>,
+    pub image: Option<ImageMetadata>,
+    pub media: Option<MediaMetadata>,
+    pub document: Option<DocumentMetadata>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct ImageMetadata {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub camera_make: Option<String>,
+    pub camera_model: Option<String>,
+    pub lens_model: Option<String>,
+    /// `DateTimeOriginal` as `YYYY-MM-DDTHH:MM:SS`, with offset when the camera recorded one.
+    pub captured_at: Option<String>,
+    pub exposure_time: Option<String>,
+    pub f_number: Option<f64>,
+    pub iso: Option<u32>,
+    pub focal_length_mm: Option<f64>,
+    pub orientation: Option<u32>,
+    pub gps: Option<GpsPosition>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct GpsPosition {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct MediaMetadata {
+    pub container: Option<String>,
+    pub duration_secs: Option<f64>,
+    /// Overall bitrate in bits per second.
+    pub bitrate: Option<u64>,
+    pub streams: Vec<MediaStream>,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub album_artist: Option<String>,
+    pub genre: Option<String>,
+    pub year: Option<String>,
+    pub track: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct MediaStream {
+    /// "video", "audio", "subtitle", ...
+    pub kind: String,
+    pub codec: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub frame_rate: Option<f64>,
+    pub sample_rate: Option<u32>,
+    pub channels: Option<u32>,
+    pub bitrate: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct DocumentMetadata {
+    pub page_count: Option<u32>,
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub subject: Option<String>,
+    /// Producing application, e.g. "Microsoft Office Word".
+    pub application: Option<String>,
+    pub created: Option<String>,
+    pub modified: Option<String>,
+    pub word_count: Option<u32>,
+}
+
+/// Category-specific metadata for the Inspector: EXIF for images, ffprobe for audio/video,
+/// document properties for PDF and Office files. Sections that can't be read are `null`.
+#[tauri::command]
+pub async fn get_file_metadata(path: String) -> Result<FileMetadata, String> {
+    tokio::task::spawn_blocking(move || read_metadata(Path::new(&path)))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+fn read_metadata(p: &Path) -> Result<FileMetadata, String> {
+    let meta = std::fs::metadata(p).map_err(|e| format!("Failed to read file metadata: {}", e))?;
+    if !meta.is_file() {
+        return Err("Not a file".to_string());
+    }
+    let mtime = meta.modified().ok().and_then(|t| t.duration_since(UNIX_EPOCH).ok());
+    let key = format!(
+        "{}\0{}\0{}",
+        p.to_string_lossy(),
+        meta.len(),
+        mtime.map(|d| d.as_nanos()).unwrap_or(0)
+    );
+    if let Some(cached) = METADATA_CACHE.get(&key) {
+        return Ok(cached);
+    }
+
+    let ext = p.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+    let category = get_file_category(&ext);
+    let mut result = FileMetadata {
+        path: p.to_string_lossy().to_string(),
+        category,
+        size: meta.len(),
+        modified: mtime.map(|d| d.as_secs()),
+        image: None,
+        media: None,
+        document: None,
+    };
+    match category {
+        FileCategory::Image => result.image = Some(image_metadata(p)),
+        FileCategory::Audio | FileCategory::Video => {
+            result.media = ffprobe(p).or_else(|| (category == FileCategory::Audio).then(|| id3_metadata(p)).flatten())
+        }
+        FileCategory::Document => result.document = document_metadata(p, &ext),
+        _ => {}
+    }
+
+    METADATA_CACHE.insert(key, result.clone());
+    Ok(result)
+}
+
+// ── Images ───────────────────────────────────────────────────────────────────
+
+fn image_metadata(p: &Path) -> ImageMetadata {
+    let mut out = ImageMetadata::default();
+    if let Ok((w, h)) = image::image_dimensions(p) {
+        out.width = Some(w);
+        out.height = Some(h);
+    }
+    let Some(exif) = read_exif(p) else { return out };
+    use exif::{In, Tag};
+
+    let text = |tag: Tag| exif_text(&exif, tag);
+    let rational = |tag: Tag| {
+        exif.get_field(tag, In::PRIMARY).and_then(|f| match &f.value {
+            exif::Value::Rational(v) => v.first().map(|r| r.to_f64()),
+            _ => None,
+        })
+    };
+    let uint = |tag: Tag| exif.get_field(tag, In::PRIMARY).and_then(|f| f.value.get_uint(0));
+
+    out.camera_make = text(Tag::Make);
+    out.camera_model = text(Tag::Model);
+    out.lens_model = text(Tag::LensModel);
+    out.captured_at = exif_datetime(&exif, Tag::DateTimeOriginal, Tag::OffsetTimeOriginal)
+        .or_else(|| exif_datetime(&exif, Tag::DateTime, Tag::OffsetTime));
+    out.exposure_time = exif
+        .get_field(Tag::ExposureTime, In::PRIMARY)
+        .map(|f| f.display_value().to_string());
+    out.f_number = rational(Tag::FNumber);
+    out.iso = uint(Tag::PhotographicSensitivity);
+    out.focal_length_mm = rational(Tag::FocalLength);
+    out.orientation = uint(Tag::Orientation);
+    // RAWs have no header the image crate can read; fall back to the EXIF dimensions.
+    if out.width.is_none() {
+        out.width = uint(Tag::PixelXDimension);
+        out.height = uint(Tag::PixelYDimension);
+    }
+    out.gps = gps_position(&exif);
+    out
+}
+
+fn exif_text(exif: &exif::Exif, tag: exif::Tag) -> Option<String> {
+    let field = exif.get_field(tag, exif::In::PRIMARY)?;
+    let exif::Value::Ascii(values) = &field.value else { return None };
+    let s = String::from_utf8_lossy(values.first()?).trim_matches(|c: char| c == '\0' || c.is_whitespace()).to_string();
+    (!s.is_empty()).then_some(s)
+}
+
+fn exif_datetime(exif: &exif::Exif, tag: exif::Tag, offset_tag: exif::Tag) -> Option<String> {
+    let field = exif.get_field(tag, exif::In::PRIMARY)?;
+    let exif::Value::Ascii(values) = &field.value else { return None };
+    let dt = exif::DateTime::from_ascii(values.first()?).ok()?;
+    let offset = exif_text(exif, offset_tag).unwrap_or_default();
+    Some(format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{}",
+        dt.year, dt.month, dt.day, dt.hour, dt.minute, dt.second, offset
+    ))
+}
+
+fn gps_position(exif: &exif::Exif) -> Option<GpsPosition> {
+    use exif::{In, Tag};
+    let degrees = |tag: Tag, ref_tag: Tag, negative: &str| -> Option<f64> {
+        let exif::Value::Rational(parts) = &exif.get_field(tag, In::PRIMARY)?.value else { return None };
+        let [d, m, s] = parts.get(..3)? else { return None };
+        let value = d.to_f64() + m.to_f64() / 60.0 + s.to_f64() / 3600.0;
+        let sign = if exif_text(exif, ref_tag).as_deref() == Some(negative) { -1.0 } else { 1.0 };
+        value.is_finite().then_some(sign * value)
+    };
+    let latitude = degrees(Tag::GPSLatitude, Tag::GPSLatitudeRef, "S")?;
+    let longitude = degrees(Tag::GPSLongitude, Tag::GPSLongitudeRef, "W")?;
+    let altitude = exif.get_field(Tag::GPSAltitude, In::PRIMARY).and_then(|f| match &f.value {
+        exif::Value::Rational(v) => v.first().map(|r| {
+            // GPSAltitudeRef 1 = below sea level.
+            let below = exif.get_field(Tag::GPSAltitudeRef, In::PRIMARY).and_then(|r| r.value.get_uint(0)) == Some(1);
+            if below { -r.to_f64() } else { r.to_f64() }
+        }),
+        _ => None,
+    });
+    Some(GpsPosition { latitude, longitude, altitude })
+}
+
+// ── Audio / video ────────────────────────────────────────────────────────────
+
+fn ffprobe(p: &Path) -> Option<MediaMetadata> {
+    let path = p.to_string_lossy();
+    let args = ["-v", "quiet", "-print_format", "json", "-show_format", "-show_streams", path.as_ref()];
+    let output = renderer_paths_to_try("ffprobe").into_iter().find_map(|bin| {
+        std::process::Command::new(bin)
+            .args(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+    })?;
+    let json: Value = serde_json::from_slice(&output.stdout).ok()?;
+
+    let num = |v: &Value| -> Option<f64> {
+        v.as_f64().or_else(|| v.as_str().and_then(|s| s.parse().ok()))
+    };
+    let format = &json["format"];
+    // Tag keys vary in case between containers (ID3 "TITLE" vs MP4 "title").
+    let tag = |name: &str| -> Option<String> {
+        let tags = format["tags"].as_object()?;
+        tags.iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .and_then(|(_, v)| v.as_str())
+            .map(|s| s.to_string())
+    };
+
+    let streams = json["streams"]
+        .as_array()
+        .map(|streams| {
+            streams
+                .iter()
+                .map(|s| MediaStream {
+                    kind: s["codec_type"].as_str().unwrap_or("unknown").to_string(),
+                    codec: s["codec_name"].as_str().map(|c| c.to_string()),
+                    width: s["width"].as_u64().map(|v| v as u32),
+                    height: s["height"].as_u64().map(|v| v as u32),
+                    frame_rate: s["avg_frame_rate"].as_str().and_then(parse_ratio),
+                    sample_rate: num(&s["sample_rate"]).map(|v| v as u32),
+                    channels: s["channels"].as_u64().map(|v| v as u32),
+                    bitrate: num(&s["bit_rate"]).map(|v| v as u64),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Some(MediaMetadata {
+        container: format["format_long_name"].as_str().or(format["format_name"].as_str()).map(|s| s.to_string()),
+        duration_secs: num(&format["duration"]),
+        bitrate: num(&format["bit_rate"]).map(|v| v as u64),
+        streams,
+        title: tag("title"),
+        artist: tag("artist"),
+        album: tag("album"),
+        album_artist: tag("album_artist"),
+        genre: tag("genre"),
+        year: tag("date").or_else(|| tag("year")),
+        track: tag("track"),
+    })
+}
+
+/// "30000/1001" -> 29.97; "0/0" (unknown) -> None.
+fn parse_ratio(s: &str) -> Option<f64> {
+    let (n, d) = s.split_once('/')?;
+    let (n, d): (f64, f64) = (n.parse().ok()?, d.parse().ok()?);
+    (d != 0.0 && n != 0.0).then(|| n / d)
+}
+
+/// Tag-only fallback when ffprobe isn't installed.
+fn id3_metadata(p: &Path) -> Option<MediaMetadata> {
+    use id3::TagLike;
+    let tag = id3::Tag::read_from_path(p).ok()?;
+    Some(MediaMetadata {
+        duration_secs: tag.duration().map(|ms| ms as f64 / 1000.0),
+        title: tag.title().map(|s| s.to_string()),
+        artist: tag.artist().map(|s| s.to_string()),
+        album: tag.album().map(|s| s.to_string()),
+        album_artist: tag.album_artist().map(|s| s.to_string()),
+        genre: tag.genre_parsed().map(|s| s.to_string()),
+        year: tag.year().map(|y| y.to_string()),
+        track: tag.track().map(|t| t.to_string()),
+        ..Default::default()
+    })
+}
+
+// ── Documents ────────────────────────────────────────────────────────────────
+
+fn document_metadata(p: &Path, ext: &str) -> Option<DocumentMetadata> {
+    match ext {
+        "pdf" => pdfinfo(p).or_else(|| pdf_fallback(p)),
+        "docx" | "xlsx" | "pptx" | "docm" | "xlsm" | "pptm" => ooxml_metadata(p),
+        "odt" | "ods" | "odp" => odf_metadata(p),
+        _ => None,
+    }
+}
+
+/// Poppler's `pdfinfo`, probed like the other command-line renderers.
+fn pdfinfo(p: &Path) -> Option<DocumentMetadata> {
+    let output = renderer_paths_to_try("pdfinfo").into_iter().find_map(|bin| {
+        std::process::Command::new(bin)
+            .arg(p)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+    })?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let field = |name: &str| {
+        text.lines()
+            .find_map(|l| l.strip_prefix(name)?.strip_prefix(':'))
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    Some(DocumentMetadata {
+        page_count: field("Pages").and_then(|v| v.parse().ok()),
+        title: field("Title"),
+        author: field("Author"),
+        subject: field("Subject"),
+        application: field("Creator").or_else(|| field("Producer")),
+        created: field("CreationDate"),
+        modified: field("ModDate"),
+        word_count: None,
+    })
+}
+
+/// Best-effort scan of an uncompressed PDF Info dictionary and page tree. Compressed object
+/// streams hide these values; the fields are simply left empty then.
+fn pdf_fallback(p: &Path) -> Option<DocumentMetadata> {
+    let file = std::fs::File::open(p).ok()?;
+    let mut bytes = Vec::new();
+    file.take(MAX_PDF_SCAN_BYTES).read_to_end(&mut bytes).ok()?;
+    if !bytes.starts_with(b"%PDF") {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&bytes);
+
+    let page_count = PDF_PAGE_COUNT_RE
+        .captures_iter(&text)
+        .filter_map(|c| c.get(1).or_else(|| c.get(2))?.as_str().parse::<u32>().ok())
+        .max();
+    let info = |key: &str| {
+        PDF_INFO_RES.get(key)?
+            .captures(&text)
+            .map(|c| c[1].replace("\\(", "(").replace("\\)", ")").replace("\\\\", "\\"))
+            .filter(|v| !v.is_empty())
+    };
+    Some(DocumentMetadata {
+        page_count,
+        title: info("Title"),
+        author: info("Author"),
+        subject: info("Subject"),
+        application: info("Creator").or_else(|| info("Producer")),
+        created: info("CreationDate"),
+        modified: info("ModDate"),
+        word_count: None,
+    })
+}
+
+fn zip_entry_text(archive: &mut zip::ZipArchive<std::io::BufReader<std::fs::File>>, name: &str) -> Option<String> {
+    let entry = archive.by_name(name).ok()?;
+    if entry.size() > MAX_ZIP_METADATA_BYTES {
+        return None;
+    }
+    let mut text = String::new();
+    entry.take(MAX_ZIP_METADATA_BYTES + 1).read_to_string(&mut text).ok()?;
+    if text.len() as u64 > MAX_ZIP_METADATA_BYTES {
+        return None;
+    }
+    Some(text)
+}
+
+/// Text content of the first `<name ...>...</name>` element. Property files are flat and
+/// small, so a full XML parser isn't needed.
+fn xml_element(xml: &str, name: &str) -> Option<String> {
+    let open = format!("<{}", name);
+    let mut rest = xml;
+    while let Some(i) = rest.find(&open) {
+        rest = &rest[i + open.len()..];
+        // Skip longer names sharing the prefix (e.g. <dc:creator vs <dc:creatorX).
+        if !rest.starts_with(['>', ' ', '/', '\t', '\n', '\r']) {
+            continue;
+        }
+        if rest.starts_with('/') {
+            return None;
+        }
+        let body = &rest[rest.find('>')? + 1..];
+        let value = &body[..body.find(&format!("</{}>", name))?];
+        let value = value
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&amp;", "&");
+        let value = value.trim();
+        return (!value.is_empty()).then(|| value.to_string());
+    }
+    None
+}
+
+/// Value of `attr="..."` on the first `<name>` element.
+fn xml_attribute(xml: &str, name: &str, attr: &str) -> Option<String> {
+    let start = xml.find(&format!("<{} ", name))?;
+    let tag = &xml[start..start + xml[start..].find('>')?];
+    let needle = format!("{}=\"", attr);
+    let value = &tag[tag.find(&needle)? + needle.len()..];
+    Some(value[..value.find('"')?].to_string())
+}
+
+fn open_zip(p: &Path) -> Option<zip::ZipArchive<std::io::BufReader<std::fs::File>>> {
+    zip::ZipArchive::new(std::io::BufReader::new(std::fs::File::open(p).ok()?)).ok()
+}
+
+/// OOXML `docProps/core.xml` (title, author, dates) and `docProps/app.xml` (pages, words, app).
+fn ooxml_metadata(p: &Path) -> Option<DocumentMetadata> {
+    let mut archive = open_zip(p)?;
+    let core = zip_entry_text(&mut archive, "docProps/core.xml").unwrap_or_default();
+    let app = zip_entry_text(&mut archive, "docProps/app.xml").unwrap_or_default();
+    Some(DocumentMetadata {
+        // Word reports <Pages>, PowerPoint <Slides>.
+        page_count: xml_element(&app, "Pages")
+            .or_else(|| xml_element(&app, "Slides"))
+            .and_then(|v| v.parse().ok()),
+        title: xml_element(&core, "dc:title"),
+        author: xml_element(&core, "dc:creator"),
+        subject: xml_element(&core, "dc:subject"),
+        application: xml_element(&app, "Application"),
+        created: xml_element(&core, "dcterms:created"),
+        modified: xml_element(&core, "dcterms:modified"),
+        word_count: xml_element(&app, "Words").and_then(|v| v.parse().ok()),
+    })
+}
+
+/// OpenDocument `meta.xml`.
+fn odf_metadata(p: &Path) -> Option<DocumentMetadata> {
+    let mut archive = open_zip(p)?;
+    let meta = zip_entry_text(&mut archive, "meta.xml")?;
+    let stat = |attr: &str| xml_attribute(&meta, "meta:document-statistic", attr).and_then(|v| v.parse().ok());
+    Some(DocumentMetadata {
+        page_count: stat("meta:page-count"),
+        title: xml_element(&meta, "dc:title"),
+        author: xml_element(&meta, "meta:initial-creator").or_else(|| xml_element(&meta, "dc:creator")),
+        subject: xml_element(&meta, "dc:subject"),
+        application: xml_element(&meta, "meta:generator"),
+        created: xml_element(&meta, "meta:creation-date"),
+        modified: xml_element(&meta, "dc:date"),
+        word_count: stat("meta:word-count"),
+    })
+}
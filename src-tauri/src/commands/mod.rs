@@ -15,3 +15,14 @@ pub mod tree;
 pub mod setup;
 pub mod cleaner;
 pub mod archive;
+pub mod dev_cleaner;
+pub mod protocol;
+pub mod metadata;
+pub mod text_preview;
+pub mod hex_view;
+pub mod extract;
+pub mod compress;
+pub mod archive_password;
+pub mod profiles;
+pub mod folder_overrides;
+pub mod exclusion;
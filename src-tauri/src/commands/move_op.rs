@@ -1,6 +1,7 @@
 use std::path::Path;
 use tauri::{AppHandle, Emitter};
-use crate::commands::operation::{register_operation, unregister_operation};
+use crate::commands::operation::{register_operation_with_paths, unregister_operation};
+use crate::utils::exclusion::DeletionGuard;
 
 #[tauri::command]
 pub async fn start_move(
@@ -15,14 +16,16 @@ pub async fn start_move(
     if !src_path.exists() {
         return Err("Source file does not exist".to_string());
     }
+    DeletionGuard::new().check(src_path)?;
 
-    let _cancel_flag = register_operation(operation_id.clone());
+    let _cancel_flag = register_operation_with_paths(operation_id.clone(), [&source, &destination]);
 
     // Simple move using fs::rename (works across same volume)
     // For different volumes, we might need copy + delete, but for now we'll keep it simple
-    std::fs::rename(src_path, dst_path).map_err(|e| e.to_string())?;
-
+    let result = std::fs::rename(src_path, dst_path).map_err(|e| e.to_string());
     unregister_operation(&operation_id);
+    result?;
+
     let _ = app.emit("move_completed", operation_id);
     Ok(())
 }
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::sync::atomic::AtomicBool;
 use lazy_static::lazy_static;
@@ -6,6 +7,8 @@ use lazy_static::lazy_static;
 lazy_static! {
     pub static ref OPERATIONS: Arc<Mutex<HashMap<String, Arc<AtomicBool>>>> = 
         Arc::new(Mutex::new(HashMap::new()));
+    /// Files and folders each running operation reads or writes, so volumes in use aren't ejected.
+    static ref OPERATION_PATHS: Mutex<HashMap<String, Vec<PathBuf>>> = Mutex::new(HashMap::new());
 }
 
 #[tauri::command]
@@ -23,7 +26,33 @@ pub fn register_operation(operation_id: String) -> Arc<AtomicBool> {
     cancel_flag
 }
 
+/// Like [register_operation], also recording the paths the job works on.
+pub fn register_operation_with_paths<P: AsRef<Path>>(
+    operation_id: String,
+    paths: impl IntoIterator<Item = P>,
+) -> Arc<AtomicBool> {
+    let paths = paths
+        .into_iter()
+        .map(|p| std::fs::canonicalize(p.as_ref()).unwrap_or_else(|_| p.as_ref().to_path_buf()))
+        .collect();
+    OPERATION_PATHS.lock().unwrap().insert(operation_id.clone(), paths);
+    register_operation(operation_id)
+}
+
 pub fn unregister_operation(operation_id: &str) {
     let mut ops = OPERATIONS.lock().unwrap();
     ops.remove(operation_id);
+    OPERATION_PATHS.lock().unwrap().remove(operation_id);
+}
+
+/// Running operations working on anything under `root`, or on a folder containing it (a scan
+/// of `/Volumes` is busy with every volume mounted there).
+pub fn operations_under(root: &Path) -> Vec<String> {
+    OPERATION_PATHS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(_, paths)| paths.iter().any(|p| p.starts_with(root) || root.starts_with(p)))
+        .map(|(id, _)| id.clone())
+        .collect()
 }
@@ -3,6 +3,8 @@ use std::fs;
 use std::path::Path;
 use std::process::Command;
 
+use crate::utils::text_encoding::{decode, detect_encoding, SNIFF_BYTES};
+
 /// Max size (bytes) for text and binary preview to avoid OOM on large files.
 const MAX_PREVIEW_BYTES: u64 = 50 * 1024 * 1024; // 50 MB
 
@@ -20,6 +22,7 @@ fn check_preview_size(p: &Path) -> Result<u64, String> {
     Ok(meta.len())
 }
 
+/// Whole-file text for small files, decoded like `read_text_range` (UTF-16 and Latin-1 too).
 #[tauri::command]
 pub async fn get_file_text_content(path: String) -> Result<String, String> {
     let p = Path::new(&path);
@@ -27,7 +30,9 @@ pub async fn get_file_text_content(path: String) -> Result<String, String> {
         return Err("File not found".to_string());
     }
     check_preview_size(p)?;
-    fs::read_to_string(p).map_err(|e| format!("Failed to read file: {}", e))
+    let data = fs::read(p).map_err(|e| format!("Failed to read file: {}", e))?;
+    let encoding = detect_encoding(&data[..data.len().min(SNIFF_BYTES)]);
+    Ok(decode(&data[encoding.bom_len().min(data.len())..], encoding))
 }
 
 #[tauri::command]
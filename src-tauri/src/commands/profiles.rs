@@ -0,0 +1,259 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+use super::settings::{
+    get_settings_path, migrate_document, publish_report, read_settings_file, settings_from_document,
+    upgrade_settings, write_atomic, write_settings_file, AppSettings, SettingsReport,
+};
+
+/// Profiles are stored as `profiles/<name>.json` next to settings.json, in the same format.
+const PROFILES_DIR: &str = "profiles";
+/// Name the working settings are saved under when switching away before any profile was used.
+const DEFAULT_PROFILE: &str = "Default";
+const MAX_PROFILE_NAME_LEN: usize = 64;
+/// Per-install state that imports never change.
+const LOCAL_FIELDS: [&str; 3] = ["schema_version", "setup_completed", "active_profile"];
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ProfileInfo {
+    pub name: String,
+    pub active: bool,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeStrategy {
+    /// The file replaces all shared settings; anything it leaves out goes back to its default.
+    Replace,
+    /// Values in the file win; settings it leaves out are kept.
+    Overwrite,
+    /// Like `Overwrite`, but lists gain the file's entries instead of being replaced.
+    #[default]
+    Merge,
+}
+
+/// One setting that differs after an import, by dotted path.
+#[derive(Debug, Serialize, Clone)]
+pub struct SettingChange {
+    pub field: String,
+    pub before: Value,
+    pub after: Value,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ImportResult {
+    pub settings: AppSettings,
+    pub changes: Vec<SettingChange>,
+    /// Migration and validation findings for the imported file.
+    pub report: SettingsReport,
+}
+
+fn profiles_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let settings_path = get_settings_path(app)?;
+    let dir = settings_path.with_file_name(PROFILES_DIR);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+fn profile_path(app: &AppHandle, name: &str) -> Result<PathBuf, String> {
+    Ok(profiles_dir(app)?.join(format!("{name}.json")))
+}
+
+/// Profile names double as file names, so they are limited to a safe character set.
+fn validate_profile_name(name: &str) -> Result<&str, String> {
+    let name = name.trim();
+    let valid = !name.is_empty()
+        && name.len() <= MAX_PROFILE_NAME_LEN
+        && !name.starts_with('.')
+        && name.chars().all(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.'));
+    if valid {
+        Ok(name)
+    } else {
+        Err(format!("Invalid profile name {name:?}: use letters, digits, spaces, '-', '_' or '.'"))
+    }
+}
+
+fn write_json(path: &Path, settings: &AppSettings) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    write_atomic(path, &content).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+fn read_document(path: &Path) -> Result<Value, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    serde_json::from_str(&content).map_err(|e| format!("{} is not valid settings JSON: {e}", path.display()))
+}
+
+fn to_value(settings: &AppSettings) -> Result<Value, String> {
+    serde_json::to_value(settings).map_err(|e| e.to_string())
+}
+
+/// Saves `settings` as the working copy and tells every window about it.
+fn apply_settings(app: &AppHandle, settings: &AppSettings, report: SettingsReport) -> Result<(), String> {
+    write_settings_file(&get_settings_path(app)?, settings)?;
+    if !report.is_empty() {
+        publish_report(app, report);
+    }
+    let _ = app.emit("settings-changed", settings);
+    Ok(())
+}
+
+/// Copies the fields present in `from` into `into`. Objects merge recursively; with
+/// `union_lists`, arrays gain missing entries, and entries sharing a `name` are replaced.
+fn overlay(into: &mut Value, from: &Value, union_lists: bool) {
+    match (into, from) {
+        (Value::Object(into), Value::Object(from)) => {
+            for (key, value) in from {
+                match into.get_mut(key) {
+                    Some(slot) => overlay(slot, value, union_lists),
+                    None => {
+                        into.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (Value::Array(into), Value::Array(from)) if union_lists => {
+            for item in from {
+                let same = |existing: &Value| {
+                    existing == item || (item.get("name").is_some() && existing.get("name") == item.get("name"))
+                };
+                match into.iter_mut().find(|existing| same(existing)) {
+                    Some(existing) => *existing = item.clone(),
+                    None => into.push(item.clone()),
+                }
+            }
+        }
+        (into, from) => *into = from.clone(),
+    }
+}
+
+/// Leaf-level differences between two settings documents; arrays compare as a whole.
+fn diff(before: &Value, after: &Value, path: &str, changes: &mut Vec<SettingChange>) {
+    match (before, after) {
+        (Value::Object(old), Value::Object(new)) => {
+            let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+            for key in keys {
+                let field = if path.is_empty() { key.clone() } else { format!("{path}.{key}") };
+                diff(
+                    old.get(key).unwrap_or(&Value::Null),
+                    new.get(key).unwrap_or(&Value::Null),
+                    &field,
+                    changes,
+                );
+            }
+        }
+        _ if before != after => changes.push(SettingChange {
+            field: path.to_string(),
+            before: before.clone(),
+            after: after.clone(),
+        }),
+        _ => {}
+    }
+}
+
+#[tauri::command]
+pub fn list_profiles(app: AppHandle) -> Result<Vec<ProfileInfo>, String> {
+    let (current, _) = read_settings_file(&get_settings_path(&app)?)?;
+    let active = current.active_profile.unwrap_or_else(|| DEFAULT_PROFILE.to_string());
+
+    let mut names: BTreeSet<String> = fs::read_dir(profiles_dir(&app)?)
+        .map_err(|e| e.to_string())?
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()? != "json" {
+                return None;
+            }
+            Some(path.file_stem()?.to_str()?.to_string())
+        })
+        .collect();
+    names.insert(active.clone());
+
+    Ok(names
+        .into_iter()
+        .map(|name| ProfileInfo { active: name == active, name })
+        .collect())
+}
+
+/// Saves the working settings into the active profile, then loads `name`. A profile that
+/// doesn't exist yet starts as a copy of the current settings.
+#[tauri::command]
+pub fn switch_profile(app: AppHandle, name: String) -> Result<AppSettings, String> {
+    let name = validate_profile_name(&name)?;
+    let (current, _) = read_settings_file(&get_settings_path(&app)?)?;
+    let outgoing = current
+        .active_profile
+        .as_deref()
+        .and_then(|active| validate_profile_name(active).ok())
+        .unwrap_or(DEFAULT_PROFILE)
+        .to_string();
+    if outgoing == name && current.active_profile.is_some() {
+        return Ok(current);
+    }
+    write_json(&profile_path(&app, &outgoing)?, &current)?;
+
+    let target = profile_path(&app, name)?;
+    let mut report = SettingsReport::default();
+    let mut next = if target.exists() {
+        upgrade_settings(read_document(&target)?, &mut report)
+    } else {
+        write_json(&target, &current)?;
+        current.clone()
+    };
+    next.setup_completed = current.setup_completed;
+    next.active_profile = Some(name.to_string());
+    apply_settings(&app, &next, report)?;
+    Ok(next)
+}
+
+#[tauri::command]
+pub fn delete_profile(app: AppHandle, name: String) -> Result<(), String> {
+    let name = validate_profile_name(&name)?;
+    let (current, _) = read_settings_file(&get_settings_path(&app)?)?;
+    if current.active_profile.as_deref().unwrap_or(DEFAULT_PROFILE) == name {
+        return Err("Switch to another profile before deleting this one".to_string());
+    }
+    fs::remove_file(profile_path(&app, name)?).map_err(|e| e.to_string())
+}
+
+/// Writes the current settings to `path` in the settings.json format.
+#[tauri::command]
+pub fn export_settings(app: AppHandle, path: String) -> Result<(), String> {
+    let (mut settings, _) = read_settings_file(&get_settings_path(&app)?)?;
+    settings.active_profile = None;
+    write_json(Path::new(&path), &settings)
+}
+
+/// Imports a settings file (any schema version, possibly only a few fields) into the working
+/// settings and returns what changed. Install-specific fields are never imported.
+#[tauri::command]
+pub fn import_settings(
+    app: AppHandle,
+    path: String,
+    merge_strategy: Option<MergeStrategy>,
+) -> Result<ImportResult, String> {
+    let (current, _) = read_settings_file(&get_settings_path(&app)?)?;
+    let before = to_value(&current)?;
+
+    let mut report = SettingsReport::default();
+    let imported = migrate_document(read_document(Path::new(&path))?, &mut report);
+    let strategy = merge_strategy.unwrap_or_default();
+    let base = match strategy {
+        MergeStrategy::Replace => AppSettings::default(),
+        MergeStrategy::Overwrite | MergeStrategy::Merge => current.clone(),
+    };
+    let mut merged = to_value(&base)?;
+    overlay(&mut merged, &imported, matches!(strategy, MergeStrategy::Merge));
+    for field in LOCAL_FIELDS {
+        merged[field] = before[field].clone();
+    }
+
+    let settings = settings_from_document(&merged, &base, &mut report);
+    let mut changes = Vec::new();
+    diff(&before, &to_value(&settings)?, "", &mut changes);
+    apply_settings(&app, &settings, report.clone())?;
+    Ok(ImportResult { settings, changes, report })
+}
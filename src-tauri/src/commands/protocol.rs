@@ -0,0 +1,285 @@
+//! `sdm://` custom URI scheme: serves file bytes and thumbnails directly to `<img>`/`<video>`
+//! instead of base64 `data:` URLs over IPC.
+//!
+//! URL forms (the kind may be the host or the first path segment, so both
+//! `sdm://thumb/<path>` and `convertFileSrc`-style `sdm://localhost/thumb/<path>` /
+//! `http://sdm.localhost/thumb/<path>` on Windows work):
+//! - `file/<percent-encoded absolute path>` — raw bytes, honours `Range`
+//! - `thumb/<percent-encoded absolute path>?w=256&h=256` — cached thumbnail
+//!
+//! Only paths inside folders the user has browsed (or scan roots they picked) are served.
+
+use std::collections::{HashSet, VecDeque};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::UNIX_EPOCH;
+
+use lazy_static::lazy_static;
+use percent_encoding::percent_decode_str;
+use tauri::http::{header, Request, Response, StatusCode};
+
+use crate::commands::thumbnails::{audio_thumbnail, file_thumbnail, thumbnail_cache, video_thumbnail};
+use crate::utils::file_types::{get_file_category, FileCategory};
+use crate::utils::thumbnail_cache::ThumbnailError;
+
+pub const SCHEME: &str = "sdm";
+
+/// Largest body sent for one request; bigger ranges are truncated and the client re-requests.
+const MAX_CHUNK_BYTES: u64 = 8 * 1024 * 1024; // 8 MB
+const DEFAULT_THUMB_EDGE: u32 = 256;
+/// Requested sizes are clamped to this; the URL is untrusted and decoders allocate w×h.
+const MAX_THUMB_EDGE: u32 = 1024;
+const MAX_BROWSED_DIRS: usize = 4096;
+const MAX_SCAN_ROOTS: usize = 64;
+
+/// Set that forgets its oldest grant once full, so a long session doesn't keep every
+/// folder ever opened servable.
+struct AllowList {
+    cap: usize,
+    order: VecDeque<PathBuf>,
+    paths: HashSet<PathBuf>,
+}
+
+impl AllowList {
+    fn new(cap: usize) -> Self {
+        Self { cap, order: VecDeque::new(), paths: HashSet::new() }
+    }
+
+    /// Inserts `path`, or moves it to the back of the queue if already present.
+    fn insert(&mut self, path: PathBuf) {
+        if self.paths.contains(&path) {
+            self.order.retain(|p| p != &path);
+        } else {
+            self.paths.insert(path.clone());
+        }
+        self.order.push_back(path);
+        while self.order.len() > self.cap {
+            if let Some(oldest) = self.order.pop_front() {
+                self.paths.remove(&oldest);
+            }
+        }
+    }
+}
+
+lazy_static! {
+    /// Folders listed via `read_dir_chunked`; their direct children are servable.
+    static ref BROWSED_DIRS: RwLock<AllowList> = RwLock::new(AllowList::new(MAX_BROWSED_DIRS));
+    /// Roots of user-started scans (search, dedupe, category view); whole subtree is servable.
+    static ref SCAN_ROOTS: RwLock<AllowList> = RwLock::new(AllowList::new(MAX_SCAN_ROOTS));
+}
+
+fn canonical(path: &Path) -> Option<PathBuf> {
+    std::fs::canonicalize(path).ok()
+}
+
+/// Grant access to the direct children of a folder the user has browsed to.
+pub fn allow_dir(path: &Path) {
+    if let Some(p) = canonical(path) {
+        BROWSED_DIRS.write().unwrap().insert(p);
+    }
+}
+
+/// Grant access to everything below a scan root the user picked. Filesystem and drive
+/// roots are refused: a scan of `/` or `C:\` must not make the whole disk servable.
+pub fn allow_tree(path: &Path) {
+    if let Some(p) = canonical(path) {
+        if p.parent().is_none() {
+            return;
+        }
+        SCAN_ROOTS.write().unwrap().insert(p);
+    }
+}
+
+/// Resolves symlinks first, so a link in a browsed folder can't expose its target's tree.
+pub fn is_allowed(path: &Path) -> bool {
+    let Some(path) = canonical(path) else { return false };
+    if let Some(parent) = path.parent() {
+        if BROWSED_DIRS.read().unwrap().paths.contains(parent) {
+            return true;
+        }
+    }
+    SCAN_ROOTS.read().unwrap().paths.iter().any(|root| path.starts_with(root))
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "text/plain")
+        .body(message.as_bytes().to_vec())
+        .unwrap()
+}
+
+/// Splits the request URI into (kind, decoded path, query).
+fn parse_uri(request: &Request<Vec<u8>>) -> Option<(String, PathBuf, String)> {
+    let uri = request.uri();
+    let host = uri.host().unwrap_or("localhost");
+    let raw_path = uri.path().trim_start_matches('/');
+
+    let (kind, encoded) = if host == "localhost" || host.starts_with("sdm.") {
+        raw_path.split_once('/')?
+    } else {
+        (host, raw_path)
+    };
+    let decoded = percent_decode_str(encoded).decode_utf8().ok()?;
+    if decoded.is_empty() {
+        return None;
+    }
+    Some((kind.to_string(), PathBuf::from(decoded.as_ref()), uri.query().unwrap_or("").to_string()))
+}
+
+fn query_u32(query: &str, name: &str) -> Option<u32> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(k, _)| *k == name)
+        .and_then(|(_, v)| v.parse().ok())
+}
+
+/// Entry point for the `sdm` scheme. Blocking: call from a blocking-capable thread.
+pub fn handle_request(app: &tauri::AppHandle, request: Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let Some((kind, path, query)) = parse_uri(&request) else {
+        return error_response(StatusCode::BAD_REQUEST, "Malformed sdm:// URL");
+    };
+    // Allowlist first: answering 404 vs 403 for arbitrary paths would reveal what exists.
+    if !is_allowed(&path) {
+        return error_response(StatusCode::FORBIDDEN, "Path is outside browsed folders");
+    }
+    if !path.is_file() {
+        return error_response(StatusCode::NOT_FOUND, "File not found");
+    }
+
+    match kind.as_str() {
+        "file" => serve_file(&request, &path),
+        "thumb" => serve_thumbnail(app, &path, &query),
+        _ => error_response(StatusCode::NOT_FOUND, "Unknown sdm:// resource"),
+    }
+}
+
+fn serve_thumbnail(app: &tauri::AppHandle, path: &Path, query: &str) -> Response<Vec<u8>> {
+    let width = query_u32(query, "w").unwrap_or(DEFAULT_THUMB_EDGE);
+    let height = query_u32(query, "h").unwrap_or(width);
+    if width == 0 || height == 0 {
+        return error_response(StatusCode::BAD_REQUEST, "Thumbnail size must be at least 1x1");
+    }
+    let (width, height) = (width.min(MAX_THUMB_EDGE), height.min(MAX_THUMB_EDGE));
+    let cache = thumbnail_cache(app);
+
+    let ext = path.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_default();
+    let result = match get_file_category(&ext) {
+        FileCategory::Image | FileCategory::Document => file_thumbnail(&cache, path, width, height),
+        FileCategory::Video => video_thumbnail(&cache, path, width, height).map_err(ThumbnailError::Decode),
+        FileCategory::Audio => audio_thumbnail(&cache, path, width, height),
+        _ => Err(ThumbnailError::Unsupported("No thumbnail for this file type".to_string())),
+    };
+
+    match result {
+        Ok(thumb) => Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, thumb.mime)
+            .header(header::CONTENT_LENGTH, thumb.bytes.len())
+            // Cache key includes size+mtime, so a changed file gets a fresh thumbnail anyway.
+            .header(header::CACHE_CONTROL, "private, max-age=86400")
+                .body(thumb.bytes.clone())
+            .unwrap(),
+        Err(e) => {
+            let status = match e {
+                ThumbnailError::NotFound(_) => StatusCode::NOT_FOUND,
+                ThumbnailError::Unsupported(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                ThumbnailError::Decode(_) | ThumbnailError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            error_response(status, &e.to_string())
+        }
+    }
+}
+
+/// Parses a single `bytes=start-end` / `bytes=start-` / `bytes=-suffix` range.
+fn parse_range(value: &str, size: u64) -> Option<(u64, u64)> {
+    let spec = value.trim().strip_prefix("bytes=")?;
+    // Multipart ranges aren't supported; serve the first one.
+    let spec = spec.split(',').next()?.trim();
+    let (start, end) = spec.split_once('-')?;
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", suffix) => {
+            let n: u64 = suffix.parse().ok()?;
+            (size.saturating_sub(n), size.checked_sub(1)?)
+        }
+        (s, "") => (s.parse().ok()?, size.checked_sub(1)?),
+        (s, e) => (s.parse().ok()?, e.parse::<u64>().ok()?.min(size.checked_sub(1)?)),
+    };
+    if start > end || start >= size {
+        return None;
+    }
+    Some((start, end))
+}
+
+fn serve_file(request: &Request<Vec<u8>>, path: &Path) -> Response<Vec<u8>> {
+    let meta = match std::fs::metadata(path) {
+        Ok(m) => m,
+        Err(e) => return error_response(StatusCode::NOT_FOUND, &e.to_string()),
+    };
+    let size = meta.len();
+    let mtime = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let etag = format!("\"{:x}-{:x}\"", size, mtime);
+    let mime = mime_guess::from_path(path).first_or_octet_stream().to_string();
+
+    let base = || {
+        Response::builder()
+            .header(header::CONTENT_TYPE, mime.as_str())
+            .header(header::ACCEPT_RANGES, "bytes")
+            .header(header::ETAG, etag.as_str())
+            .header(header::CACHE_CONTROL, "private, no-cache")
+        };
+
+    let if_none_match = request.headers().get(header::IF_NONE_MATCH).and_then(|v| v.to_str().ok());
+    if if_none_match == Some(etag.as_str()) {
+        return base().status(StatusCode::NOT_MODIFIED).body(Vec::new()).unwrap();
+    }
+
+    let range_header = request.headers().get(header::RANGE).and_then(|v| v.to_str().ok());
+    let (start, end, partial) = match range_header {
+        Some(value) => match parse_range(value, size) {
+            Some((s, e)) => (s, e.min(s + MAX_CHUNK_BYTES - 1), true),
+            None => {
+                return base()
+                    .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                    .header(header::CONTENT_RANGE, format!("bytes */{}", size))
+                    .body(Vec::new())
+                    .unwrap();
+            }
+        },
+        // Large media without a Range header: answer with the first chunk as a partial
+        // response so the player switches to ranged requests.
+        None if size > MAX_CHUNK_BYTES => (0, MAX_CHUNK_BYTES - 1, true),
+        None => (0, size.saturating_sub(1), false),
+    };
+
+    let body = match read_span(path, start, if size == 0 { 0 } else { end - start + 1 }) {
+        Ok(b) => b,
+        Err(e) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    };
+
+    let mut builder = base().header(header::CONTENT_LENGTH, body.len());
+    if partial {
+        builder = builder
+            .status(StatusCode::PARTIAL_CONTENT)
+            .header(header::CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, size));
+    } else {
+        builder = builder.status(StatusCode::OK);
+    }
+    builder.body(body).unwrap()
+}
+
+fn read_span(path: &Path, start: u64, len: u64) -> std::io::Result<Vec<u8>> {
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(start))?;
+    let mut body = Vec::with_capacity(len as usize);
+    file.take(len).read_to_end(&mut body)?;
+    Ok(body)
+}
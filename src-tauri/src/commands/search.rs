@@ -2,7 +2,11 @@ use serde::Serialize;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use tauri::{AppHandle, Emitter};
 
-use crate::commands::operation::{register_operation, unregister_operation};
+use crate::commands::operation::{register_operation_with_paths, unregister_operation};
+use crate::commands::protocol::allow_tree;
+use crate::commands::settings::{get_settings_path, read_settings_file};
+use crate::utils::exclusion::exclusions;
+use crate::utils::mountinfo::MountFilter;
 use grep::regex::RegexMatcher;
 use grep::searcher::{Searcher, Sink, SinkMatch};
 
@@ -19,33 +23,22 @@ pub struct SearchResult {
     pub preview: Option<String>,
 }
 
-fn is_system_path(path: &std::path::Path) -> bool {
-    let path_str = path.to_string_lossy();
-    #[cfg(target_os = "macos")]
-    {
-        path_str == "/System" || path_str.starts_with("/System/") ||
-        path_str == "/Library" || path_str.starts_with("/Library/") ||
-        path_str == "/private" || path_str.starts_with("/private/") ||
-        path_str == "/Applications" || path_str.starts_with("/Applications/") ||
-        path_str == "/bin" || path_str == "/usr" || path_str == "/sbin" ||
-        path_str == "/dev" || path_str == "/etc"
-    }
-    #[cfg(target_os = "windows")]
-    {
-        let p_upper = path_str.to_uppercase();
-        p_upper.contains("WINDOWS") || p_upper.contains("PROGRAM FILES") ||
-        p_upper.contains("PROGRAM DATA") || p_upper.contains("RECYCLE.BIN") ||
-        p_upper.contains("SYSTEM VOLUME INFORMATION")
-    }
-    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
-    {
-        path_str.starts_with("/proc") || path_str.starts_with("/sys") ||
-        path_str.starts_with("/boot") || path_str.starts_with("/dev")
-    }
+/// Searches have no settings of their own and follow the explorer's mount options.
+fn mount_filter(app: &AppHandle) -> MountFilter {
+    let explorer = get_settings_path(app)
+        .and_then(|path| read_settings_file(&path))
+        .map(|(settings, _)| settings.explorer)
+        .unwrap_or_default();
+    MountFilter::new(&explorer)
 }
 
-fn walk_builder(root: &str, max_depth: Option<u32>) -> ignore::Walk {
+fn walk_builder(root: &str, max_depth: Option<u32>, mounts: MountFilter) -> ignore::Walk {
     let mut builder = ignore::WalkBuilder::new(root);
+    let rules = exclusions();
+    builder.filter_entry(move |e| {
+        rules.excluded(e.path()).is_none()
+            && (e.depth() == 0 || !e.file_type().is_some_and(|t| t.is_dir()) || mounts.allows(e.path()))
+    });
     builder.follow_links(false);
     builder.hidden(false); // Show hidden files
     builder.git_global(false);
@@ -68,14 +61,17 @@ pub async fn start_file_search(
     item_type: Option<String>,
     extensions: Option<Vec<String>>,
 ) -> Result<(), String> {
-    let cancel_flag = register_operation(search_id.clone());
+    let cancel_flag = register_operation_with_paths(search_id.clone(), [&root]);
+    allow_tree(std::path::Path::new(&root));
     let pattern = pattern.to_lowercase();
     let limit = result_limit.unwrap_or(DEFAULT_RESULT_LIMIT);
     let filter_type = item_type.unwrap_or_else(|| "both".to_string());
 
+    let mounts = mount_filter(&app);
+
     tokio::task::spawn_blocking(move || {
         let count = AtomicUsize::new(0);
-        for result in walk_builder(&root, max_depth) {
+        for result in walk_builder(&root, max_depth, mounts) {
             if cancel_flag.load(Ordering::Relaxed) {
                 break;
             }
@@ -84,7 +80,6 @@ pub async fn start_file_search(
                 Err(_) => continue,
             };
             let path = entry.path();
-            if is_system_path(path) { continue; }
             let is_dir = path.is_dir();
 
             // Apply item_type filter
@@ -183,15 +178,18 @@ pub async fn start_content_search(
     item_type: Option<String>,
     extensions: Option<Vec<String>>,
 ) -> Result<(), String> {
-    let cancel_flag = register_operation(search_id.clone());
     let matcher = RegexMatcher::new(&pattern).map_err(|e| e.to_string())?;
+    let cancel_flag = register_operation_with_paths(search_id.clone(), [&root]);
+    allow_tree(std::path::Path::new(&root));
     let mut searcher = Searcher::new();
     let limit = result_limit.unwrap_or(DEFAULT_RESULT_LIMIT);
     let filter_type = item_type.unwrap_or_else(|| "both".to_string());
 
+    let mounts = mount_filter(&app);
+
     tokio::task::spawn_blocking(move || {
         let count = AtomicUsize::new(0);
-        for result in walk_builder(&root, max_depth) {
+        for result in walk_builder(&root, max_depth, mounts) {
             if cancel_flag.load(Ordering::Relaxed) {
                 break;
             }
@@ -215,7 +213,6 @@ pub async fn start_content_search(
             }
 
             let path = entry.path();
-            if is_system_path(path) { continue; }
             let path_str = path.to_string_lossy().into_owned();
             let name = path
                 .file_name()
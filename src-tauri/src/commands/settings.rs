@@ -1,8 +1,18 @@
+use lazy_static::lazy_static;
 use serde::{Serialize, Deserialize};
-use std::fs;
-use std::path::PathBuf;
+use serde_json::Value;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Mutex};
+use std::time::Duration;
 use tauri::AppHandle;
-use tauri::Manager;
+use tauri::{Emitter, Manager};
+
+use crate::utils::categories::{self, builtin_definitions, MAX_SIGNATURE_END};
+use crate::utils::exclusion;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PreviewSettings {
@@ -11,41 +21,62 @@ pub struct PreviewSettings {
     pub audio: bool,
     pub document: bool,
     pub archive: bool,
+    /// Toggles for user-defined categories, keyed by category id.
+    #[serde(flatten, default)]
+    pub custom: BTreeMap<String, bool>,
 }
 
 impl PreviewSettings {
-    pub fn is_extension_enabled(&self, ext: &str) -> bool {
-        let ext = ext.to_lowercase();
-        let ext = ext.trim_start_matches('.');
-
-        if is_image_extension(ext) { return self.image; }
-        if is_video_extension(ext) { return self.video; }
-        if is_audio_extension(ext) { return self.audio; }
-        if is_document_extension(ext) { return self.document; }
-        if is_archive_extension(ext) { return self.archive; }
-        
-        false
+    /// Built-in categories use their own fields; user categories are on until switched off.
+    pub fn is_category_enabled(&self, id: &str) -> bool {
+        match id {
+            "image" => self.image,
+            "video" => self.video,
+            "audio" => self.audio,
+            "document" => self.document,
+            "archive" => self.archive,
+            other => self.custom.get(other).copied().unwrap_or(true),
+        }
     }
 }
 
-fn is_image_extension(ext: &str) -> bool {
-    matches!(ext, "jpg" | "jpeg" | "png" | "gif" | "bmp" | "webp" | "svg" | "tiff" | "ico" | "heic" | "heif" | "avif")
-}
-
-fn is_video_extension(ext: &str) -> bool {
-    matches!(ext, "mp4" | "m4v" | "webm" | "mkv" | "mov" | "avi" | "wmv" | "asf" | "ts" | "mts" | "m2ts" | "m3u8" | "mpeg" | "mpg" | "mp2" | "mpe" | "mpv" | "flv" | "f4v" | "3gp" | "3g2" | "ogv" | "vob" | "rm" | "rmvb" | "divx" | "mk3d" | "qt" | "hevc" | "h265" | "h264")
-}
-
-fn is_audio_extension(ext: &str) -> bool {
-    matches!(ext, "mp3" | "wav" | "ogg" | "oga" | "flac" | "m4a" | "aac" | "wma" | "aiff" | "aif" | "alac" | "opus" | "mid" | "midi" | "amr" | "ape" | "wv" | "caf")
+/// Content rule for a category: `hex` bytes found at `offset` from the start of the file.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct MagicSignature {
+    #[serde(default)]
+    pub offset: u64,
+    pub hex: String,
 }
 
-fn is_document_extension(ext: &str) -> bool {
-    matches!(ext, "pdf" | "doc" | "docx" | "xls" | "xlsx" | "ppt" | "pptx" | "odt" | "ods" | "odp" | "pages" | "numbers" | "key" | "rtf" | "txt")
+/// A file category. Files are matched by extension first; `mime_types` (e.g. `image/*`,
+/// checked against the sniffed type) and `signatures` only apply to extensions no category
+/// claims.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct CategoryDefinition {
+    /// Stable key used by the `preview_enabled` toggles; the built-ins are `image`, `video`,
+    /// `audio`, `document` and `archive`.
+    pub id: String,
+    pub name: String,
+    pub extensions: Vec<String>,
+    #[serde(default)]
+    pub mime_types: Vec<String>,
+    #[serde(default)]
+    pub signatures: Vec<MagicSignature>,
 }
 
-fn is_archive_extension(ext: &str) -> bool {
-    matches!(ext, "zip" | "tar" | "gz" | "tgz" | "bz2" | "xz" | "7z" | "rar" | "iso" | "cab" | "ar" | "lz" | "lzma" | "z" | "war" | "ear")
+/// User additions to the built-in exclusion rules (see `utils::exclusion`).
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct ExclusionSettings {
+    /// Never deleted or moved, nor is any folder containing them.
+    #[serde(default)]
+    pub protected_paths: Vec<String>,
+    /// Folders every scan and search skips.
+    #[serde(default)]
+    pub excluded_paths: Vec<String>,
+    /// Globs every scan and search skips; without a `/` they match the file name
+    /// (`node_modules`, `*.bak`), otherwise the whole path (`**/cache/**`).
+    #[serde(default)]
+    pub excluded_globs: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -64,16 +95,53 @@ pub struct ConfigSection {
     /// When false (default), skip plain-text-like extensions in `find_duplicates` only.
     #[serde(default)]
     pub include_plain_text_in_duplicate_scan: bool, // default false for older settings.json
+    /// Also categorize files by their first bytes, and report both types in results.
+    #[serde(default)]
+    pub detect_content_type: bool,
+    /// Walks stay on the filesystem of the folder they started from.
+    #[serde(default)]
+    pub same_filesystem: bool,
+    /// Walks don't descend into NFS, SMB, FUSE and similar mounts below their starting folder.
+    #[serde(default = "default_skip_network_mounts")]
+    pub skip_network_mounts: bool,
+}
+
+fn default_skip_network_mounts() -> bool {
+    true
+}
+
+/// Build-artifact cleaner rule: a folder containing any of `markers` is a project root,
+/// folders named in `artifact_dirs` directly inside it are regenerable output, and so are
+/// folders named in `nested_dirs` at any depth below it (caches like `__pycache__`).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ArtifactRule {
+    pub name: String,
+    pub markers: Vec<String>,
+    pub artifact_dirs: Vec<String>,
+    #[serde(default)]
+    pub nested_dirs: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AppSettings {
+    /// On-disk shape version; files written before versioning are detected and migrated.
+    #[serde(default)]
+    pub schema_version: u32,
     pub explorer: ConfigSection,
     pub dedupe: ConfigSection,
     pub content_search: ConfigSection,
     pub clean: ConfigSection,
     pub theme: ThemeSettings,
     pub setup_completed: bool,
+    #[serde(default = "default_artifact_rules")]
+    pub artifact_rules: Vec<ArtifactRule>,
+    #[serde(default = "builtin_definitions")]
+    pub categories: Vec<CategoryDefinition>,
+    #[serde(default)]
+    pub exclusions: ExclusionSettings,
+    /// Profile the working settings were last switched to; `None` until profiles are used.
+    #[serde(default)]
+    pub active_profile: Option<String>,
 }
 
 impl Default for PreviewSettings {
@@ -84,6 +152,7 @@ impl Default for PreviewSettings {
             audio: true,
             document: true,
             archive: true,
+            custom: BTreeMap::new(),
         }
     }
 }
@@ -103,6 +172,9 @@ impl Default for ConfigSection {
                 ".DS_Store".to_string()
             ],
             include_plain_text_in_duplicate_scan: false,
+            detect_content_type: false,
+            same_filesystem: false,
+            skip_network_mounts: true,
         }
     }
 }
@@ -116,20 +188,437 @@ impl Default for ThemeSettings {
     }
 }
 
+fn artifact_rule(name: &str, markers: &[&str], artifact_dirs: &[&str], nested_dirs: &[&str]) -> ArtifactRule {
+    ArtifactRule {
+        name: name.to_string(),
+        markers: markers.iter().map(|m| m.to_string()).collect(),
+        artifact_dirs: artifact_dirs.iter().map(|d| d.to_string()).collect(),
+        nested_dirs: nested_dirs.iter().map(|d| d.to_string()).collect(),
+    }
+}
+
+pub fn default_artifact_rules() -> Vec<ArtifactRule> {
+    vec![
+        artifact_rule("Rust", &["Cargo.toml"], &["target"], &[]),
+        artifact_rule(
+            "Node.js",
+            &["package.json"],
+            &["node_modules", "dist", ".next", ".nuxt", ".turbo", ".parcel-cache", ".svelte-kit"],
+            &[],
+        ),
+        artifact_rule(
+            "Python",
+            &["pyproject.toml", "setup.py", "setup.cfg", "requirements.txt", "Pipfile"],
+            &[".venv", "venv", ".pytest_cache", ".mypy_cache", ".ruff_cache", ".tox"],
+            &["__pycache__"],
+        ),
+        artifact_rule(
+            "Gradle",
+            &["build.gradle", "build.gradle.kts", "settings.gradle", "settings.gradle.kts"],
+            &[".gradle", "build"],
+            &[],
+        ),
+        artifact_rule("Maven", &["pom.xml"], &["target"], &[]),
+        artifact_rule("Swift", &["Package.swift"], &[".build"], &[]),
+        artifact_rule("Dart", &["pubspec.yaml"], &[".dart_tool", "build"], &[]),
+    ]
+}
+
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
+            schema_version: SETTINGS_SCHEMA_VERSION,
             explorer: ConfigSection::default(),
             dedupe: ConfigSection::default(),
             content_search: ConfigSection::default(),
             clean: ConfigSection::default(),
             theme: ThemeSettings::default(),
             setup_completed: false,
+            artifact_rules: default_artifact_rules(),
+            categories: builtin_definitions(),
+            exclusions: ExclusionSettings::default(),
+            active_profile: None,
+        }
+    }
+}
+
+// ── Schema versioning ───────────────────────────────────────────────────────
+
+/// Bump when the on-disk shape changes and append the upgrade step to [MIGRATIONS].
+pub const SETTINGS_SCHEMA_VERSION: u32 = 2;
+
+type Migration = fn(Value) -> Result<Value, String>;
+
+/// `MIGRATIONS[n]` turns a version `n` document into a version `n + 1` one.
+const MIGRATIONS: &[Migration] = &[migrate_v0_explorer_only, migrate_v1_nested_artifacts];
+
+/// Rolling copies of the previous settings kept as `settings.json.bak.1` (newest) … `.bak.N`.
+const SETTINGS_BACKUPS: usize = 3;
+
+const SECTIONS: [&str; 4] = ["explorer", "dedupe", "content_search", "clean"];
+
+lazy_static! {
+    /// What the last load or save had to migrate, repair or reset.
+    static ref LAST_REPORT: Mutex<Option<SettingsReport>> = Mutex::new(None);
+    /// settings.json as this process last read or wrote it, so the watcher can ignore our
+    /// own writes and duplicate events.
+    static ref KNOWN_CONTENT: Mutex<Option<String>> = Mutex::new(None);
+}
+
+/// A field that held an unusable value and was put back to its default.
+#[derive(Debug, Serialize, Clone)]
+pub struct SettingsFieldReset {
+    /// Dotted path, e.g. `theme.custom_color` or `artifact_rules[2]`.
+    pub field: String,
+    pub reason: String,
+}
+
+/// Emitted on "settings-validation" whenever loading or saving changed what was on disk.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct SettingsReport {
+    /// Version the file declared (or was detected as); `None` when there was no usable file.
+    pub found_version: Option<u32>,
+    pub migrated: bool,
+    /// Parse error when the file wasn't valid JSON and was replaced with defaults.
+    pub unreadable: Option<String>,
+    /// Copy of the original file, kept whenever values were discarded.
+    pub backup_path: Option<String>,
+    pub reset_fields: Vec<SettingsFieldReset>,
+    /// Fields cleaned up without losing anything (e.g. `.PNG` → `png`).
+    pub normalized_fields: Vec<String>,
+}
+
+impl SettingsReport {
+    pub(crate) fn is_empty(&self) -> bool {
+        !self.migrated
+            && self.unreadable.is_none()
+            && self.backup_path.is_none()
+            && self.reset_fields.is_empty()
+            && self.normalized_fields.is_empty()
+    }
+
+    fn discarded_values(&self) -> bool {
+        self.unreadable.is_some() || !self.reset_fields.is_empty()
+    }
+
+    fn reset(&mut self, field: impl Into<String>, reason: impl Into<String>) {
+        self.reset_fields.push(SettingsFieldReset { field: field.into(), reason: reason.into() });
+    }
+}
+
+/// Files without `schema_version` predate versioning: a top-level explorer config is
+/// version 0, anything else the sectioned layout of version 1.
+fn detect_version(doc: &Value) -> u32 {
+    match doc.get("schema_version").and_then(Value::as_u64) {
+        Some(version) => version.min(u32::MAX as u64) as u32,
+        None if doc.get("explorer").is_none() && doc.get("preview_enabled").is_some() => 0,
+        None => 1,
+    }
+}
+
+/// v0 kept one explorer config at the top level; it becomes every section.
+fn migrate_v0_explorer_only(old: Value) -> Result<Value, String> {
+    let mut section = serde_json::to_value(ConfigSection::default()).map_err(|e| e.to_string())?;
+    for key in ["preview_enabled", "show_hidden_files", "show_system_files", "blocked_extensions"] {
+        if let Some(value) = old.get(key) {
+            section[key] = value.clone();
+        }
+    }
+    section["blocked_names"] = Value::Array(Vec::new());
+
+    let mut new = serde_json::to_value(AppSettings::default()).map_err(|e| e.to_string())?;
+    for name in SECTIONS {
+        new[name] = section.clone();
+    }
+    if let Some(done) = old.get("setup_completed") {
+        new["setup_completed"] = done.clone();
+    }
+    Ok(new)
+}
+
+/// Folders v1 artifact rules matched at any depth; `artifact_dirs` is direct children only now.
+const NESTED_ARTIFACT_DIRS: &[&str] = &["__pycache__"];
+
+/// v1 matched `artifact_dirs` anywhere below a project root; caches move to `nested_dirs`.
+fn migrate_v1_nested_artifacts(mut doc: Value) -> Result<Value, String> {
+    if let Some(rules) = doc.get_mut("artifact_rules").and_then(Value::as_array_mut) {
+        for rule in rules.iter_mut().filter_map(Value::as_object_mut) {
+            let Some(dirs) = rule.get_mut("artifact_dirs").and_then(Value::as_array_mut) else { continue };
+            let (nested, direct): (Vec<Value>, Vec<Value>) = dirs
+                .drain(..)
+                .partition(|d| d.as_str().is_some_and(|d| NESTED_ARTIFACT_DIRS.contains(&d)));
+            *dirs = direct;
+            rule.insert("nested_dirs".to_string(), Value::Array(nested));
+        }
+    }
+    Ok(doc)
+}
+
+/// Runs `doc` through the migration chain without filling in missing fields.
+pub(crate) fn migrate_document(mut doc: Value, report: &mut SettingsReport) -> Value {
+    if !doc.is_object() {
+        report.reset("settings", "expected a JSON object");
+        doc = Value::Object(Default::default());
+    }
+    let found = detect_version(&doc);
+    report.found_version = Some(found);
+
+    for (version, migrate) in MIGRATIONS.iter().enumerate().skip(found as usize) {
+        match migrate(doc.clone()) {
+            Ok(next) => {
+                doc = next;
+                report.migrated = true;
+            }
+            Err(e) => {
+                report.reset("settings", format!("migration from version {version} failed: {e}"));
+                break;
+            }
+        }
+    }
+    doc
+}
+
+/// Migrates `doc`, salvages whatever deserializes and validates the result. Documents from a
+/// newer version are read as-is, ignoring fields this build lacks.
+pub(crate) fn upgrade_settings(doc: Value, report: &mut SettingsReport) -> AppSettings {
+    let doc = migrate_document(doc, report);
+    settings_from_document(&doc, &AppSettings::default(), report)
+}
+
+/// Salvages and validates a document that is already at the current version; fields that
+/// don't deserialize take their value from `fallback`.
+pub(crate) fn settings_from_document(doc: &Value, fallback: &AppSettings, report: &mut SettingsReport) -> AppSettings {
+    let mut settings = salvage(doc, fallback, report);
+    settings.schema_version = SETTINGS_SCHEMA_VERSION;
+    validate_settings(&mut settings, report);
+    settings
+}
+
+/// Deserializes `doc`, falling back field by field: a value is kept only while the whole
+/// document still deserializes with it, so one bad field doesn't cost the rest.
+fn salvage(doc: &Value, fallback: &AppSettings, report: &mut SettingsReport) -> AppSettings {
+    if let Ok(settings) = serde_json::from_value::<AppSettings>(doc.clone()) {
+        return settings;
+    }
+    let defaults = serde_json::to_value(fallback).unwrap_or_default();
+    let mut merged = defaults.clone();
+    salvage_fields(&mut merged, &defaults, doc, "", report);
+    serde_json::from_value(merged).unwrap_or_else(|_| fallback.clone())
+}
+
+fn salvage_fields(merged: &mut Value, defaults: &Value, doc: &Value, pointer: &str, report: &mut SettingsReport) {
+    let Some(found) = doc.pointer(pointer).and_then(Value::as_object) else { return };
+    for (key, value) in found {
+        let child = format!("{pointer}/{}", key.replace('~', "~0").replace('/', "~1"));
+        let default = defaults.pointer(&child).cloned();
+        let set = |merged: &mut Value, value: Option<Value>| {
+            if let Some(parent) = merged.pointer_mut(pointer).and_then(Value::as_object_mut) {
+                match value {
+                    Some(value) => parent.insert(key.clone(), value),
+                    None => parent.remove(key),
+                };
+            }
+        };
+        set(merged, Some(value.clone()));
+
+        let error = match serde_json::from_value::<AppSettings>(merged.clone()) {
+            Ok(_) => continue,
+            Err(e) => e,
+        };
+        let descend = default.as_ref().is_some_and(Value::is_object) && value.is_object();
+        set(merged, default);
+        if descend {
+            salvage_fields(merged, defaults, doc, &child, report);
+        } else {
+            let field = if pointer.is_empty() { key.clone() } else { format!("{}.{key}", pointer[1..].replace('/', ".")) };
+            report.reset(field, error.to_string());
+        }
+    }
+}
+
+// ── Validation ──────────────────────────────────────────────────────────────
+
+fn is_hex_color(value: &str) -> bool {
+    value
+        .strip_prefix('#')
+        .is_some_and(|hex| matches!(hex.len(), 3 | 4 | 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Trims, drops empties and duplicates (keeping first occurrences); `map` normalizes each entry.
+fn normalize_list(list: &mut Vec<String>, map: impl Fn(&str) -> String) -> bool {
+    let mut seen = HashSet::new();
+    let normalized: Vec<String> = list
+        .iter()
+        .map(|item| map(item.trim()))
+        .filter(|item| !item.is_empty() && seen.insert(item.clone()))
+        .collect();
+    let changed = normalized != *list;
+    *list = normalized;
+    changed
+}
+
+fn normalize_extension(ext: &str) -> String {
+    ext.trim_start_matches('.').trim().to_lowercase()
+}
+
+/// Cleans up values that deserialized but aren't usable as-is, recording what changed.
+pub(crate) fn validate_settings(settings: &mut AppSettings, report: &mut SettingsReport) {
+    let sections = [
+        ("explorer", &mut settings.explorer),
+        ("dedupe", &mut settings.dedupe),
+        ("content_search", &mut settings.content_search),
+        ("clean", &mut settings.clean),
+    ];
+    for (name, section) in sections {
+        if normalize_list(&mut section.blocked_extensions, normalize_extension) {
+            report.normalized_fields.push(format!("{name}.blocked_extensions"));
+        }
+        if normalize_list(&mut section.blocked_names, str::to_string) {
+            report.normalized_fields.push(format!("{name}.blocked_names"));
         }
     }
+
+    let color = settings.theme.custom_color.trim();
+    if !is_hex_color(color) {
+        report.reset(
+            "theme.custom_color",
+            format!("{:?} is not a hex color like #3b82f6", settings.theme.custom_color),
+        );
+        settings.theme.custom_color = ThemeSettings::default().custom_color;
+    } else if color.len() != settings.theme.custom_color.len() {
+        settings.theme.custom_color = color.to_string();
+        report.normalized_fields.push("theme.custom_color".to_string());
+    }
+
+    let mut index = 0;
+    settings.artifact_rules.retain_mut(|rule| {
+        let mut changed = normalize_list(&mut rule.markers, str::to_string);
+        changed |= normalize_list(&mut rule.artifact_dirs, str::to_string);
+        changed |= normalize_list(&mut rule.nested_dirs, str::to_string);
+        let name = rule.name.trim();
+        if name.len() != rule.name.len() {
+            rule.name = name.to_string();
+            changed = true;
+        }
+
+        let problem = if rule.name.is_empty() {
+            Some("rule has no name")
+        } else if rule.markers.is_empty() {
+            Some("rule has no marker files")
+        } else if rule.artifact_dirs.is_empty() && rule.nested_dirs.is_empty() {
+            Some("rule has no artifact folders")
+        } else {
+            None
+        };
+        let field = format!("artifact_rules[{index}]");
+        index += 1;
+        match problem {
+            Some(reason) => {
+                report.reset(field, reason);
+                false
+            }
+            None => {
+                if changed {
+                    report.normalized_fields.push(field);
+                }
+                true
+            }
+        }
+    });
+
+    validate_categories(settings, report);
+    validate_exclusions(&mut settings.exclusions, report);
 }
 
-fn get_settings_path(app: &AppHandle) -> Result<PathBuf, String> {
+fn validate_exclusions(exclusions: &mut ExclusionSettings, report: &mut SettingsReport) {
+    for (name, paths) in [
+        ("protected_paths", &mut exclusions.protected_paths),
+        ("excluded_paths", &mut exclusions.excluded_paths),
+    ] {
+        let changed = normalize_list(paths, str::to_string);
+        let count = paths.len();
+        paths.retain(|path| Path::new(path).is_absolute());
+        if paths.len() != count {
+            report.reset(format!("exclusions.{name}"), "paths must be absolute");
+        } else if changed {
+            report.normalized_fields.push(format!("exclusions.{name}"));
+        }
+    }
+
+    let changed = normalize_list(&mut exclusions.excluded_globs, str::to_string);
+    let count = exclusions.excluded_globs.len();
+    exclusions.excluded_globs.retain(|glob| globset::Glob::new(glob).is_ok());
+    if exclusions.excluded_globs.len() != count {
+        report.reset("exclusions.excluded_globs", "invalid glob pattern");
+    } else if changed {
+        report.normalized_fields.push("exclusions.excluded_globs".to_string());
+    }
+}
+
+/// Category ids are lowercase words joined by `-`, so they work as `preview_enabled` keys.
+fn category_id(raw: &str) -> String {
+    raw.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn validate_categories(settings: &mut AppSettings, report: &mut SettingsReport) {
+    let mut seen = HashSet::new();
+    let mut index = 0;
+    settings.categories.retain_mut(|category| {
+        let field = format!("categories[{index}]");
+        index += 1;
+
+        let id = category_id(if category.id.trim().is_empty() { &category.name } else { &category.id });
+        let mut changed = id != category.id;
+        category.id = id;
+        let name = category.name.trim();
+        if name.is_empty() || name.len() != category.name.len() {
+            category.name = if name.is_empty() { category.id.clone() } else { name.to_string() };
+            changed = true;
+        }
+        changed |= normalize_list(&mut category.extensions, normalize_extension);
+        changed |= normalize_list(&mut category.mime_types, str::to_lowercase);
+
+        let signatures = category.signatures.len();
+        category.signatures.retain(|signature| {
+            categories::decode_hex(&signature.hex)
+                .is_some_and(|bytes| signature.offset.saturating_add(bytes.len() as u64) <= MAX_SIGNATURE_END as u64)
+        });
+        if category.signatures.len() != signatures {
+            report.reset(
+                format!("{field}.signatures"),
+                "signatures must be hex bytes within the first 64 KB of the file",
+            );
+        }
+
+        if category.id.is_empty() {
+            report.reset(field, "category has no name");
+            return false;
+        }
+        if !seen.insert(category.id.clone()) {
+            report.reset(field, format!("duplicate category id {:?}", category.id));
+            return false;
+        }
+        if changed {
+            report.normalized_fields.push(field);
+        }
+        true
+    });
+
+    for builtin in builtin_definitions() {
+        if !seen.contains(&builtin.id) {
+            report.reset(format!("categories.{}", builtin.id), "built-in categories can't be removed");
+            settings.categories.push(builtin);
+        }
+    }
+}
+
+// ── Load / save ─────────────────────────────────────────────────────────────
+
+pub(crate) fn get_settings_path<R: tauri::Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
     let mut path = app.path().app_config_dir().map_err(|e| e.to_string())?;
     if !path.exists() {
         fs::create_dir_all(&path).map_err(|e| e.to_string())?;
@@ -138,55 +627,205 @@ fn get_settings_path(app: &AppHandle) -> Result<PathBuf, String> {
     Ok(path)
 }
 
-#[tauri::command]
-pub fn load_settings(app: AppHandle) -> Result<AppSettings, String> {
-    let path = get_settings_path(&app)?;
+/// Copies `path` next to itself as `settings.<label>-<timestamp>.json`.
+fn backup_settings_file(path: &Path, label: &str) -> Result<PathBuf, String> {
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let backup = path.with_file_name(format!("settings.{label}-{stamp}.json"));
+    fs::copy(path, &backup).map_err(|e| format!("Failed to back up settings: {e}"))?;
+    Ok(backup)
+}
+
+/// `path` with `suffix` appended to its file name.
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Shifts `.bak.1` … `.bak.N` up by one, dropping the oldest, and copies `path` to `.bak.1`.
+fn rotate_backups(path: &Path) {
+    for n in (1..SETTINGS_BACKUPS).rev() {
+        let _ = fs::rename(sibling(path, &format!(".bak.{n}")), sibling(path, &format!(".bak.{}", n + 1)));
+    }
+    let _ = fs::copy(path, sibling(path, ".bak.1"));
+}
+
+/// Replaces `path` atomically: the content is written and fsynced to a temp file that is then
+/// renamed over the original, so a crash leaves either the old or the new file intact.
+pub(crate) fn write_atomic(path: &Path, content: &str) -> std::io::Result<()> {
+    let tmp = sibling(path, ".tmp");
+    let result = (|| {
+        let mut file = File::create(&tmp)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&tmp, path)?;
+        #[cfg(unix)]
+        if let Some(dir) = path.parent() {
+            File::open(dir)?.sync_all()?;
+        }
+        Ok(())
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+pub(crate) fn write_settings_file(path: &Path, settings: &AppSettings) -> Result<(), String> {
+    categories::set_categories(&settings.categories);
+    exclusion::set_exclusions(&settings.exclusions);
+    let content = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    let mut known = KNOWN_CONTENT.lock().unwrap();
+    if fs::read_to_string(path).is_ok_and(|current| current == content) {
+        *known = Some(content);
+        return Ok(());
+    }
+    if path.exists() {
+        rotate_backups(path);
+    }
+    write_atomic(path, &content).map_err(|e| format!("Failed to write settings: {e}"))?;
+    *known = Some(content);
+    Ok(())
+}
+
+/// Writes repaired settings back, first backing up the original if values were lost.
+/// Files from a newer version are never rewritten.
+fn store_repairs(path: &Path, settings: &AppSettings, report: &mut SettingsReport) -> Result<(), String> {
+    let newer = report.found_version.is_some_and(|v| v > SETTINGS_SCHEMA_VERSION);
+    if report.is_empty() || newer {
+        return Ok(());
+    }
+    if report.discarded_values() {
+        report.backup_path = Some(backup_settings_file(path, "corrupt")?.to_string_lossy().into_owned());
+    }
+    write_settings_file(path, settings)
+}
+
+/// Reads, migrates and validates the settings file, writing back any repairs.
+pub(crate) fn read_settings_file(path: &Path) -> Result<(AppSettings, SettingsReport), String> {
+    let mut report = SettingsReport::default();
     if !path.exists() {
-        return Ok(AppSettings::default());
+        return Ok((AppSettings::default(), report));
     }
-    
+
     let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
-    
-    // Attempt to parse as new structure first
-    if let Ok(settings) = serde_json::from_str::<AppSettings>(&content) {
-        return Ok(settings);
-    }
-    
-    // Migration: Attempt to parse as old ExplorerSettings and move to explorer section
-    #[derive(Debug, Serialize, Deserialize, Clone)]
-    pub struct OldExplorerSettings {
-        pub preview_enabled: PreviewSettings,
-        pub show_hidden_files: bool,
-        pub show_system_files: bool,
-        pub blocked_extensions: Vec<String>,
-        pub setup_completed: bool,
-    }
-
-    if let Ok(old) = serde_json::from_str::<OldExplorerSettings>(&content) {
-        let mut new = AppSettings::default();
-        new.explorer = ConfigSection {
-            preview_enabled: old.preview_enabled,
-            show_hidden_files: old.show_hidden_files,
-            show_system_files: old.show_system_files,
-            blocked_extensions: old.blocked_extensions,
-            blocked_names: Vec::new(),
-            include_plain_text_in_duplicate_scan: false,
-        };
-        new.dedupe = new.explorer.clone();
-        new.content_search = new.explorer.clone();
-        new.clean = new.explorer.clone();
-        new.theme = ThemeSettings::default();
-        new.setup_completed = old.setup_completed;
-        return Ok(new);
+    *KNOWN_CONTENT.lock().unwrap() = Some(content.clone());
+    let settings = match serde_json::from_str::<Value>(&content) {
+        Ok(doc) => upgrade_settings(doc, &mut report),
+        Err(e) => {
+            report.unreadable = Some(e.to_string());
+            AppSettings::default()
+        }
+    };
+    store_repairs(path, &settings, &mut report)?;
+    categories::set_categories(&settings.categories);
+    exclusion::set_exclusions(&settings.exclusions);
+    Ok((settings, report))
+}
+
+pub(crate) fn publish_report(app: &AppHandle, report: SettingsReport) {
+    if !report.is_empty() {
+        let _ = app.emit("settings-validation", &report);
     }
+    *LAST_REPORT.lock().unwrap() = Some(report);
+}
+
+#[tauri::command]
+pub fn load_settings(app: AppHandle) -> Result<AppSettings, String> {
+    let path = get_settings_path(&app)?;
+    let (settings, report) = read_settings_file(&path)?;
+    publish_report(&app, report);
+    Ok(settings)
+}
 
-    Ok(AppSettings::default())
+/// Saves and emits "settings-changed" so other windows pick the change up. A file written by
+/// a newer version is backed up first, since saving drops the fields this build doesn't know.
+#[tauri::command]
+pub fn save_settings(app: AppHandle, mut settings: AppSettings) -> Result<(), String> {
+    let path = get_settings_path(&app)?;
+    let mut report = SettingsReport::default();
+    let on_disk = fs::read_to_string(&path).ok().and_then(|content| serde_json::from_str::<Value>(&content).ok());
+    if let Some(found) = on_disk.map(|doc| detect_version(&doc)).filter(|&v| v > SETTINGS_SCHEMA_VERSION) {
+        report.found_version = Some(found);
+        report.backup_path = Some(backup_settings_file(&path, "newer")?.to_string_lossy().into_owned());
+    }
+    settings.schema_version = SETTINGS_SCHEMA_VERSION;
+    validate_settings(&mut settings, &mut report);
+    write_settings_file(&path, &settings)?;
+    if !report.is_empty() {
+        publish_report(&app, report);
+    }
+    let _ = app.emit("settings-changed", &settings);
+    Ok(())
 }
 
+/// The report from the most recent load or save, for UIs that start listening late.
 #[tauri::command]
-pub fn save_settings(app: AppHandle, settings: AppSettings) -> Result<(), String> {
+pub fn get_settings_report() -> Option<SettingsReport> {
+    LAST_REPORT.lock().unwrap().clone()
+}
+
+// ── External changes ────────────────────────────────────────────────────────
+
+/// Editors save in several steps; events closer together than this are handled once.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Watches the settings folder and emits "settings-changed" with the new [AppSettings]
+/// whenever settings.json is changed outside this process. The folder is watched rather than
+/// the file because atomic saves (ours and most editors') replace the file.
+pub fn watch_settings(app: AppHandle) -> Result<(), String> {
     let path = get_settings_path(&app)?;
-    let content = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
-    fs::write(path, content).map_err(|e| e.to_string())?;
+    let dir = path.parent().ok_or("Settings file has no parent folder")?.to_path_buf();
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender).map_err(|e| e.to_string())?;
+    watcher.watch(&dir, RecursiveMode::NonRecursive).map_err(|e| e.to_string())?;
+
+    std::thread::spawn(move || {
+        let _watcher = watcher;
+        let touches = |event: &notify::Result<Event>| {
+            event.as_ref().is_ok_and(|event| {
+                !matches!(event.kind, EventKind::Access(_))
+                    && event.paths.iter().any(|p| p.file_name() == path.file_name())
+            })
+        };
+        while let Ok(event) = receiver.recv() {
+            let mut changed = touches(&event);
+            while let Ok(event) = receiver.recv_timeout(WATCH_DEBOUNCE) {
+                changed |= touches(&event);
+            }
+            if changed {
+                reload_changed_settings(&app, &path);
+            }
+        }
+    });
     Ok(())
 }
+
+/// Re-reads settings.json after an external change. Invalid JSON is reported but left alone,
+/// since it is usually a hand edit in progress.
+fn reload_changed_settings(app: &AppHandle, path: &Path) {
+    let Ok(content) = fs::read_to_string(path) else { return };
+    {
+        let mut known = KNOWN_CONTENT.lock().unwrap();
+        if known.as_deref() == Some(content.as_str()) {
+            return;
+        }
+        *known = Some(content.clone());
+    }
+
+    let doc = match serde_json::from_str::<Value>(&content) {
+        Ok(doc) => doc,
+        Err(e) => {
+            let report = SettingsReport { unreadable: Some(e.to_string()), ..Default::default() };
+            publish_report(app, report);
+            return;
+        }
+    };
+    let mut report = SettingsReport::default();
+    let settings = upgrade_settings(doc, &mut report);
+    let _ = store_repairs(path, &settings, &mut report);
+    categories::set_categories(&settings.categories);
+    exclusion::set_exclusions(&settings.exclusions);
+    publish_report(app, report);
+    let _ = app.emit("settings-changed", &settings);
+}
@@ -0,0 +1,273 @@
+use lazy_static::lazy_static;
+use memmap2::Mmap;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use crate::utils::text_encoding::{
+    align_backward, align_forward, decode, detect_encoding, detect_line_ending, LineEnding, TextEncoding, SNIFF_BYTES,
+};
+
+/// Upper bound for one `read_text_range` call, whatever the frontend asks for.
+const MAX_RANGE_BYTES: usize = 4 * 1024 * 1024; // 4 MB
+/// Upper bound for one `read_lines` call.
+const MAX_LINES_PER_READ: u64 = 10_000;
+/// Single lines longer than this are cut (minified JSON, binary junk) so a page stays small.
+const MAX_LINE_BYTES: usize = 64 * 1024;
+/// A checkpoint (byte offset of the line start) is kept every N lines.
+const LINE_INDEX_STRIDE: u64 = 1_000;
+/// Indexes are kept for this many recently viewed files.
+const MAX_INDEXED_FILES: usize = 32;
+
+lazy_static! {
+    static ref LINE_INDEXES: Mutex<HashMap<PathBuf, LineIndex>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct TextChunk {
+    pub text: String,
+    /// Byte offset the text actually starts at (aligned to a character boundary).
+    pub offset: u64,
+    /// Byte offset to pass as `offset` for the next chunk.
+    pub next_offset: u64,
+    pub file_size: u64,
+    pub encoding: TextEncoding,
+    pub line_ending: LineEnding,
+    pub eof: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct TextLines {
+    pub lines: Vec<String>,
+    pub start_line: u64,
+    /// Known once the index has reached the end of the file (always after a tail read).
+    pub total_lines: Option<u64>,
+    pub file_size: u64,
+    pub encoding: TextEncoding,
+    pub line_ending: LineEnding,
+    pub eof: bool,
+}
+
+/// Sparse line index: line-start offsets every [LINE_INDEX_STRIDE] lines, built lazily as far
+/// as reads need it. Growth of the file is treated as an append and indexed incrementally, so
+/// tailing a growing log never rescans it; a shrink or rewrite starts over.
+#[derive(Debug, Clone)]
+struct LineIndex {
+    encoding: TextEncoding,
+    line_ending: LineEnding,
+    newline: u8,
+    checkpoints: Vec<u64>,
+    /// Newlines counted in `[bom, indexed_bytes)`.
+    lines: u64,
+    indexed_bytes: u64,
+    last_line_start: u64,
+    file_size: u64,
+    modified: Option<SystemTime>,
+    last_used: SystemTime,
+}
+
+impl LineIndex {
+    fn new(bytes: &[u8], modified: Option<SystemTime>) -> Self {
+        let sample = &bytes[..bytes.len().min(SNIFF_BYTES)];
+        let encoding = detect_encoding(sample);
+        let line_ending = detect_line_ending(&sample[encoding.bom_len().min(sample.len())..], encoding);
+        let start = encoding.bom_len().min(bytes.len()) as u64;
+        Self {
+            encoding,
+            line_ending,
+            // Classic Mac files break on CR; everything else on LF (CRLF's CR is trimmed).
+            newline: if line_ending == LineEnding::Cr { b'\r' } else { b'\n' },
+            checkpoints: vec![start],
+            lines: 0,
+            indexed_bytes: start,
+            last_line_start: start,
+            file_size: bytes.len() as u64,
+            modified,
+            last_used: SystemTime::now(),
+        }
+    }
+
+    fn complete(&self) -> bool {
+        self.indexed_bytes >= self.file_size
+    }
+
+    /// Lines in the file, counting a final line without a terminator.
+    fn total_lines(&self) -> Option<u64> {
+        self.complete()
+            .then(|| self.lines + u64::from(self.file_size > self.last_line_start))
+    }
+
+    /// Scans forward until `want_lines` newlines are indexed (or to EOF when `None`).
+    fn extend(&mut self, bytes: &[u8], want_lines: Option<u64>) {
+        self.file_size = bytes.len() as u64;
+        let mut pos = self.indexed_bytes as usize;
+        while want_lines.is_none_or(|w| self.lines < w) {
+            match next_line_start(bytes, pos, self.encoding, self.newline) {
+                Some(next) => {
+                    self.lines += 1;
+                    self.last_line_start = next as u64;
+                    if self.lines.is_multiple_of(LINE_INDEX_STRIDE) {
+                        self.checkpoints.push(next as u64);
+                    }
+                    pos = next;
+                }
+                None => {
+                    pos = bytes.len();
+                    break;
+                }
+            }
+        }
+        self.indexed_bytes = pos as u64;
+    }
+
+    /// Byte offset where `line` starts, if the file has that many lines.
+    fn line_start(&self, bytes: &[u8], line: u64) -> Option<usize> {
+        let checkpoint = (line / LINE_INDEX_STRIDE) as usize;
+        let mut pos = *self.checkpoints.get(checkpoint)? as usize;
+        for _ in 0..(line % LINE_INDEX_STRIDE) {
+            pos = next_line_start(bytes, pos, self.encoding, self.newline)?;
+        }
+        Some(pos)
+    }
+}
+
+/// Start of the line after the one containing `from`, honouring UTF-16 code-unit alignment.
+fn next_line_start(bytes: &[u8], from: usize, encoding: TextEncoding, newline: u8) -> Option<usize> {
+    let mut cur = from;
+    loop {
+        let p = cur + memchr::memchr(newline, bytes.get(cur..)?)?;
+        match encoding {
+            TextEncoding::Utf16Le if p.is_multiple_of(2) && bytes.get(p + 1) == Some(&0) => return Some(p + 2),
+            TextEncoding::Utf16Be if !p.is_multiple_of(2) && bytes[p - 1] == 0 => return Some(p + 1),
+            TextEncoding::Utf16Le | TextEncoding::Utf16Be => cur = p + 1,
+            _ => return Some(p + 1),
+        }
+    }
+}
+
+fn map_file(p: &Path) -> Result<(Option<Mmap>, std::fs::Metadata), String> {
+    let file = File::open(p).map_err(|e| format!("Failed to open file: {}", e))?;
+    let meta = file.metadata().map_err(|e| format!("Failed to read file: {}", e))?;
+    if !meta.is_file() {
+        return Err("Not a file".to_string());
+    }
+    // Zero-length maps fail on some platforms.
+    if meta.len() == 0 {
+        return Ok((None, meta));
+    }
+    // SAFETY: the map is only read; a concurrent truncation by another process can fault, the
+    // same trade-off every mmap-based viewer makes for large files.
+    let map = unsafe { Mmap::map(&file) }.map_err(|e| format!("Failed to map file: {}", e))?;
+    Ok((Some(map), meta))
+}
+
+/// Reads up to `max_bytes` of decoded text starting near `offset`. Both ends are moved to
+/// character boundaries; use `next_offset` to continue.
+#[tauri::command]
+pub async fn read_text_range(path: String, offset: u64, max_bytes: usize) -> Result<TextChunk, String> {
+    tokio::task::spawn_blocking(move || {
+        let (map, meta) = map_file(Path::new(&path))?;
+        let bytes: &[u8] = map.as_deref().unwrap_or(&[]);
+        let sample = &bytes[..bytes.len().min(SNIFF_BYTES)];
+        let encoding = detect_encoding(sample);
+        let line_ending = detect_line_ending(&sample[encoding.bom_len().min(sample.len())..], encoding);
+
+        let start = align_forward(bytes, offset.min(meta.len()) as usize, encoding);
+        let wanted = start.saturating_add(max_bytes.clamp(1, MAX_RANGE_BYTES));
+        let mut end = align_backward(bytes, wanted, encoding);
+        if end <= start && start < bytes.len() {
+            // A range smaller than one character still makes progress.
+            end = align_forward(bytes, start + 1, encoding).max(start + 1).min(bytes.len());
+        }
+
+        Ok(TextChunk {
+            text: decode(&bytes[start..end], encoding),
+            offset: start as u64,
+            next_offset: end as u64,
+            file_size: meta.len(),
+            encoding,
+            line_ending,
+            eof: end >= bytes.len(),
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Reads `count` lines from `start_line` (0-based). With `start_line` omitted, returns the last
+/// `count` lines, which is how the viewer tails a growing file.
+#[tauri::command]
+pub async fn read_lines(path: String, start_line: Option<u64>, count: u64) -> Result<TextLines, String> {
+    tokio::task::spawn_blocking(move || {
+        let p = PathBuf::from(&path);
+        let (map, meta) = map_file(&p)?;
+        let bytes: &[u8] = map.as_deref().unwrap_or(&[]);
+        let count = count.clamp(1, MAX_LINES_PER_READ);
+        let modified = meta.modified().ok();
+
+        // Scanning a big file takes a while; take the index out so other files' reads aren't
+        // blocked on the map lock meanwhile. A concurrent read of this file builds its own.
+        let cached = LINE_INDEXES.lock().unwrap().remove(&p);
+        let mut index = cached.unwrap_or_else(|| LineIndex::new(bytes, modified));
+        let appended = meta.len() >= index.file_size && meta.len() >= index.indexed_bytes;
+        if !appended || (meta.len() == index.file_size && modified != index.modified) {
+            index = LineIndex::new(bytes, modified);
+        }
+        index.modified = modified;
+        index.last_used = SystemTime::now();
+
+        let start_line = match start_line {
+            Some(line) => {
+                index.extend(bytes, Some(line.saturating_add(count)));
+                line
+            }
+            None => {
+                index.extend(bytes, None);
+                index.total_lines().unwrap_or(0).saturating_sub(count)
+            }
+        };
+
+        let mut lines = Vec::new();
+        if let Some(mut pos) = index.line_start(bytes, start_line) {
+            let unit = if index.encoding.is_utf16() { 2 } else { 1 };
+            while (lines.len() as u64) < count && pos < bytes.len() {
+                let next = next_line_start(bytes, pos, index.encoding, index.newline);
+                let line_end = next.map(|n| n - unit).unwrap_or(bytes.len());
+                let cut = align_backward(bytes, line_end.min(pos + MAX_LINE_BYTES), index.encoding);
+                let mut text = decode(&bytes[pos..cut], index.encoding);
+                if text.ends_with('\r') {
+                    text.pop();
+                }
+                lines.push(text);
+                match next {
+                    Some(n) => pos = n,
+                    None => break,
+                }
+            }
+        }
+
+        let result = TextLines {
+            eof: index.complete() && start_line + lines.len() as u64 >= index.total_lines().unwrap_or(0),
+            total_lines: index.total_lines(),
+            start_line,
+            lines,
+            file_size: meta.len(),
+            encoding: index.encoding,
+            line_ending: index.line_ending,
+        };
+
+        let mut indexes = LINE_INDEXES.lock().unwrap();
+        indexes.insert(p, index);
+        if indexes.len() > MAX_INDEXED_FILES {
+            if let Some(oldest) = indexes.iter().min_by_key(|(_, i)| i.last_used).map(|(k, _)| k.clone()) {
+                indexes.remove(&oldest);
+            }
+        }
+        Ok(result)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -1,88 +1,51 @@
-use base64::{engine::general_purpose, Engine as _};
 use image::imageops::FilterType;
-use sha2::{Digest, Sha256};
-use std::path::{Path, PathBuf};
+use image::DynamicImage;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::path::Path;
 use std::process::Stdio;
-use std::time::UNIX_EPOCH;
-use lazy_static::lazy_static;
-use tauri::Manager;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Once};
+use tauri::{Emitter, Manager};
 
-/// Max file size (bytes) to decode for thumbnails — avoids memory spikes on huge RAWs/DSLR.
+use crate::commands::archive::{read_entry_bytes, ArchiveError};
+use crate::commands::archive_password::no_password;
+use crate::commands::operation::{register_operation_with_paths, unregister_operation};
+use crate::utils::audio_art::{embedded_cover, waveform_peaks};
+use crate::utils::file_types::{get_file_category, FileCategory};
+use crate::utils::image_decode::{decode_jpeg_scaled, embedded_preview, exif_orientation, is_raw_extension, read_exif};
+use crate::utils::thumbnail_cache::{ThumbKey, Thumb, ThumbnailCache, ThumbnailError};
+
+/// JPEGs above this size are decoded at reduced scale instead of full resolution.
 const MAX_THUMBNAIL_DECODE_BYTES: u64 = 25 * 1024 * 1024; // 25 MB
+/// Allocation ceiling for a full decode — fails cleanly instead of spiking memory on huge images.
+const MAX_DECODE_ALLOC_BYTES: u64 = 768 * 1024 * 1024; // 768 MB
+/// Archive entries are read into memory before decoding; larger ones get no thumbnail.
+const MAX_ARCHIVE_ENTRY_DECODE_BYTES: u64 = 64 * 1024 * 1024; // 64 MB
+/// Embedded office-document previews are small; anything bigger is skipped.
+const MAX_EMBEDDED_PREVIEW_BYTES: u64 = 20 * 1024 * 1024; // 20 MB
 
-/// Video thumbnail cache limits (for 5TB-scale: keep cache bounded).
-const VIDEO_CACHE_DIR_NAME: &str = "video_thumbnails";
-const MAX_CACHE_FILES: usize = 2000;
-const MAX_CACHE_BYTES: u64 = 400 * 1024 * 1024; // 400 MB
-const MEMORY_CACHE_CAP: u64 = 80;
+const THUMBNAIL_CACHE_DIR_NAME: &str = "thumbnails";
+/// Pre-shared-cache location of video GIFs; removed on first use of the new cache.
+const LEGACY_VIDEO_CACHE_DIR_NAME: &str = "video_thumbnails";
 const VIDEO_THUMB_DURATION_SECS: &str = "2";
+/// Low rate is plenty for a peak envelope and keeps ffmpeg output small for long tracks.
+const WAVEFORM_SAMPLE_RATE: u32 = 4000;
+const WAVEFORM_BAR_WIDTH: u32 = 2;
+const WAVEFORM_BAR_GAP: u32 = 1;
+const WAVEFORM_COLOR: [u8; 4] = [96, 165, 250, 255];
 
-lazy_static! {
-    /// In-memory LRU for same-session repeat requests (avoids disk I/O).
-    static ref MEMORY_CACHE: moka::sync::Cache<String, String> = moka::sync::Cache::builder()
-        .max_capacity(MEMORY_CACHE_CAP)
-        .build();
-}
+static LEGACY_CACHE_CLEANED: Once = Once::new();
 
-fn video_cache_key(path: &str, width: u32, height: u32, mtime_nanos: u64) -> String {
-    let mut hasher = Sha256::new();
-    hasher.update(path.as_bytes());
-    hasher.update(&[0]);
-    hasher.update(width.to_string().as_bytes());
-    hasher.update(&[0]);
-    hasher.update(height.to_string().as_bytes());
-    hasher.update(&[0]);
-    hasher.update(mtime_nanos.to_string().as_bytes());
-    hex::encode(hasher.finalize())
-}
-
-fn video_cache_dir(app: &tauri::AppHandle) -> PathBuf {
-    app.path()
+pub(crate) fn thumbnail_cache(app: &tauri::AppHandle) -> ThumbnailCache {
+    let base = app
+        .path()
         .app_cache_dir()
-        .map(|p| p.join(VIDEO_CACHE_DIR_NAME))
-        .unwrap_or_else(|_| {
-            std::env::temp_dir()
-                .join("smart-desktop-manager")
-                .join(VIDEO_CACHE_DIR_NAME)
-        })
-}
-
-/// Cache file extension: zstd-compressed GIF to save disk (fast encode/decode, minimal latency).
-const CACHE_EXT: &str = "zst";
-/// zstd level 1 = fast, still good ratio for GIFs.
-const ZSTD_LEVEL: i32 = 1;
-
-/// Evict oldest cache files by mtime until under MAX_CACHE_FILES and MAX_CACHE_BYTES.
-fn evict_disk_cache_if_needed(cache_dir: &Path, new_file_size: u64) {
-    let entries: Vec<_> = match std::fs::read_dir(cache_dir) {
-        Ok(rd) => rd
-            .filter_map(|e| e.ok())
-            .filter_map(|e| {
-                let path = e.path();
-                let ext = path.extension().and_then(|x| x.to_str());
-                if ext == Some(CACHE_EXT) || ext == Some("gif") {
-                    let meta = std::fs::metadata(&path).ok()?;
-                    let mtime = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?.as_nanos() as u64;
-                    Some((path, mtime, meta.len()))
-                } else {
-                    None
-                }
-            })
-            .collect(),
-        Err(_) => return,
-    };
-    let mut total: u64 = entries.iter().map(|(_, _, len)| len).sum::<u64>() + new_file_size;
-    let mut count = entries.len() + 1;
-    let mut by_age: Vec<_> = entries.into_iter().map(|(p, mtime, len)| (p, mtime, len)).collect();
-    by_age.sort_by_key(|(_, mtime, _)| *mtime);
-    for (path, _, len) in by_age {
-        if count <= MAX_CACHE_FILES && total <= MAX_CACHE_BYTES {
-            break;
-        }
-        let _ = std::fs::remove_file(&path);
-        count = count.saturating_sub(1);
-        total = total.saturating_sub(len);
-    }
+        .unwrap_or_else(|_| std::env::temp_dir().join("smart-desktop-manager"));
+    LEGACY_CACHE_CLEANED.call_once(|| {
+        let _ = std::fs::remove_dir_all(base.join(LEGACY_VIDEO_CACHE_DIR_NAME));
+    });
+    ThumbnailCache::new(base.join(THUMBNAIL_CACHE_DIR_NAME))
 }
 
 /// Common locations for ffmpeg when the app is launched from a bundle (e.g. .app) and has minimal PATH.
@@ -93,6 +56,14 @@ fn ffmpeg_paths_to_try() -> Vec<&'static str> {
     return vec!["ffmpeg"];
 }
 
+/// Same probing as [ffmpeg_paths_to_try] for other optional command-line renderers.
+pub(crate) fn renderer_paths_to_try(bin: &'static str) -> Vec<String> {
+    #[cfg(target_os = "macos")]
+    return vec![format!("/opt/homebrew/bin/{}", bin), format!("/usr/local/bin/{}", bin), bin.to_string()];
+    #[cfg(not(target_os = "macos"))]
+    return vec![bin.to_string()];
+}
+
 /// Extract multiple frames as a short animated GIF for video preview (requires ffmpeg on PATH).
 /// Uses first 2 seconds at 2 fps (4 frames). Results are cached on disk (with size limits) and in memory.
 #[tauri::command]
@@ -102,49 +73,36 @@ pub async fn get_video_thumbnail(
     width: u32,
     height: u32,
 ) -> Result<String, String> {
-    let p = Path::new(&path);
+    let cache = thumbnail_cache(&app);
+    tokio::task::spawn_blocking(move || video_thumbnail(&cache, Path::new(&path), width, height))
+        .await
+        .map_err(|e| e.to_string())?
+        .map(|thumb| thumb.to_data_url())
+}
+
+pub(crate) fn video_thumbnail(
+    cache: &ThumbnailCache,
+    p: &Path,
+    width: u32,
+    height: u32,
+) -> Result<Arc<Thumb>, String> {
     if !p.exists() || !p.is_file() {
         return Err("File not found".to_string());
     }
-    let mtime_nanos = std::fs::metadata(p)
-        .map_err(|e| format!("Failed to read file metadata: {}", e))?
-        .modified()
-        .map_err(|e| format!("Failed to get mtime: {}", e))?
-        .duration_since(UNIX_EPOCH)
-        .map_err(|e| format!("Invalid mtime: {}", e))?
-        .as_nanos() as u64;
-    let cache_key = video_cache_key(&path, width, height, mtime_nanos);
-    let cache_dir = video_cache_dir(&app);
-
-    if let Some(data_url) = MEMORY_CACHE.get(&cache_key) {
-        return Ok(data_url);
-    }
-
-    let cache_path_zst = cache_dir.join(format!("{}.{}", cache_key, CACHE_EXT));
-    let cache_path_gif = cache_dir.join(format!("{}.gif", cache_key));
-    if cache_path_zst.exists() {
-        let compressed = std::fs::read(&cache_path_zst).map_err(|e| format!("Failed to read cache: {}", e))?;
-        let bytes = zstd::decode_all(compressed.as_slice()).map_err(|e| format!("Failed to decompress cache: {}", e))?;
-        let data_url = format!("data:image/gif;base64,{}", general_purpose::STANDARD.encode(&bytes));
-        MEMORY_CACHE.insert(cache_key.clone(), data_url.clone());
-        return Ok(data_url);
-    }
-    if cache_path_gif.exists() {
-        let bytes = std::fs::read(&cache_path_gif).map_err(|e| format!("Failed to read cache: {}", e))?;
-        let data_url = format!("data:image/gif;base64,{}", general_purpose::STANDARD.encode(&bytes));
-        MEMORY_CACHE.insert(cache_key.clone(), data_url.clone());
-        return Ok(data_url);
-    }
-
-    let _ = std::fs::create_dir_all(&cache_dir);
+    let key = ThumbKey::for_file(p, width, height, "video")?;
+    if let Some(thumb) = cache.get(&key) {
+        return Ok(thumb);
+    }
+
     let filter = format!(
         "fps=2,scale={}:{}:force_original_aspect_ratio=decrease,pad={}:{}:(ow-iw)/2:(oh-ih)/2:color=black",
         width, height, width, height
     );
+    let path = p.to_string_lossy();
     let args = [
         "-y",
         "-i",
-        path.as_str(),
+        path.as_ref(),
         "-vf",
         filter.as_str(),
         "-t",
@@ -156,25 +114,13 @@ pub async fn get_video_thumbnail(
     let mut last_err = String::new();
     for ffmpeg_bin in ffmpeg_paths_to_try() {
         let result = std::process::Command::new(ffmpeg_bin)
-            .args(&args)
+            .args(args)
             .stdout(Stdio::piped())
             .stderr(Stdio::null())
             .output();
         match result {
             Ok(output) if output.status.success() => {
-                let bytes = output.stdout;
-                let compressed = match zstd::encode_all(bytes.as_slice(), ZSTD_LEVEL) {
-                    Ok(c) => c,
-                    Err(_) => bytes.clone(),
-                };
-                let size_for_eviction = compressed.len() as u64;
-                evict_disk_cache_if_needed(&cache_dir, size_for_eviction);
-                if std::fs::write(&cache_path_zst, &compressed).is_err() {
-                    // non-fatal: still return the thumbnail
-                }
-                let data_url = format!("data:image/gif;base64,{}", general_purpose::STANDARD.encode(&bytes));
-                MEMORY_CACHE.insert(cache_key, data_url.clone());
-                return Ok(data_url);
+                return Ok(cache.put(&key, Thumb { bytes: output.stdout, mime: "image/gif" }));
             }
             Ok(_) => continue,
             Err(e) => last_err = e.to_string(),
@@ -183,28 +129,477 @@ pub async fn get_video_thumbnail(
     Err(format!("ffmpeg not available: {}", last_err))
 }
 
+/// Album art for audio files, or a waveform of the decoded samples when there is none
+/// (waveform requires ffmpeg on PATH). Cached like video thumbnails.
 #[tauri::command]
-pub async fn get_thumbnail(path: String, width: u32, height: u32) -> Result<String, String> {
-    let p = Path::new(&path);
-    if !p.exists() {
-        return Err("File not found".to_string());
+pub async fn get_audio_thumbnail(
+    app: tauri::AppHandle,
+    path: String,
+    width: u32,
+    height: u32,
+) -> Result<String, ThumbnailError> {
+    let cache = thumbnail_cache(&app);
+    tokio::task::spawn_blocking(move || audio_thumbnail(&cache, Path::new(&path), width, height))
+        .await
+        .map_err(|e| ThumbnailError::Io(e.to_string()))?
+        .map(|thumb| thumb.to_data_url())
+}
+
+pub(crate) fn audio_thumbnail(
+    cache: &ThumbnailCache,
+    p: &Path,
+    width: u32,
+    height: u32,
+) -> Result<Arc<Thumb>, ThumbnailError> {
+    if !p.exists() || !p.is_file() {
+        return Err(ThumbnailError::NotFound("File not found".to_string()));
+    }
+    let key = ThumbKey::for_file(p, width, height, "audio").map_err(ThumbnailError::Io)?;
+    if let Some(thumb) = cache.get(&key) {
+        return Ok(thumb);
+    }
+
+    let ext = p.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+    if let Some(cover) = embedded_cover(p, &ext).and_then(|bytes| image::load_from_memory(&bytes).ok()) {
+        let bytes = encode_jpeg(&cover.resize_to_fill(width, height, FilterType::Lanczos3))?;
+        return Ok(cache.put(&key, Thumb { bytes, mime: "image/jpeg" }));
+    }
+
+    let samples = decode_audio_samples(p).map_err(ThumbnailError::Unsupported)?;
+    let bytes = render_waveform(&samples, width, height).map_err(ThumbnailError::Decode)?;
+    Ok(cache.put(&key, Thumb { bytes, mime: "image/png" }))
+}
+
+/// Mono s16le samples at [WAVEFORM_SAMPLE_RATE], decoded by ffmpeg.
+fn decode_audio_samples(p: &Path) -> Result<Vec<u8>, String> {
+    let path = p.to_string_lossy();
+    let rate = WAVEFORM_SAMPLE_RATE.to_string();
+    let args = [
+        "-i",
+        path.as_ref(),
+        "-vn",
+        "-ac",
+        "1",
+        "-ar",
+        rate.as_str(),
+        "-f",
+        "s16le",
+        "pipe:1",
+    ];
+    let mut last_err = String::new();
+    for ffmpeg_bin in ffmpeg_paths_to_try() {
+        let result = std::process::Command::new(ffmpeg_bin)
+            .args(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .output();
+        match result {
+            Ok(output) if output.status.success() && !output.stdout.is_empty() => return Ok(output.stdout),
+            Ok(_) => continue,
+            Err(e) => last_err = e.to_string(),
+        }
+    }
+    Err(format!("ffmpeg not available: {}", last_err))
+}
+
+/// Symmetric bar waveform on a transparent background, encoded as PNG.
+fn render_waveform(samples: &[u8], width: u32, height: u32) -> Result<Vec<u8>, String> {
+    let (width, height) = (width.max(1), height.max(1));
+    let bar_step = WAVEFORM_BAR_WIDTH + WAVEFORM_BAR_GAP;
+    let bars = (width / bar_step).max(1);
+    let peaks = waveform_peaks(samples, bars as usize);
+
+    let mut img = image::RgbaImage::new(width, height);
+    let mid = height as f32 / 2.0;
+    for (i, peak) in peaks.iter().enumerate() {
+        // Keep silent stretches visible as a thin line.
+        let half = (peak * mid).max(0.5);
+        let top = (mid - half).floor().max(0.0) as u32;
+        let bottom = ((mid + half).ceil() as u32).min(height);
+        let x0 = i as u32 * bar_step;
+        for x in x0..(x0 + WAVEFORM_BAR_WIDTH).min(width) {
+            for y in top..bottom {
+                img.put_pixel(x, y, image::Rgba(WAVEFORM_COLOR));
+            }
+        }
+    }
+
+    let mut buffer = std::io::Cursor::new(Vec::new());
+    DynamicImage::ImageRgba8(img)
+        .write_to(&mut buffer, image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode waveform: {}", e))?;
+    Ok(buffer.into_inner())
+}
+
+#[tauri::command]
+pub async fn get_thumbnail(
+    app: tauri::AppHandle,
+    path: String,
+    width: u32,
+    height: u32,
+) -> Result<String, ThumbnailError> {
+    let cache = thumbnail_cache(&app);
+    tokio::task::spawn_blocking(move || file_thumbnail(&cache, Path::new(&path), width, height))
+        .await
+        .map_err(|e| ThumbnailError::Io(e.to_string()))?
+        .map(|thumb| thumb.to_data_url())
+}
+
+/// Cached image thumbnail: served from memory/disk when the source is unchanged, otherwise
+/// decoded (EXIF orientation applied), resized and stored.
+pub(crate) fn image_thumbnail(
+    cache: &ThumbnailCache,
+    p: &Path,
+    width: u32,
+    height: u32,
+) -> Result<Arc<Thumb>, ThumbnailError> {
+    if !p.is_file() {
+        return Err(ThumbnailError::NotFound("File not found".to_string()));
+    }
+    let key = ThumbKey::for_file(p, width, height, "image").map_err(ThumbnailError::Io)?;
+    if let Some(thumb) = cache.get(&key) {
+        return Ok(thumb);
+    }
+
+    let exif = read_exif(p);
+    let mut img = decode_for_thumbnail(p, exif.as_ref(), width, height)?;
+    // Embedded previews share the main image's unrotated pixel layout.
+    if let Some(exif) = &exif {
+        img.apply_orientation(exif_orientation(exif));
+    }
+    let bytes = encode_jpeg(&img.resize_to_fill(width, height, FilterType::Lanczos3))?;
+    Ok(cache.put(&key, Thumb { bytes, mime: "image/jpeg" }))
+}
+
+/// Thumbnail for an image inside a zip/tar, decoded from memory without extracting.
+#[tauri::command]
+pub async fn get_archive_entry_thumbnail(
+    app: tauri::AppHandle,
+    path: String,
+    inner_path: String,
+    width: u32,
+    height: u32,
+) -> Result<String, ThumbnailError> {
+    let cache = thumbnail_cache(&app);
+    tokio::task::spawn_blocking(move || archive_entry_thumbnail(&cache, Path::new(&path), &inner_path, width, height))
+        .await
+        .map_err(|e| ThumbnailError::Io(e.to_string()))?
+        .map(|thumb| thumb.to_data_url())
+}
+
+pub(crate) fn archive_entry_thumbnail(
+    cache: &ThumbnailCache,
+    archive: &Path,
+    inner_path: &str,
+    width: u32,
+    height: u32,
+) -> Result<Arc<Thumb>, ThumbnailError> {
+    if !archive.is_file() {
+        return Err(ThumbnailError::NotFound("File not found".to_string()));
+    }
+    // Keyed on the archive's size/mtime: rewriting the archive invalidates its entries.
+    let variant = format!("archive-entry\0{}", inner_path);
+    let key = ThumbKey::for_file(archive, width, height, &variant).map_err(ThumbnailError::Io)?;
+    if let Some(thumb) = cache.get(&key) {
+        return Ok(thumb);
+    }
+
+    let (data, _, truncated) =
+        read_entry_bytes(archive, inner_path, MAX_ARCHIVE_ENTRY_DECODE_BYTES as usize, &mut None, &mut no_password)
+            .map_err(|e| match e {
+                ArchiveError::PasswordRequired(m) | ArchiveError::WrongPassword(m) => ThumbnailError::Unsupported(m),
+                e => ThumbnailError::NotFound(e.to_string()),
+            })?;
+    if truncated {
+        return Err(ThumbnailError::Unsupported("Archive entry too large for a thumbnail".to_string()));
+    }
+    let mut reader = image::ImageReader::new(std::io::Cursor::new(&data))
+        .with_guessed_format()
+        .map_err(|e| ThumbnailError::Io(e.to_string()))?;
+    let mut limits = image::Limits::default();
+    limits.max_alloc = Some(MAX_DECODE_ALLOC_BYTES);
+    reader.limits(limits);
+    let mut img = reader.decode().map_err(|e| match e {
+        image::ImageError::Unsupported(u) => ThumbnailError::Unsupported(u.to_string()),
+        other => ThumbnailError::Decode(format!("Failed to open image: {}", other)),
+    })?;
+    if let Ok(exif) = exif::Reader::new().read_from_container(&mut std::io::Cursor::new(&data)) {
+        img.apply_orientation(exif_orientation(&exif));
+    }
+    let bytes = encode_jpeg(&img.resize_to_fill(width, height, FilterType::Lanczos3))?;
+    Ok(cache.put(&key, Thumb { bytes, mime: "image/jpeg" }))
+}
+
+fn decode_for_thumbnail(
+    p: &Path,
+    exif: Option<&exif::Exif>,
+    width: u32,
+    height: u32,
+) -> Result<DynamicImage, ThumbnailError> {
+    let ext = p.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+    let size = std::fs::metadata(p).map(|m| m.len()).unwrap_or(0);
+    let preview = exif.and_then(embedded_preview);
+
+    if is_raw_extension(&ext) {
+        return preview.ok_or_else(|| {
+            ThumbnailError::Unsupported("RAW file has no embedded preview".to_string())
+        });
+    }
+    // An embedded preview that already covers the requested size saves a full decode.
+    if let Some(preview) = &preview {
+        if preview.width() >= width && preview.height() >= height {
+            return Ok(preview.clone());
+        }
     }
-    let meta = std::fs::metadata(p).map_err(|e| format!("Failed to read file: {}", e))?;
-    if meta.len() > MAX_THUMBNAIL_DECODE_BYTES {
-        return Err(format!(
-            "Image too large for thumbnail (max {} MB)",
-            MAX_THUMBNAIL_DECODE_BYTES / (1024 * 1024)
-        ));
+
+    match ext.as_str() {
+        "jpg" | "jpeg" if size > MAX_THUMBNAIL_DECODE_BYTES => {
+            if let Some(img) = decode_jpeg_scaled(p, width, height) {
+                return Ok(img);
+            }
+        }
+        "svg" | "svgz" | "heic" | "heif" | "avif" => {
+            return external_decode(p, &ext, width.max(height)).ok_or_else(|| {
+                ThumbnailError::Unsupported(format!("No renderer available for .{} files", ext))
+            });
+        }
+        _ => {}
+    }
+
+    let decoded = image::ImageReader::open(p)
+        .and_then(|r| r.with_guessed_format())
+        .map_err(|e| ThumbnailError::Io(format!("Failed to open image: {}", e)))
+        .and_then(|mut reader| {
+            let mut limits = image::Limits::default();
+            limits.max_alloc = Some(MAX_DECODE_ALLOC_BYTES);
+            reader.limits(limits);
+            reader.decode().map_err(|e| match e {
+                image::ImageError::Unsupported(u) => ThumbnailError::Unsupported(u.to_string()),
+                other => ThumbnailError::Decode(format!("Failed to open image: {}", other)),
+            })
+        });
+
+    // Fall back to a small embedded preview rather than nothing.
+    match (decoded, preview) {
+        (Ok(img), _) => Ok(img),
+        (Err(_), Some(preview)) => Ok(preview),
+        (Err(e), None) => Err(e),
     }
+}
+
+/// Renders formats the image crate can't decode (SVG, HEIC, AVIF, PDF) with an installed tool,
+/// probed like [ffmpeg_paths_to_try]. Returns the first successful rendering.
+fn external_decode(p: &Path, ext: &str, edge: u32) -> Option<DynamicImage> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let out_dir = std::env::temp_dir().join(format!(
+        "sdm-render-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::create_dir_all(&out_dir).ok()?;
+    let out_file = out_dir.join("out.png");
+    let input = p.to_string_lossy().to_string();
+    let edge = edge.to_string();
+    let out = out_file.to_string_lossy().to_string();
 
-    let img = image::open(p).map_err(|e| format!("Failed to open image: {}", e))?;
-    let thumbnail = img.resize_to_fill(width, height, FilterType::Lanczos3);
+    let mut attempts: Vec<(String, Vec<String>)> = Vec::new();
+    match ext {
+        "svg" | "svgz" => {
+            for bin in renderer_paths_to_try("rsvg-convert") {
+                attempts.push((bin, vec!["-w".into(), edge.clone(), "-a".into(), "-o".into(), out.clone(), input.clone()]));
+            }
+        }
+        "pdf" => {
+            // pdftoppm appends ".png" to the output root when -singlefile is set.
+            let root = out_dir.join("out").to_string_lossy().to_string();
+            for bin in renderer_paths_to_try("pdftoppm") {
+                attempts.push((bin, vec![
+                    "-png".into(), "-f".into(), "1".into(), "-l".into(), "1".into(), "-singlefile".into(),
+                    "-scale-to".into(), edge.clone(), input.clone(), root.clone(),
+                ]));
+            }
+            #[cfg(target_os = "macos")]
+            attempts.push(("sips".to_string(), vec!["-s".into(), "format".into(), "png".into(), "-Z".into(), edge.clone(), input.clone(), "--out".into(), out.clone()]));
+        }
+        "heic" | "heif" | "avif" => {
+            #[cfg(target_os = "macos")]
+            attempts.push(("sips".to_string(), vec!["-s".into(), "format".into(), "png".into(), "-Z".into(), edge.clone(), input.clone(), "--out".into(), out.clone()]));
+            for bin in ffmpeg_paths_to_try() {
+                attempts.push((bin.to_string(), vec!["-y".into(), "-i".into(), input.clone(), "-frames:v".into(), "1".into(), out.clone()]));
+            }
+        }
+        _ => {}
+    }
+    // Quick Look renders nearly everything on macOS; writes <out_dir>/<file name>.png.
+    #[cfg(target_os = "macos")]
+    attempts.push(("qlmanage".to_string(), vec!["-t".into(), "-s".into(), edge.clone(), "-o".into(), out_dir.to_string_lossy().to_string(), input.clone()]));
 
+    let mut result = None;
+    for (bin, args) in attempts {
+        let ok = std::process::Command::new(bin)
+            .args(&args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map(|s| s.success())
+            .unwrap_or(false);
+        if !ok {
+            continue;
+        }
+        let produced = if out_file.exists() {
+            Some(out_file.clone())
+        } else {
+            p.file_name().map(|n| out_dir.join(format!("{}.png", n.to_string_lossy())))
+        };
+        if let Some(img) = produced.and_then(|f| image::open(f).ok()) {
+            result = Some(img);
+            break;
+        }
+    }
+    let _ = std::fs::remove_dir_all(&out_dir);
+    result
+}
+
+/// Preview images that office suites embed inside the document's zip container.
+const EMBEDDED_DOCUMENT_THUMBNAILS: &[&str] = &[
+    "docProps/thumbnail.jpeg",
+    "docProps/thumbnail.jpg",
+    "docProps/thumbnail.png",
+    "Thumbnails/thumbnail.png",
+    "QuickLook/Thumbnail.jpg",
+    "preview.jpg",
+];
+
+fn embedded_document_thumbnail(p: &Path) -> Option<DynamicImage> {
+    let file = std::fs::File::open(p).ok()?;
+    let mut archive = zip::ZipArchive::new(std::io::BufReader::new(file)).ok()?;
+    EMBEDDED_DOCUMENT_THUMBNAILS.iter().find_map(|name| {
+        let entry = archive.by_name(name).ok()?;
+        // The declared size comes from the file and can lie, so read through a hard cap too.
+        if entry.size() > MAX_EMBEDDED_PREVIEW_BYTES {
+            return None;
+        }
+        let mut bytes = Vec::new();
+        std::io::Read::read_to_end(&mut std::io::Read::take(entry, MAX_EMBEDDED_PREVIEW_BYTES + 1), &mut bytes).ok()?;
+        if bytes.len() as u64 > MAX_EMBEDDED_PREVIEW_BYTES {
+            return None;
+        }
+        image::load_from_memory(&bytes).ok()
+    })
+}
+
+/// First-page thumbnail for PDFs (via pdftoppm / Quick Look) and office documents that embed
+/// a preview (OOXML `docProps/thumbnail.*`, ODF `Thumbnails/thumbnail.png`, iWork previews).
+/// Pages keep their aspect ratio instead of being cropped like photos.
+pub(crate) fn document_thumbnail(
+    cache: &ThumbnailCache,
+    p: &Path,
+    width: u32,
+    height: u32,
+) -> Result<Arc<Thumb>, ThumbnailError> {
+    if !p.is_file() {
+        return Err(ThumbnailError::NotFound("File not found".to_string()));
+    }
+    let key = ThumbKey::for_file(p, width, height, "document").map_err(ThumbnailError::Io)?;
+    if let Some(thumb) = cache.get(&key) {
+        return Ok(thumb);
+    }
+
+    let ext = p.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+    let edge = width.max(height);
+    let img = match ext.as_str() {
+        "pdf" => external_decode(p, &ext, edge),
+        _ => embedded_document_thumbnail(p).or_else(|| external_decode(p, &ext, edge)),
+    }
+    .ok_or_else(|| ThumbnailError::Unsupported(format!("No page preview available for .{} files", ext)))?;
+
+    let bytes = encode_jpeg(&img.resize(width, height, FilterType::Lanczos3))?;
+    Ok(cache.put(&key, Thumb { bytes, mime: "image/jpeg" }))
+}
+
+/// Thumbnail for whatever `get_thumbnail` supports: images and documents.
+pub(crate) fn file_thumbnail(
+    cache: &ThumbnailCache,
+    p: &Path,
+    width: u32,
+    height: u32,
+) -> Result<Arc<Thumb>, ThumbnailError> {
+    let ext = p.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_default();
+    match get_file_category(&ext) {
+        FileCategory::Document => document_thumbnail(cache, p, width, height),
+        _ => image_thumbnail(cache, p, width, height),
+    }
+}
+
+fn encode_jpeg(img: &DynamicImage) -> Result<Vec<u8>, ThumbnailError> {
     let mut buffer = std::io::Cursor::new(Vec::new());
-    thumbnail
+    img.to_rgb8()
         .write_to(&mut buffer, image::ImageFormat::Jpeg)
-        .map_err(|e| format!("Failed to encode image: {}", e))?;
+        .map_err(|e| ThumbnailError::Decode(format!("Failed to encode image: {}", e)))?;
+    Ok(buffer.into_inner())
+}
+
+/// Emitted on "thumbnail_ready" for each thumbnail produced by `prefetch_thumbnails`. The
+/// thumbnail itself is now in the cache; the frontend loads it over `sdm://thumb/`.
+#[derive(Serialize, Clone)]
+pub struct ThumbnailReady {
+    pub operation_id: String,
+    pub path: String,
+}
+
+/// Emitted on "thumbnail_prefetch_finished" when a prefetch batch ends or is cancelled.
+#[derive(Serialize, Clone)]
+pub struct ThumbnailPrefetchFinished {
+    pub operation_id: String,
+    pub generated: usize,
+    pub cancelled: bool,
+}
+
+/// Generate thumbnails for a page of directory entries in parallel. Paths other than images
+/// and documents are skipped. The frontend cancels with `cancel_operation(operation_id)` when the page scrolls away.
+#[tauri::command]
+pub async fn prefetch_thumbnails(
+    app: tauri::AppHandle,
+    operation_id: String,
+    paths: Vec<String>,
+    width: u32,
+    height: u32,
+) -> Result<(), String> {
+    let cancel_flag = register_operation_with_paths(operation_id.clone(), &paths);
+    let cache = thumbnail_cache(&app);
+    let generated = AtomicUsize::new(0);
+
+    let op_id = operation_id.clone();
+    let app_clone = app.clone();
+    let generated = tokio::task::spawn_blocking(move || {
+        paths.par_iter().for_each(|path| {
+            if cancel_flag.load(Ordering::Relaxed) {
+                return;
+            }
+            let p = Path::new(path);
+            let ext = p.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_default();
+            if !matches!(get_file_category(&ext), FileCategory::Image | FileCategory::Document) {
+                return;
+            }
+            if file_thumbnail(&cache, p, width, height).is_ok() {
+                generated.fetch_add(1, Ordering::Relaxed);
+                let _ = app_clone.emit("thumbnail_ready", ThumbnailReady {
+                    operation_id: op_id.clone(),
+                    path: path.clone(),
+                });
+            }
+        });
+        (generated.into_inner(), cancel_flag.load(Ordering::Relaxed))
+    })
+    .await
+    .map_err(|e| e.to_string());
 
-    let base64_str = general_purpose::STANDARD.encode(buffer.into_inner());
-    Ok(format!("data:image/jpeg;base64,{}", base64_str))
+    unregister_operation(&operation_id);
+    let (generated, cancelled) = generated?;
+    let _ = app.emit("thumbnail_prefetch_finished", ThumbnailPrefetchFinished {
+        operation_id,
+        generated,
+        cancelled,
+    });
+    Ok(())
 }
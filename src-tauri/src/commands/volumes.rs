@@ -1,7 +1,13 @@
 use serde::{Serialize, Deserialize};
+use std::path::Path;
 use sysinfo::Disks;
+use tauri::AppHandle;
 
-#[derive(Debug, Serialize, Deserialize)]
+use crate::commands::operation::operations_under;
+use crate::utils::exclusion::exclusions;
+use crate::utils::mountinfo::{classify, classify_fs, device_ids, inode_usage, read_mounts, MountKind};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Volume {
     pub name: String,
     pub mount_point: String,
@@ -9,11 +15,30 @@ pub struct Volume {
     pub available_space: u64,
     pub is_removable: bool,
     pub is_system: bool,
+    pub kind: MountKind,
+    /// e.g. "ext4", "apfs", "ntfs".
+    pub fs_type: String,
+    /// Backing device or share, e.g. `/dev/sdb1` or `server:/export`.
+    pub device: Option<String>,
+    pub read_only: bool,
+    pub uuid: Option<String>,
+    pub label: Option<String>,
+    pub inodes_total: Option<u64>,
+    pub inodes_free: Option<u64>,
+}
+
+/// Package images (snaps and the like) are read-only squashfs loops nobody browses.
+fn is_hidden_by_default(volume: &Volume) -> bool {
+    volume.kind == MountKind::Virtual || (volume.kind == MountKind::Loop && volume.fs_type == "squashfs")
 }
 
+/// Mounted volumes; virtual filesystems and package images are left out unless `include_virtual`.
 #[tauri::command]
-pub fn list_volumes() -> Vec<Volume> {
+pub fn list_volumes(include_virtual: Option<bool>) -> Vec<Volume> {
     let disks = Disks::new_with_refreshed_list();
+    let mounts = read_mounts();
+    let include_virtual = include_virtual.unwrap_or(false);
+    let rules = exclusions();
     disks.into_iter()
         .map(|d| {
             let name = if d.name().is_empty() {
@@ -21,6 +46,22 @@ pub fn list_volumes() -> Vec<Volume> {
             } else {
                 d.name().to_string_lossy().to_string()
             };
+            // The last entry wins when several filesystems are stacked on one mount point
+            let mount = mounts.iter().rev().find(|m| m.mount_point == d.mount_point());
+            let device = mount.map(|m| m.source.clone()).filter(|s| s.starts_with('/') || s.contains(':'));
+            let (uuid, label) = device.as_deref().map(device_ids).unwrap_or_default();
+            let inodes = inode_usage(d.mount_point());
+            let fs_type = mount
+                .map(|m| m.fs_type.clone())
+                .unwrap_or_else(|| d.file_system().to_string_lossy().to_string());
+            let kind = match mount {
+                Some(m) => classify(m),
+                None => classify_fs(&fs_type).unwrap_or(if d.is_removable() {
+                    MountKind::Removable
+                } else {
+                    MountKind::Local
+                }),
+            };
 
             Volume {
                 name,
@@ -28,24 +69,127 @@ pub fn list_volumes() -> Vec<Volume> {
                 total_space: d.total_space(),
                 available_space: d.available_space(),
                 is_removable: d.is_removable(),
-                // Simplistic check for system disk, can be refined per OS
-                is_system: is_system_path(d.mount_point().to_str().unwrap_or("")),
+                is_system: rules.is_system_volume(d.mount_point()),
+                kind,
+                fs_type,
+                device,
+                read_only: mount.map_or(d.is_read_only(), |m| m.read_only()),
+                uuid,
+                label,
+                inodes_total: inodes.map(|(total, _)| total),
+                inodes_free: inodes.map(|(_, free)| free),
             }
         })
+        .filter(|volume| include_virtual || !is_hidden_by_default(volume))
         .collect()
 }
 
-fn is_system_path(path: &str) -> bool {
-    #[cfg(target_os = "macos")]
-    {
-        path == "/" || path.contains("/System") || path.contains("/Library")
+// ── Mount events ────────────────────────────────────────────────────────────
+
+/// Emits "volume-mounted" and "volume-unmounted" with the [Volume] whenever the mount table
+/// changes. Linux only: the kernel flags `/proc/self/mountinfo` for `poll` on every change.
+#[cfg(target_os = "linux")]
+pub fn watch_mounts(app: AppHandle) -> Result<(), String> {
+    use std::fs::File;
+    use std::os::fd::AsRawFd;
+    use tauri::Emitter;
+
+    let file = File::open(crate::utils::mountinfo::MOUNTINFO_PATH).map_err(|e| e.to_string())?;
+    std::thread::spawn(move || {
+        let mut known = list_volumes(None);
+        loop {
+            let mut fds = libc::pollfd {
+                fd: file.as_raw_fd(),
+                events: libc::POLLPRI | libc::POLLERR,
+                revents: 0,
+            };
+            if unsafe { libc::poll(&mut fds, 1, -1) } < 0 {
+                if std::io::Error::last_os_error().kind() == std::io::ErrorKind::Interrupted {
+                    continue;
+                }
+                break;
+            }
+
+            let current = list_volumes(None);
+            for volume in current.iter().filter(|v| !known.iter().any(|k| k.mount_point == v.mount_point)) {
+                let _ = app.emit("volume-mounted", volume);
+            }
+            for volume in known.iter().filter(|k| !current.iter().any(|v| v.mount_point == k.mount_point)) {
+                let _ = app.emit("volume-unmounted", volume);
+            }
+            known = current;
+        }
+    });
+    Ok(())
+}
+
+/// Mount events are Linux-only; elsewhere the sidebar keeps refreshing on demand.
+#[cfg(not(target_os = "linux"))]
+pub fn watch_mounts(_app: AppHandle) -> Result<(), String> {
+    Ok(())
+}
+
+// ── Eject ───────────────────────────────────────────────────────────────────
+
+fn run(program: &str, args: &[&str]) -> std::io::Result<Result<(), String>> {
+    let output = std::process::Command::new(program).args(args).output()?;
+    Ok(if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    })
+}
+
+/// Unmounts through udisks so no root is needed, then powers the drive off if it is
+/// removable; falls back to `umount` where udisks isn't installed.
+#[cfg(target_os = "linux")]
+fn unmount(volume: &Volume) -> Result<(), String> {
+    if let Some(device) = volume.device.as_deref().filter(|d| d.starts_with("/dev/")) {
+        match run("udisksctl", &["unmount", "--no-user-interaction", "-b", device]) {
+            Ok(result) => {
+                result?;
+                if volume.is_removable {
+                    // Fails harmlessly when other partitions of the drive are still mounted
+                    let _ = run("udisksctl", &["power-off", "--no-user-interaction", "-b", device]);
+                }
+                return Ok(());
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.to_string()),
+        }
     }
-    #[cfg(target_os = "windows")]
-    {
-        path.to_uppercase().starts_with("C:")
+    run("umount", &[&volume.mount_point]).map_err(|e| e.to_string())?
+}
+
+#[cfg(target_os = "macos")]
+fn unmount(volume: &Volume) -> Result<(), String> {
+    run("diskutil", &["eject", &volume.mount_point]).map_err(|e| e.to_string())?
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn unmount(_volume: &Volume) -> Result<(), String> {
+    Err("Ejecting volumes isn't supported on this platform".to_string())
+}
+
+/// Unmounts the volume at `mount_point`, refusing while running jobs still use it.
+#[tauri::command]
+pub async fn eject_volume(mount_point: String) -> Result<(), String> {
+    let volume = list_volumes(Some(true))
+        .into_iter()
+        .find(|v| v.mount_point == mount_point)
+        .ok_or_else(|| format!("{mount_point} is not mounted"))?;
+    if volume.is_system {
+        return Err(format!("{} is a system volume", volume.name));
     }
-    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
-    {
-        path == "/" || path.starts_with("/boot")
+    let busy = operations_under(Path::new(&volume.mount_point));
+    if !busy.is_empty() {
+        return Err(format!(
+            "{} is in use by {} running operation(s); wait for them to finish or cancel them",
+            volume.name,
+            busy.len()
+        ));
     }
+    tokio::task::spawn_blocking(move || unmount(&volume))
+        .await
+        .map_err(|e| e.to_string())?
 }
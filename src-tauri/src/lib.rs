@@ -11,6 +11,23 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
+        .setup(|app| {
+            // Loading once up front builds the file category registry before the first listing.
+            let _ = crate::commands::settings::load_settings(app.handle().clone());
+            if let Err(e) = crate::commands::settings::watch_settings(app.handle().clone()) {
+                eprintln!("Settings file watcher unavailable: {e}");
+            }
+            if let Err(e) = crate::commands::volumes::watch_mounts(app.handle().clone()) {
+                eprintln!("Mount watcher unavailable: {e}");
+            }
+            Ok(())
+        })
+        .register_asynchronous_uri_scheme_protocol(crate::commands::protocol::SCHEME, |ctx, request, responder| {
+            let app = ctx.app_handle().clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                responder.respond(crate::commands::protocol::handle_request(&app, request));
+            });
+        })
         .invoke_handler(tauri::generate_handler![
             greet,
             crate::commands::dir::read_dir_chunked,
@@ -26,7 +43,15 @@ pub fn run() {
             crate::commands::batch::fast_copy,
             crate::commands::thumbnails::get_thumbnail,
             crate::commands::thumbnails::get_video_thumbnail,
+            crate::commands::thumbnails::get_audio_thumbnail,
+            crate::commands::thumbnails::get_archive_entry_thumbnail,
+            crate::commands::thumbnails::prefetch_thumbnails,
+            crate::commands::metadata::get_file_metadata,
             crate::commands::preview_op::get_file_text_content,
+            crate::commands::text_preview::read_text_range,
+            crate::commands::text_preview::read_lines,
+            crate::commands::hex_view::read_hex_range,
+            crate::commands::hex_view::search_hex,
             crate::commands::preview_op::get_file_base64_content,
             crate::commands::preview_op::get_file_blob,
             crate::commands::preview_op::show_in_finder,
@@ -35,19 +60,37 @@ pub fn run() {
             crate::commands::search::start_file_search,
             crate::commands::search::start_content_search,
             crate::commands::volumes::list_volumes,
+            crate::commands::volumes::eject_volume,
             crate::commands::settings::load_settings,
             crate::commands::settings::save_settings,
+            crate::commands::settings::get_settings_report,
+            crate::commands::profiles::list_profiles,
+            crate::commands::profiles::switch_profile,
+            crate::commands::profiles::delete_profile,
+            crate::commands::profiles::export_settings,
+            crate::commands::profiles::import_settings,
+            crate::commands::folder_overrides::get_folder_override,
+            crate::commands::folder_overrides::set_folder_override,
+            crate::commands::folder_overrides::list_folder_overrides,
+            crate::commands::exclusion::explain_exclusion,
             crate::commands::dedupe::find_duplicates,
             crate::commands::content_search::find_content_by_category,
+            crate::commands::content_search::verify_extensions,
             crate::commands::tree::get_tree_nodes,
             crate::commands::cleaner::find_empty_folders,
             crate::commands::cleaner::delete_empty_folders,
+            crate::commands::dev_cleaner::find_build_artifacts,
             crate::commands::setup::check_system_requirements,
             crate::commands::setup::check_ollama_status,
             crate::commands::setup::pull_model,
             crate::commands::setup::is_setup_complete,
             crate::commands::archive::extract_archive,
-            crate::commands::archive::compress_to_zip
+            crate::commands::archive::compress_to_zip,
+            crate::commands::archive::list_archive,
+            crate::commands::archive::read_archive_entry,
+            crate::commands::extract::start_extract,
+            crate::commands::compress::create_archive,
+            crate::commands::archive_password::provide_archive_password
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
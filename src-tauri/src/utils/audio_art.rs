@@ -0,0 +1,151 @@
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Embedded covers larger than this are ignored rather than read into memory.
+const MAX_COVER_BYTES: u64 = 32 * 1024 * 1024; // 32 MB
+
+/// ID3v2 / FLAC picture type for the front cover.
+const FRONT_COVER: u32 = 3;
+
+/// Encoded cover art embedded in an audio file: ID3 `APIC` (MP3, WAV, AIFF), FLAC `PICTURE`
+/// blocks, or the MP4 `covr` atom (M4A/AAC/ALAC). Prefers the front cover when several exist.
+pub fn embedded_cover(path: &Path, ext: &str) -> Option<Vec<u8>> {
+    match ext {
+        "flac" => flac_picture(path),
+        "m4a" | "m4b" | "mp4" | "alac" => mp4_cover(path),
+        _ => id3_picture(path),
+    }
+}
+
+/// `read_from_path` also finds ID3 chunks inside WAV and AIFF files.
+fn id3_picture(path: &Path) -> Option<Vec<u8>> {
+    let tag = id3::Tag::read_from_path(path).ok()?;
+    let pictures: Vec<&id3::frame::Picture> = tag.pictures().collect();
+    pictures
+        .iter()
+        .find(|p| p.picture_type == id3::frame::PictureType::CoverFront)
+        .or_else(|| pictures.first())
+        .map(|p| p.data.clone())
+}
+
+fn read_u32_be(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+/// Walks the `fLaC` metadata blocks and decodes `PICTURE` (type 6) blocks.
+fn flac_picture(path: &Path) -> Option<Vec<u8>> {
+    let mut file = BufReader::new(File::open(path).ok()?);
+    let mut magic = [0u8; 4];
+    file.read_exact(&mut magic).ok()?;
+    if &magic != b"fLaC" {
+        return None;
+    }
+
+    let mut fallback = None;
+    loop {
+        let mut header = [0u8; 4];
+        file.read_exact(&mut header).ok()?;
+        let last = header[0] & 0x80 != 0;
+        let block_type = header[0] & 0x7f;
+        let len = u32::from_be_bytes([0, header[1], header[2], header[3]]) as u64;
+
+        if block_type == 6 && len <= MAX_COVER_BYTES {
+            let mut block = vec![0u8; len as usize];
+            file.read_exact(&mut block).ok()?;
+            if let Some((picture_type, data)) = parse_flac_picture(&block) {
+                if picture_type == FRONT_COVER {
+                    return Some(data);
+                }
+                fallback.get_or_insert(data);
+            }
+        } else {
+            file.seek(SeekFrom::Current(len as i64)).ok()?;
+        }
+        if last {
+            return fallback;
+        }
+    }
+}
+
+/// PICTURE layout: type, mime (len-prefixed), description (len-prefixed), 4 dimension fields,
+/// then the len-prefixed image data. All integers are big-endian u32.
+fn parse_flac_picture(block: &[u8]) -> Option<(u32, Vec<u8>)> {
+    let picture_type = read_u32_be(block, 0)?;
+    let mime_len = read_u32_be(block, 4)? as usize;
+    let desc_at = 8 + mime_len;
+    let desc_len = read_u32_be(block, desc_at)? as usize;
+    let data_len_at = desc_at + 4 + desc_len + 16;
+    let data_len = read_u32_be(block, data_len_at)? as usize;
+    let data_at = data_len_at + 4;
+    Some((picture_type, block.get(data_at..data_at + data_len)?.to_vec()))
+}
+
+/// Finds `moov/udta/meta/ilst/covr/data` and returns the first image payload.
+fn mp4_cover(path: &Path) -> Option<Vec<u8>> {
+    let mut file = BufReader::new(File::open(path).ok()?);
+    let end = file.seek(SeekFrom::End(0)).ok()?;
+    let mut range = (0u64, end);
+    // `meta` is a full box: 4 bytes of version/flags precede its children.
+    for (name, skip) in [(b"moov", 0), (b"udta", 0), (b"meta", 4), (b"ilst", 0), (b"covr", 0), (b"data", 0)] {
+        let (start, len) = find_atom(&mut file, range, name)?;
+        range = (start + skip, start + len);
+    }
+    // `data` payload: 4 bytes type indicator + 4 bytes locale, then the image.
+    let (start, stop) = (range.0 + 8, range.1);
+    if stop <= start || stop - start > MAX_COVER_BYTES {
+        return None;
+    }
+    file.seek(SeekFrom::Start(start)).ok()?;
+    let mut data = vec![0u8; (stop - start) as usize];
+    file.read_exact(&mut data).ok()?;
+    Some(data)
+}
+
+/// Scans sibling atoms in `[start, end)` for `name`; returns (payload start, payload length).
+fn find_atom<R: Read + Seek>(file: &mut R, (mut pos, end): (u64, u64), name: &[u8; 4]) -> Option<(u64, u64)> {
+    while pos.checked_add(8).is_some_and(|header_end| header_end <= end) {
+        file.seek(SeekFrom::Start(pos)).ok()?;
+        let mut header = [0u8; 8];
+        file.read_exact(&mut header).ok()?;
+        let mut size = read_u32_be(&header, 0)? as u64;
+        let mut header_len = 8;
+        if size == 1 {
+            let mut large = [0u8; 8];
+            file.read_exact(&mut large).ok()?;
+            size = u64::from_be_bytes(large);
+            header_len = 16;
+        } else if size == 0 {
+            size = end - pos;
+        }
+        if size < header_len {
+            return None;
+        }
+        // Sizes come from the file: stop on overflow or an atom running past its parent.
+        let next = pos.checked_add(size).filter(|&next| next <= end)?;
+        if &header[4..8] == name {
+            return Some((pos + header_len, size - header_len));
+        }
+        pos = next;
+    }
+    None
+}
+
+/// Peak amplitude (0.0..=1.0) per column from mono signed 16-bit little-endian samples.
+pub fn waveform_peaks(samples: &[u8], columns: usize) -> Vec<f32> {
+    let count = samples.len() / 2;
+    if count == 0 || columns == 0 {
+        return vec![0.0; columns];
+    }
+    (0..columns)
+        .map(|c| {
+            let from = c * count / columns;
+            let to = ((c + 1) * count / columns).max(from + 1).min(count);
+            (from..to)
+                .map(|i| i16::from_le_bytes([samples[2 * i], samples[2 * i + 1]]).unsigned_abs())
+                .max()
+                .unwrap_or(0) as f32
+                / i16::MAX as f32
+        })
+        .collect()
+}
@@ -0,0 +1,286 @@
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+use crate::commands::settings::{CategoryDefinition, ConfigSection};
+use crate::utils::file_types::FileCategory;
+use crate::utils::magic::{detect_bytes, MAGIC_SNIFF_BYTES};
+
+/// Signatures must end within this many bytes of the start of a file.
+pub const MAX_SIGNATURE_END: usize = 64 * 1024;
+
+/// A category that ships with the app. Its id doubles as the `PreviewSettings` field, and
+/// its kind picks the thumbnail and metadata decoders no matter how the user edits the list.
+pub struct BuiltinCategory {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub kind: FileCategory,
+    pub extensions: &'static [&'static str],
+}
+
+pub const BUILTIN_CATEGORIES: &[BuiltinCategory] = &[
+    BuiltinCategory {
+        id: "image",
+        name: "Images",
+        kind: FileCategory::Image,
+        extensions: &["jpg", "jpeg", "png", "gif", "bmp", "webp", "svg", "tiff", "ico", "heic", "heif", "avif", "cr2", "cr3", "nef", "nrw", "arw", "srf", "sr2", "dng", "orf", "rw2", "raf", "pef", "srw", "x3f"],
+    },
+    BuiltinCategory {
+        id: "video",
+        name: "Videos",
+        kind: FileCategory::Video,
+        extensions: &["mp4", "m4v", "webm", "mkv", "mov", "avi", "wmv", "asf", "ts", "mts", "m2ts", "m3u8", "mpeg", "mpg", "mp2", "mpe", "mpv", "flv", "f4v", "3gp", "3g2", "ogv", "vob", "rm", "rmvb", "divx", "mk3d", "qt", "hevc", "h265", "h264"],
+    },
+    BuiltinCategory {
+        id: "audio",
+        name: "Audio",
+        kind: FileCategory::Audio,
+        extensions: &["mp3", "wav", "ogg", "oga", "flac", "m4a", "aac", "wma", "aiff", "aif", "alac", "opus", "mid", "midi", "amr", "ape", "wv", "caf"],
+    },
+    BuiltinCategory {
+        id: "document",
+        name: "Documents",
+        kind: FileCategory::Document,
+        extensions: &["pdf", "doc", "docx", "xls", "xlsx", "ppt", "pptx", "odt", "ods", "odp", "pages", "numbers", "key", "rtf", "txt"],
+    },
+    BuiltinCategory {
+        id: "archive",
+        name: "Compressed",
+        kind: FileCategory::Archive,
+        extensions: &["zip", "tar", "gz", "tgz", "bz2", "tbz2", "xz", "txz", "zst", "tzst", "7z", "rar", "iso", "cab", "ar", "lz", "lzma", "z", "war", "ear"],
+    },
+];
+
+/// The built-in categories as settings entries; the default for `AppSettings::categories`.
+pub fn builtin_definitions() -> Vec<CategoryDefinition> {
+    BUILTIN_CATEGORIES
+        .iter()
+        .map(|builtin| CategoryDefinition {
+            id: builtin.id.to_string(),
+            name: builtin.name.to_string(),
+            extensions: builtin.extensions.iter().map(|ext| ext.to_string()).collect(),
+            mime_types: Vec::new(),
+            signatures: Vec::new(),
+        })
+        .collect()
+}
+
+pub fn is_builtin(id: &str) -> bool {
+    BUILTIN_CATEGORIES.iter().any(|builtin| builtin.id == id)
+}
+
+/// Decodes a signature such as `"25 50 44 46"`; whitespace is ignored.
+pub fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    let digits: Vec<u8> = hex.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+    let pairs = digits.chunks_exact(2);
+    if digits.is_empty() || !pairs.remainder().is_empty() {
+        return None;
+    }
+    pairs
+        .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok())
+        .collect()
+}
+
+lazy_static! {
+    static ref REGISTRY: RwLock<Arc<CategoryRegistry>> =
+        RwLock::new(Arc::new(CategoryRegistry::new(&builtin_definitions())));
+}
+
+/// The registry for the current settings; hold on to it for the length of a walk.
+pub fn registry() -> Arc<CategoryRegistry> {
+    REGISTRY.read().unwrap().clone()
+}
+
+/// Rebuilds the registry; called whenever settings are loaded or saved.
+pub fn set_categories(definitions: &[CategoryDefinition]) {
+    *REGISTRY.write().unwrap() = Arc::new(CategoryRegistry::new(definitions));
+}
+
+pub struct Category {
+    pub id: String,
+    pub name: String,
+    pub builtin: bool,
+    /// Decoder kind of a built-in category, used to file content-detected types.
+    kind: Option<FileCategory>,
+    mime_types: Vec<String>,
+    signatures: Vec<(usize, Vec<u8>)>,
+}
+
+impl Category {
+    fn matches_content(&self, bytes: &[u8], mime: Option<&str>) -> bool {
+        self.signatures
+            .iter()
+            .any(|(offset, signature)| bytes.get(*offset..offset + signature.len()) == Some(signature.as_slice()))
+            || mime.is_some_and(|mime| self.mime_types.iter().any(|pattern| mime_matches(pattern, mime)))
+    }
+}
+
+/// `pattern` is a full type (`application/epub+zip`) or a top-level wildcard (`image/*`).
+fn mime_matches(pattern: &str, mime: &str) -> bool {
+    match pattern.strip_suffix("/*") {
+        Some(top) => mime.split('/').next() == Some(top),
+        None => pattern == mime,
+    }
+}
+
+pub struct CategoryRegistry {
+    categories: Vec<Category>,
+    by_extension: HashMap<String, usize>,
+    /// Bytes to read for content rules; 0 when no category has any.
+    sniff_len: usize,
+}
+
+impl CategoryRegistry {
+    /// User categories may claim extensions from the built-ins; otherwise the first
+    /// category listing an extension keeps it.
+    pub fn new(definitions: &[CategoryDefinition]) -> Self {
+        let mut categories: Vec<Category> = Vec::new();
+        let mut by_extension: HashMap<String, usize> = HashMap::new();
+        let mut sniff_len = 0;
+
+        let (builtins, custom): (Vec<_>, Vec<_>) = definitions.iter().partition(|def| is_builtin(&def.id));
+        for def in builtins.into_iter().chain(custom) {
+            let index = categories.len();
+            let kind = BUILTIN_CATEGORIES.iter().find(|b| b.id == def.id).map(|b| b.kind);
+            let builtin = kind.is_some();
+            for ext in &def.extensions {
+                let claim = match by_extension.get(ext) {
+                    None => true,
+                    Some(&owner) => categories[owner].builtin && !builtin,
+                };
+                if claim {
+                    by_extension.insert(ext.clone(), index);
+                }
+            }
+
+            let signatures: Vec<(usize, Vec<u8>)> = def
+                .signatures
+                .iter()
+                .filter_map(|signature| Some((signature.offset as usize, decode_hex(&signature.hex)?)))
+                .filter(|(offset, bytes)| offset.saturating_add(bytes.len()) <= MAX_SIGNATURE_END)
+                .collect();
+            if !def.mime_types.is_empty() {
+                sniff_len = sniff_len.max(MAGIC_SNIFF_BYTES);
+            }
+            for (offset, bytes) in &signatures {
+                sniff_len = sniff_len.max(offset + bytes.len());
+            }
+
+            categories.push(Category {
+                id: def.id.clone(),
+                name: def.name.clone(),
+                builtin,
+                kind,
+                mime_types: def.mime_types.clone(),
+                signatures,
+            });
+        }
+        Self { categories, by_extension, sniff_len }
+    }
+
+    pub fn by_extension(&self, ext: &str) -> Option<&Category> {
+        let ext = ext.trim_start_matches('.').to_lowercase();
+        self.by_extension.get(&ext).map(|&index| &self.categories[index])
+    }
+
+    /// The category for a file: by extension, or for extensions no category claims, by the
+    /// MIME and signature rules. The file is only read in that second case.
+    pub fn categorize(&self, path: &Path, ext: &str) -> Option<&Category> {
+        if let Some(category) = self.by_extension(ext) {
+            return Some(category);
+        }
+        if self.sniff_len == 0 {
+            return None;
+        }
+        let mut bytes = Vec::with_capacity(self.sniff_len);
+        File::open(path).ok()?.take(self.sniff_len as u64).read_to_end(&mut bytes).ok()?;
+        let mime = detect_bytes(&bytes).map(|found| found.mime);
+        self.categories.iter().find(|category| category.matches_content(&bytes, mime))
+    }
+
+    /// Categorizes by extension and by content. The content decides when the extension is
+    /// missing, unclaimed or wrong for it; user content rules come before the built-in kinds.
+    pub fn classify(&self, path: &Path, ext: &str) -> (Option<&Category>, TypeDetection) {
+        let ext = ext.trim_start_matches('.').to_lowercase();
+        let by_extension = self.by_extension(&ext);
+
+        let sniff_len = MAGIC_SNIFF_BYTES.max(self.sniff_len);
+        let mut bytes = Vec::with_capacity(sniff_len);
+        if let Ok(file) = File::open(path) {
+            let _ = file.take(sniff_len as u64).read_to_end(&mut bytes);
+        }
+        let content_type = detect_bytes(&bytes);
+        let mime = content_type.map(|found| found.mime);
+        let by_content = self
+            .categories
+            .iter()
+            .find(|category| !category.builtin && category.matches_content(&bytes, mime))
+            .or_else(|| {
+                let kind = content_type?.category;
+                self.categories.iter().find(|category| category.kind == Some(kind))
+            });
+
+        let expected_extension = content_type
+            .filter(|found| !found.accepts_extension(&ext))
+            .and_then(|found| found.extensions.iter().find(|e| !e.is_empty()))
+            .map(|e| e.to_string());
+        let category = if by_extension.is_none() || expected_extension.is_some() {
+            by_content.or(by_extension)
+        } else {
+            by_extension
+        };
+
+        let detection = TypeDetection {
+            extension_category: by_extension.map(|c| c.id.clone()),
+            content_mime: mime.map(str::to_string),
+            content_name: content_type.map(|found| found.name.to_string()),
+            content_category: by_content.map(|c| c.id.clone()),
+            expected_extension,
+        };
+        (category, detection)
+    }
+}
+
+/// The category a file's extension implies next to the one its content does.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct TypeDetection {
+    pub extension_category: Option<String>,
+    /// `None` when no signature matched, which includes plain text.
+    pub content_mime: Option<String>,
+    /// e.g. "PNG image".
+    pub content_name: Option<String>,
+    pub content_category: Option<String>,
+    /// Canonical extension for the content; only set when the file's extension doesn't fit it.
+    pub expected_extension: Option<String>,
+}
+
+impl TypeDetection {
+    pub fn is_mismatch(&self) -> bool {
+        self.expected_extension.is_some()
+    }
+}
+
+/// Categorizes a file for a section: by content as well when it has `detect_content_type` on,
+/// in which case the detection is returned too.
+pub fn categorize_for<'a>(
+    registry: &'a CategoryRegistry,
+    section: &ConfigSection,
+    path: &Path,
+    ext: &str,
+) -> (Option<&'a Category>, Option<TypeDetection>) {
+    if section.detect_content_type {
+        let (category, detection) = registry.classify(path, ext);
+        (category, Some(detection))
+    } else {
+        (registry.categorize(path, ext), None)
+    }
+}
+
+/// Whether files of `category` are included in a section; uncategorized files never are.
+pub fn is_enabled(section: &ConfigSection, category: Option<&Category>) -> bool {
+    category.is_some_and(|category| section.preview_enabled.is_category_enabled(&category.id))
+}
@@ -0,0 +1,342 @@
+use globset::{Glob, GlobMatcher};
+use lazy_static::lazy_static;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use sysinfo::Disks;
+
+use crate::commands::settings::ExclusionSettings;
+
+/// Well-known folders directly under home that must survive even when empty.
+const HOME_STANDARD_DIRS: &[&str] = &[
+    "Desktop", "Documents", "Downloads", "Pictures", "Music", "Movies", "Videos", "Public",
+    "Templates", "Library", "Applications",
+];
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleKind {
+    /// OS folders: walks skip them and destructive commands refuse them and anything inside.
+    System,
+    /// Destructive commands refuse the path itself and every folder containing it.
+    Protected,
+    /// Walks skip the folder and everything in it.
+    ExcludedPath,
+    /// Walks skip entries matching the glob.
+    ExcludedGlob,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleSource {
+    Platform,
+    /// From `exclusions` in settings.json.
+    User,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ExclusionRule {
+    pub kind: RuleKind,
+    pub source: RuleSource,
+    /// The path or glob as configured.
+    pub pattern: String,
+}
+
+enum Matcher {
+    /// The path and everything below it; user paths carry both the configured and the
+    /// canonical spelling, so walks through symlinks still match.
+    Tree(Vec<PathBuf>),
+    /// The path itself only. Walkers prune it, but a walk started below it (`/usr/local`)
+    /// isn't affected.
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    Exact(PathBuf),
+    /// Any path with a component of this name (lowercased), at any depth.
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    Component(String),
+    /// Globs without a `/` match the file name, others the whole path.
+    Glob { matcher: GlobMatcher, whole_path: bool },
+}
+
+/// Paths compare case-insensitively on Windows.
+fn normalized(path: &Path) -> PathBuf {
+    #[cfg(target_os = "windows")]
+    {
+        PathBuf::from(path.to_string_lossy().to_lowercase())
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        path.to_path_buf()
+    }
+}
+
+impl Matcher {
+    fn matches(&self, path: &Path) -> bool {
+        match self {
+            Matcher::Tree(roots) => {
+                let path = normalized(path);
+                roots.iter().any(|root| path.starts_with(root))
+            }
+            Matcher::Exact(exact) => normalized(path) == *exact,
+            Matcher::Component(name) => path
+                .components()
+                .any(|c| c.as_os_str().to_string_lossy().to_lowercase() == *name),
+            Matcher::Glob { matcher, whole_path: true } => matcher.is_match(path),
+            Matcher::Glob { matcher, whole_path: false } => path.file_name().is_some_and(|n| matcher.is_match(n)),
+        }
+    }
+
+    /// Whether removing `path` would take the matched location with it.
+    fn is_inside(&self, path: &Path) -> bool {
+        let path = normalized(path);
+        match self {
+            Matcher::Tree(roots) => roots.iter().any(|root| root.starts_with(&path)),
+            Matcher::Exact(exact) => exact.starts_with(&path),
+            Matcher::Component(_) | Matcher::Glob { .. } => false,
+        }
+    }
+}
+
+fn tree_matchers(paths: &[&str]) -> Vec<(String, Matcher)> {
+    paths.iter().map(|p| (p.to_string(), Matcher::Tree(vec![normalized(Path::new(p))]))).collect()
+}
+
+#[cfg(target_os = "macos")]
+fn system_matchers() -> Vec<(String, Matcher)> {
+    let mut out = tree_matchers(&["/System", "/Library", "/private", "/Applications"]);
+    // Only the folders themselves: `/usr/local` holds user-installed software.
+    for p in ["/bin", "/usr", "/sbin", "/dev", "/etc"] {
+        out.push((p.to_string(), Matcher::Exact(PathBuf::from(p))));
+    }
+    out
+}
+
+/// Where destructive commands refuse to work. On macOS `/tmp`, `/var/folders` and
+/// `/Applications` hold things users delete, and the first two resolve into `/private`, so
+/// only the OS parts of it are listed.
+#[cfg(target_os = "macos")]
+fn destructive_matchers() -> Vec<(String, Matcher)> {
+    tree_matchers(&[
+        "/System",
+        "/Library",
+        "/bin",
+        "/sbin",
+        "/usr/bin",
+        "/usr/sbin",
+        "/usr/lib",
+        "/usr/libexec",
+        "/usr/share",
+        "/dev",
+        "/etc",
+        "/private/etc",
+        "/private/var/db",
+        "/private/var/root",
+    ])
+}
+
+#[cfg(not(target_os = "macos"))]
+fn destructive_matchers() -> Vec<(String, Matcher)> {
+    system_matchers()
+}
+
+#[cfg(target_os = "windows")]
+fn system_matchers() -> Vec<(String, Matcher)> {
+    let mut out: Vec<(String, Matcher)> = [
+        ("SystemRoot", r"C:\Windows"),
+        ("ProgramFiles", r"C:\Program Files"),
+        ("ProgramFiles(x86)", r"C:\Program Files (x86)"),
+        ("ProgramData", r"C:\ProgramData"),
+    ]
+    .iter()
+    .map(|(var, fallback)| std::env::var(var).unwrap_or_else(|_| fallback.to_string()))
+    .map(|p| (p.clone(), Matcher::Tree(vec![normalized(Path::new(&p))])))
+    .collect();
+    // Present at the root of every drive
+    for name in ["$Recycle.Bin", "System Volume Information"] {
+        out.push((name.to_string(), Matcher::Component(name.to_lowercase())));
+    }
+    out
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn system_matchers() -> Vec<(String, Matcher)> {
+    tree_matchers(&["/proc", "/sys", "/boot", "/dev"])
+}
+
+pub fn home_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    let home = std::env::var_os("USERPROFILE");
+    #[cfg(not(target_os = "windows"))]
+    let home = std::env::var_os("HOME");
+    home.filter(|h| !h.is_empty()).map(PathBuf::from)
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    normalized(&std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf()))
+}
+
+/// `path` with its parent folder resolved: the entry a destructive command acts on. A symlink
+/// is removed itself, so its own target doesn't matter.
+fn resolved_entry(path: &Path) -> PathBuf {
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) if !parent.as_os_str().is_empty() => canonical(parent).join(name),
+        _ => normalized(path),
+    }
+}
+
+/// Builds a glob; `None` when the pattern is invalid (settings validation drops those).
+fn compile_glob(pattern: &str) -> Option<Matcher> {
+    let matcher = Glob::new(pattern).ok()?.compile_matcher();
+    Some(Matcher::Glob { matcher, whole_path: pattern.contains('/') })
+}
+
+/// Platform defaults plus the user's `exclusions`. Protected paths only hold the static
+/// part; volume roots are added per [DeletionGuard], as mounts come and go.
+pub struct Exclusions {
+    rules: Vec<(ExclusionRule, Matcher)>,
+    /// OS locations destructive commands refuse; see [destructive_matchers].
+    destructive: Vec<(ExclusionRule, Matcher)>,
+    /// `Protected` rules with the canonical path each one guards.
+    protected: Vec<(ExclusionRule, PathBuf)>,
+}
+
+impl Exclusions {
+    pub fn new(settings: &ExclusionSettings) -> Self {
+        let rule = |kind, source, pattern: &str| ExclusionRule { kind, source, pattern: pattern.to_string() };
+        let platform = |matchers: Vec<(String, Matcher)>| -> Vec<(ExclusionRule, Matcher)> {
+            matchers
+                .into_iter()
+                .map(|(pattern, matcher)| (rule(RuleKind::System, RuleSource::Platform, &pattern), matcher))
+                .collect()
+        };
+        let mut rules = platform(system_matchers());
+        let destructive = platform(destructive_matchers());
+        for path in &settings.excluded_paths {
+            let configured = normalized(Path::new(path));
+            let mut spellings = vec![canonical(&configured)];
+            if spellings[0] != configured {
+                spellings.push(configured);
+            }
+            rules.push((rule(RuleKind::ExcludedPath, RuleSource::User, path), Matcher::Tree(spellings)));
+        }
+        for glob in &settings.excluded_globs {
+            if let Some(matcher) = compile_glob(glob) {
+                rules.push((rule(RuleKind::ExcludedGlob, RuleSource::User, glob), matcher));
+            }
+        }
+
+        let mut protected = vec![(rule(RuleKind::Protected, RuleSource::Platform, "/"), PathBuf::from("/"))];
+        if let Some(home) = home_dir() {
+            for dir in HOME_STANDARD_DIRS {
+                let path = home.join(dir);
+                protected.push((rule(RuleKind::Protected, RuleSource::Platform, &path.to_string_lossy()), canonical(&path)));
+            }
+            protected.push((rule(RuleKind::Protected, RuleSource::Platform, &home.to_string_lossy()), canonical(&home)));
+        }
+        for path in &settings.protected_paths {
+            protected.push((rule(RuleKind::Protected, RuleSource::User, path), canonical(Path::new(path))));
+        }
+
+        Self { rules, destructive, protected }
+    }
+
+    /// The OS rule `path` falls under; listings hide these unless `show_system_files` is on.
+    pub fn system(&self, path: &Path) -> Option<&ExclusionRule> {
+        self.rules
+            .iter()
+            .find(|(rule, matcher)| rule.kind == RuleKind::System && matcher.matches(path))
+            .map(|(rule, _)| rule)
+    }
+
+    /// The rule that keeps walks out of `path`, system folders included. Walkers check
+    /// directories before descending, so one match prunes the whole subtree.
+    pub fn excluded(&self, path: &Path) -> Option<&ExclusionRule> {
+        self.rules.iter().find(|(_, matcher)| matcher.matches(path)).map(|(rule, _)| rule)
+    }
+
+    /// Whether `mount_point` holds the running system.
+    pub fn is_system_volume(&self, mount_point: &Path) -> bool {
+        #[cfg(target_os = "windows")]
+        {
+            let drive = std::env::var("SystemDrive").unwrap_or_else(|_| "C:".to_string());
+            normalized(mount_point).starts_with(normalized(Path::new(&format!("{drive}\\"))))
+        }
+        #[cfg(not(target_os = "windows"))]
+        {
+            mount_point == Path::new("/") || self.system(mount_point).is_some()
+        }
+    }
+}
+
+lazy_static! {
+    static ref EXCLUSIONS: RwLock<Arc<Exclusions>> = RwLock::new(Arc::new(Exclusions::new(&ExclusionSettings::default())));
+}
+
+/// The rules from the last loaded or saved settings.
+pub fn exclusions() -> Arc<Exclusions> {
+    EXCLUSIONS.read().unwrap().clone()
+}
+
+/// Rebuilds the rules; called wherever settings are loaded or saved.
+pub fn set_exclusions(settings: &ExclusionSettings) {
+    *EXCLUSIONS.write().unwrap() = Arc::new(Exclusions::new(settings));
+}
+
+/// What destructive commands check before touching a path. Build one per batch: it
+/// snapshots the mounted volume roots, which are protected too.
+pub struct DeletionGuard {
+    exclusions: Arc<Exclusions>,
+    volume_roots: Vec<(ExclusionRule, PathBuf)>,
+}
+
+impl DeletionGuard {
+    pub fn new() -> Self {
+        let volume_roots = Disks::new_with_refreshed_list()
+            .list()
+            .iter()
+            .map(|disk| {
+                let rule = ExclusionRule {
+                    kind: RuleKind::Protected,
+                    source: RuleSource::Platform,
+                    pattern: disk.mount_point().to_string_lossy().to_string(),
+                };
+                (rule, canonical(disk.mount_point()))
+            })
+            .collect();
+        Self { exclusions: exclusions(), volume_roots }
+    }
+
+    /// The rule that forbids removing or moving `path`: it is (inside or containing) a system
+    /// folder, or it is (or contains) a protected path. System folders are checked against
+    /// the path as given and with its parent resolved, never through the entry's own symlink.
+    pub fn rule_for(&self, path: &Path) -> Option<&ExclusionRule> {
+        let resolved = resolved_entry(path);
+        let given = normalized(path);
+        let system = self.exclusions.destructive.iter().find(|(_, matcher)| {
+            [&given, &resolved].iter().any(|p| matcher.matches(p) || matcher.is_inside(p))
+        });
+        system.map(|(rule, _)| rule).or_else(|| {
+            self.exclusions
+                .protected
+                .iter()
+                .chain(&self.volume_roots)
+                .find(|(_, protected)| protected.starts_with(&resolved))
+                .map(|(rule, _)| rule)
+        })
+    }
+
+    pub fn check(&self, path: &Path) -> Result<(), String> {
+        match self.rule_for(path) {
+            Some(rule) if rule.kind == RuleKind::System => {
+                Err(format!("{} is a system location ({})", path.display(), rule.pattern))
+            }
+            Some(rule) => Err(format!("{} is protected ({})", path.display(), rule.pattern)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Default for DeletionGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,81 @@
+use image::metadata::Orientation;
+use image::{DynamicImage, GrayImage, RgbImage};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+/// Camera RAW formats: never decoded directly, only through their embedded JPEG previews.
+pub fn is_raw_extension(ext: &str) -> bool {
+    matches!(
+        ext,
+        "cr2" | "cr3" | "nef" | "nrw" | "arw" | "srf" | "sr2" | "dng" | "orf" | "rw2" | "raf" | "pef" | "srw" | "x3f"
+    )
+}
+
+/// Parses EXIF from JPEG/TIFF/HEIF/PNG/WebP containers and TIFF-based RAWs.
+pub fn read_exif(path: &Path) -> Option<exif::Exif> {
+    let file = File::open(path).ok()?;
+    exif::Reader::new().read_from_container(&mut BufReader::new(file)).ok()
+}
+
+pub fn exif_orientation(exif: &exif::Exif) -> Orientation {
+    exif.get_field(exif::Tag::Orientation, exif::In::PRIMARY)
+        .and_then(|f| f.value.get_uint(0))
+        .and_then(|v| u8::try_from(v).ok())
+        .and_then(Orientation::from_exif)
+        .unwrap_or(Orientation::NoTransforms)
+}
+
+fn uint(exif: &exif::Exif, tag: exif::Tag, ifd: exif::In) -> Option<usize> {
+    exif.get_field(tag, ifd)?.value.get_uint(0).map(|v| v as usize)
+}
+
+/// Largest decodable JPEG preview embedded in the EXIF data: the IFD1 thumbnail, an IFD0
+/// JPEG preview, or (CR2 and friends) a single JPEG-compressed IFD0 strip.
+pub fn embedded_preview(exif: &exif::Exif) -> Option<DynamicImage> {
+    use exif::{In, Tag};
+
+    let mut spans = Vec::new();
+    for ifd in [In::PRIMARY, In::THUMBNAIL] {
+        if let (Some(offset), Some(len)) = (
+            uint(exif, Tag::JPEGInterchangeFormat, ifd),
+            uint(exif, Tag::JPEGInterchangeFormatLength, ifd),
+        ) {
+            spans.push((offset, len));
+        }
+    }
+    // Compression 6 = old-style JPEG; CR2 stores a full-size JPEG this way.
+    if uint(exif, Tag::Compression, In::PRIMARY) == Some(6) {
+        if let (Some(offset), Some(len)) = (
+            uint(exif, Tag::StripOffsets, In::PRIMARY),
+            uint(exif, Tag::StripByteCounts, In::PRIMARY),
+        ) {
+            spans.push((offset, len));
+        }
+    }
+
+    spans.sort_by_key(|(_, len)| std::cmp::Reverse(*len));
+    let buf = exif.buf();
+    spans.into_iter().find_map(|(offset, len)| {
+        let bytes = buf.get(offset..offset.checked_add(len)?)?;
+        image::load_from_memory_with_format(bytes, image::ImageFormat::Jpeg).ok()
+    })
+}
+
+/// Decodes a JPEG with DCT scaling (1/2, 1/4, 1/8) so only about `width`x`height` pixels are
+/// materialized. Returns `None` for pixel formats the scaled path doesn't handle (CMYK, 16-bit).
+pub fn decode_jpeg_scaled(path: &Path, width: u32, height: u32) -> Option<DynamicImage> {
+    let file = File::open(path).ok()?;
+    let mut decoder = jpeg_decoder::Decoder::new(BufReader::new(file));
+    decoder.read_info().ok()?;
+    let clamp = |v: u32| v.clamp(1, u16::MAX as u32) as u16;
+    decoder.scale(clamp(width), clamp(height)).ok()?;
+    let pixels = decoder.decode().ok()?;
+    let info = decoder.info()?;
+    let (w, h) = (info.width as u32, info.height as u32);
+    match info.pixel_format {
+        jpeg_decoder::PixelFormat::RGB24 => RgbImage::from_raw(w, h, pixels).map(DynamicImage::ImageRgb8),
+        jpeg_decoder::PixelFormat::L8 => GrayImage::from_raw(w, h, pixels).map(DynamicImage::ImageLuma8),
+        _ => None,
+    }
+}
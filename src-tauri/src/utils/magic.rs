@@ -0,0 +1,353 @@
+use serde::Serialize;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::utils::file_types::FileCategory;
+
+/// Bytes read from the start of a file for detection; every signature but ISO 9660 fits in this.
+pub const MAGIC_SNIFF_BYTES: usize = 8 * 1024;
+const ISO_DESCRIPTOR_OFFSET: usize = 0x8001;
+
+/// A file type identified from its content.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct MagicType {
+    pub mime: &'static str,
+    /// Human-readable name, e.g. "PNG image".
+    pub name: &'static str,
+    /// Extensions that are correct for this content; the first is canonical.
+    pub extensions: &'static [&'static str],
+    pub category: FileCategory,
+}
+
+impl MagicType {
+    /// Whether a file with extension `ext` (lowercase, no dot) fits this content. Versioned
+    /// ELF libraries and binaries ("libc.so.6", "python3.12") end in a number and count too.
+    pub fn accepts_extension(&self, ext: &str) -> bool {
+        self.extensions.contains(&ext) || (*self == ELF && !ext.is_empty() && ext.bytes().all(|b| b.is_ascii_digit()))
+    }
+}
+
+const fn t(
+    mime: &'static str,
+    name: &'static str,
+    extensions: &'static [&'static str],
+    category: FileCategory,
+) -> MagicType {
+    MagicType { mime, name, extensions, category }
+}
+
+use FileCategory::{Archive, Audio, Document, Image, Other, Video};
+
+const PNG: MagicType = t("image/png", "PNG image", &["png", "apng"], Image);
+const JPEG: MagicType = t("image/jpeg", "JPEG image", &["jpg", "jpeg", "jpe", "jfif"], Image);
+const GIF: MagicType = t("image/gif", "GIF image", &["gif"], Image);
+const WEBP: MagicType = t("image/webp", "WebP image", &["webp"], Image);
+const BMP: MagicType = t("image/bmp", "BMP image", &["bmp", "dib"], Image);
+const TIFF: MagicType = t("image/tiff", "TIFF image", &["tif", "tiff", "dng", "nef", "cr2", "arw", "pef", "srw", "orf", "rw2"], Image);
+const ICO: MagicType = t("image/x-icon", "Windows icon", &["ico", "cur"], Image);
+const PSD: MagicType = t("image/vnd.adobe.photoshop", "Photoshop document", &["psd"], Image);
+const HEIC: MagicType = t("image/heic", "HEIF image", &["heic", "heif"], Image);
+const AVIF: MagicType = t("image/avif", "AVIF image", &["avif"], Image);
+const JXL: MagicType = t("image/jxl", "JPEG XL image", &["jxl"], Image);
+const CR3: MagicType = t("image/x-canon-cr3", "Canon CR3 raw", &["cr3"], Image);
+const RAF: MagicType = t("image/x-fuji-raf", "Fujifilm RAF raw", &["raf"], Image);
+
+const MP4: MagicType = t("video/mp4", "MPEG-4 video", &["mp4", "m4v", "m4a"], Video);
+const MOV: MagicType = t("video/quicktime", "QuickTime video", &["mov", "qt"], Video);
+const THREE_GP: MagicType = t("video/3gpp", "3GPP video", &["3gp", "3g2"], Video);
+const MKV: MagicType = t("video/x-matroska", "Matroska video", &["mkv", "webm", "mka"], Video);
+const AVI: MagicType = t("video/x-msvideo", "AVI video", &["avi"], Video);
+const FLV: MagicType = t("video/x-flv", "Flash video", &["flv"], Video);
+const MPEG_TS: MagicType = t("video/mp2t", "MPEG transport stream", &["ts", "mts", "m2ts"], Video);
+const MPEG_PS: MagicType = t("video/mpeg", "MPEG video", &["mpg", "mpeg", "vob"], Video);
+const ASF: MagicType = t("video/x-ms-asf", "Windows Media", &["wmv", "wma", "asf"], Video);
+
+const MP3: MagicType = t("audio/mpeg", "MP3 audio", &["mp3"], Audio);
+const M4A: MagicType = t("audio/mp4", "MPEG-4 audio", &["m4a", "m4b", "aac"], Audio);
+const FLAC: MagicType = t("audio/flac", "FLAC audio", &["flac"], Audio);
+const OGG: MagicType = t("audio/ogg", "Ogg media", &["ogg", "oga", "opus", "ogv"], Audio);
+const WAV: MagicType = t("audio/wav", "WAV audio", &["wav"], Audio);
+const AIFF: MagicType = t("audio/aiff", "AIFF audio", &["aif", "aiff", "aifc"], Audio);
+const MIDI: MagicType = t("audio/midi", "MIDI", &["mid", "midi"], Audio);
+const AAC_ADTS: MagicType = t("audio/aac", "AAC audio", &["aac"], Audio);
+
+const PDF: MagicType = t("application/pdf", "PDF document", &["pdf"], Document);
+const RTF: MagicType = t("application/rtf", "Rich Text document", &["rtf"], Document);
+const OLE: MagicType = t("application/x-ole-storage", "Legacy Office document", &["doc", "xls", "ppt", "msg", "msi"], Document);
+const DOCX: MagicType = t("application/vnd.openxmlformats-officedocument.wordprocessingml.document", "Word document", &["docx", "docm", "dotx"], Document);
+const XLSX: MagicType = t("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet", "Excel workbook", &["xlsx", "xlsm", "xltx"], Document);
+const PPTX: MagicType = t("application/vnd.openxmlformats-officedocument.presentationml.presentation", "PowerPoint presentation", &["pptx", "pptm", "potx"], Document);
+const ODT: MagicType = t("application/vnd.oasis.opendocument.text", "OpenDocument text", &["odt"], Document);
+const ODS: MagicType = t("application/vnd.oasis.opendocument.spreadsheet", "OpenDocument spreadsheet", &["ods"], Document);
+const ODP: MagicType = t("application/vnd.oasis.opendocument.presentation", "OpenDocument presentation", &["odp"], Document);
+const EPUB: MagicType = t("application/epub+zip", "EPUB book", &["epub"], Document);
+
+const ZIP: MagicType = t("application/zip", "ZIP archive", &["zip", "jar", "apk", "ipa", "xpi", "whl", "nupkg", "aar", "war", "pages", "numbers", "key"], Archive);
+const GZIP: MagicType = t("application/gzip", "gzip archive", &["gz", "tgz"], Archive);
+const BZIP2: MagicType = t("application/x-bzip2", "bzip2 archive", &["bz2", "tbz", "tbz2"], Archive);
+const XZ: MagicType = t("application/x-xz", "XZ archive", &["xz", "txz"], Archive);
+const ZSTD: MagicType = t("application/zstd", "Zstandard archive", &["zst", "tzst"], Archive);
+const SEVEN_Z: MagicType = t("application/x-7z-compressed", "7-Zip archive", &["7z"], Archive);
+const RAR: MagicType = t("application/vnd.rar", "RAR archive", &["rar"], Archive);
+const TAR: MagicType = t("application/x-tar", "tar archive", &["tar"], Archive);
+const LZ4: MagicType = t("application/x-lz4", "LZ4 archive", &["lz4"], Archive);
+const CAB: MagicType = t("application/vnd.ms-cab-compressed", "Cabinet archive", &["cab"], Archive);
+const ISO: MagicType = t("application/x-iso9660-image", "Disk image", &["iso"], Archive);
+const DEB: MagicType = t("application/vnd.debian.binary-package", "Debian package", &["deb", "a"], Archive);
+const RPM: MagicType = t("application/x-rpm", "RPM package", &["rpm"], Archive);
+
+const ELF: MagicType = t("application/x-elf", "ELF executable", &["", "so", "o", "elf", "bin"], Other);
+const MACHO: MagicType = t("application/x-mach-binary", "Mach-O executable", &["", "dylib", "bundle", "o"], Other);
+const PE: MagicType = t("application/vnd.microsoft.portable-executable", "Windows executable", &["exe", "dll", "sys", "scr", "ocx", "efi"], Other);
+const JAVA_CLASS: MagicType = t("application/java-vm", "Java class", &["class"], Other);
+const WASM: MagicType = t("application/wasm", "WebAssembly module", &["wasm"], Other);
+const SQLITE: MagicType = t("application/vnd.sqlite3", "SQLite database", &["sqlite", "sqlite3", "db", "db3"], Other);
+const TTF: MagicType = t("font/ttf", "TrueType font", &["ttf", "ttc"], Other);
+const OTF: MagicType = t("font/otf", "OpenType font", &["otf"], Other);
+const WOFF: MagicType = t("font/woff", "WOFF font", &["woff"], Other);
+const WOFF2: MagicType = t("font/woff2", "WOFF2 font", &["woff2"], Other);
+
+/// Fixed signatures: (offset, magic bytes, type). Containers that need a closer look (RIFF,
+/// ISO-BMFF `ftyp`, ZIP, FORM) are handled in [detect_bytes].
+const SIGNATURES: &[(usize, &[u8], MagicType)] = &[
+    (0, b"\x89PNG\r\n\x1a\n", PNG),
+    (0, b"\xFF\xD8\xFF", JPEG),
+    (0, b"GIF87a", GIF),
+    (0, b"GIF89a", GIF),
+    (0, b"II*\x00", TIFF),
+    (0, b"MM\x00*", TIFF),
+    (0, b"\x00\x00\x01\x00", ICO),
+    (0, b"8BPS", PSD),
+    (0, b"\x00\x00\x00\x0CJXL \r\n\x87\n", JXL),
+    (0, b"FUJIFILMCCD-RAW", RAF),
+    (0, b"\x1A\x45\xDF\xA3", MKV),
+    (0, b"FLV\x01", FLV),
+    (0, b"\x00\x00\x01\xBA", MPEG_PS),
+    (0, b"\x30\x26\xB2\x75\x8E\x66\xCF\x11", ASF),
+    (0, b"ID3", MP3),
+    (0, b"fLaC", FLAC),
+    (0, b"OggS", OGG),
+    (0, b"MThd", MIDI),
+    (0, b"%PDF-", PDF),
+    (0, b"{\\rtf", RTF),
+    (0, b"\xD0\xCF\x11\xE0\xA1\xB1\x1A\xE1", OLE),
+    (0, b"\x1F\x8B", GZIP),
+    (0, b"BZh", BZIP2),
+    (0, b"\xFD7zXZ\x00", XZ),
+    (0, b"\x28\xB5\x2F\xFD", ZSTD),
+    (0, b"7z\xBC\xAF\x27\x1C", SEVEN_Z),
+    (0, b"Rar!\x1A\x07", RAR),
+    (257, b"ustar", TAR),
+    (0, b"\x04\x22\x4D\x18", LZ4),
+    (0, b"MSCF", CAB),
+    (0, b"!<arch>\ndebian", DEB),
+    (0, b"\xED\xAB\xEE\xDB", RPM),
+    (0, b"\x7FELF", ELF),
+    (0, b"\xFE\xED\xFA\xCE", MACHO),
+    (0, b"\xFE\xED\xFA\xCF", MACHO),
+    (0, b"\xCE\xFA\xED\xFE", MACHO),
+    (0, b"\xCF\xFA\xED\xFE", MACHO),
+    (0, b"\x00asm", WASM),
+    (0, b"SQLite format 3\x00", SQLITE),
+    (0, b"\x00\x01\x00\x00\x00", TTF),
+    (0, b"ttcf", TTF),
+    (0, b"OTTO", OTF),
+    (0, b"wOFF", WOFF),
+    (0, b"wOF2", WOFF2),
+];
+
+/// Detects the type of `bytes` (the start of a file). `None` means no known signature, which
+/// includes plain text.
+pub fn detect_bytes(bytes: &[u8]) -> Option<MagicType> {
+    let at = |offset: usize, magic: &[u8]| bytes.get(offset..offset + magic.len()) == Some(magic);
+
+    if at(0, b"RIFF") {
+        return match bytes.get(8..12)? {
+            b"WEBP" => Some(WEBP),
+            b"WAVE" => Some(WAV),
+            b"AVI " => Some(AVI),
+            _ => None,
+        };
+    }
+    if at(0, b"FORM") && matches!(bytes.get(8..12), Some(b"AIFF") | Some(b"AIFC")) {
+        return Some(AIFF);
+    }
+    if at(4, b"ftyp") {
+        return Some(ftyp_type(bytes));
+    }
+    if at(0, b"PK\x03\x04") {
+        return Some(zip_type(bytes));
+    }
+    // 0xCAFEBABE is both a Java class and a Mach-O fat binary; Java's major version is >= 45
+    // while fat binaries count architectures (small numbers).
+    if at(0, b"\xCA\xFE\xBA\xBE") {
+        let n = u32::from_be_bytes(bytes.get(4..8)?.try_into().ok()?);
+        return Some(if n > 0x2C { JAVA_CLASS } else { MACHO });
+    }
+    // "BM" alone is too common in text; require a known DIB header size as well.
+    if at(0, b"BM") {
+        let dib = u32::from_le_bytes(bytes.get(14..18)?.try_into().ok()?);
+        if matches!(dib, 12 | 40 | 52 | 56 | 64 | 108 | 124) {
+            return Some(BMP);
+        }
+    }
+    // MPEG-TS: 0x47 sync byte repeating every 188 bytes.
+    if bytes.len() >= 188 * 3 && (0..3).all(|i| bytes[i * 188] == 0x47) {
+        return Some(MPEG_TS);
+    }
+    // Only reachable when the caller passes a whole mapped file; see [detect_file].
+    if at(ISO_DESCRIPTOR_OFFSET, b"CD001") {
+        return Some(ISO);
+    }
+
+    // "MZ" alone matches plenty of text; a PE image points at its "PE\0\0" header.
+    if at(0, b"MZ") {
+        let pe_offset = u32::from_le_bytes(bytes.get(0x3C..0x40)?.try_into().ok()?) as usize;
+        if at(pe_offset, b"PE\x00\x00") {
+            return Some(PE);
+        }
+    }
+    // Bare JPEG XL codestream: the two-byte marker must be followed by binary header data,
+    // not by text that merely happens to start with 0xFF and a newline.
+    if at(0, b"\xFF\x0A") {
+        let header = bytes.get(2..16)?;
+        if header.iter().any(|&b| b < 0x09 || (0x0E..0x20).contains(&b) || b >= 0x7F) {
+            return Some(JXL);
+        }
+    }
+
+    if let Some((_, _, found)) = SIGNATURES.iter().find(|(offset, magic, _)| at(*offset, magic)) {
+        return Some(*found);
+    }
+
+    // Frame-sync headers without a tag: ADTS AAC, and MPEG audio confirmed by a valid header
+    // and, when it fits in the buffer, a second frame right after the first.
+    match bytes {
+        // UTF-16 byte order marks look like frame syncs.
+        [0xFF, 0xFE, ..] | [0xFE, 0xFF, ..] => None,
+        [0xFF, b1, b2, ..] if b1 & 0xF6 == 0xF0 && (b2 >> 2) & 0x0F < 13 => Some(AAC_ADTS),
+        [0xFF, ..] => {
+            let len = mpeg_audio_frame_len(bytes)?;
+            match bytes.get(len..len + 2) {
+                Some(&[0xFF, next]) if next & 0xE0 == 0xE0 => Some(MP3),
+                Some(_) => None,
+                None => Some(MP3),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Length in bytes of the MPEG audio frame whose header starts `bytes`; `None` when the
+/// header is invalid (reserved version/layer, free-format or bad bitrate, reserved rate).
+fn mpeg_audio_frame_len(bytes: &[u8]) -> Option<usize> {
+    const V1_BITRATES: [[u32; 15]; 3] = [
+        [0, 32, 64, 96, 128, 160, 192, 224, 256, 288, 320, 352, 384, 416, 448],
+        [0, 32, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384],
+        [0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320],
+    ];
+    const V2_BITRATES: [[u32; 15]; 2] = [
+        [0, 32, 48, 56, 64, 80, 96, 112, 128, 144, 160, 176, 192, 224, 256],
+        [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160],
+    ];
+
+    let &[0xFF, b1, b2, ..] = bytes else { return None };
+    if b1 & 0xE0 != 0xE0 {
+        return None;
+    }
+    let version = (b1 >> 3) & 0x03; // 3 = MPEG-1, 2 = MPEG-2, 0 = MPEG-2.5
+    let layer = (b1 >> 1) & 0x03; // 3 = I, 2 = II, 1 = III
+    let bitrate_index = (b2 >> 4) as usize;
+    let rate_index = ((b2 >> 2) & 0x03) as usize;
+    if version == 1 || layer == 0 || bitrate_index == 0 || bitrate_index == 15 || rate_index == 3 {
+        return None;
+    }
+
+    let kbps = match (version, layer) {
+        (3, _) => V1_BITRATES[(3 - layer) as usize][bitrate_index],
+        (_, 3) => V2_BITRATES[0][bitrate_index],
+        _ => V2_BITRATES[1][bitrate_index],
+    };
+    // MPEG-2 halves the MPEG-1 rates, MPEG-2.5 quarters them.
+    let sample_rate = [44_100, 48_000, 32_000][rate_index] >> match version {
+        3 => 0,
+        2 => 1,
+        _ => 2,
+    };
+    let padding = ((b2 >> 1) & 0x01) as u32;
+    let bits_per_second = kbps * 1000;
+    let len = match layer {
+        3 => (12 * bits_per_second / sample_rate + padding) * 4,
+        1 if version != 3 => 72 * bits_per_second / sample_rate + padding,
+        _ => 144 * bits_per_second / sample_rate + padding,
+    };
+    Some(len as usize)
+}
+
+/// ISO base media files: the major brand tells MP4, QuickTime, HEIF, AVIF, CR3 and M4A apart.
+fn ftyp_type(bytes: &[u8]) -> MagicType {
+    match bytes.get(8..12).unwrap_or_default() {
+        b"qt  " => MOV,
+        b"M4A " | b"M4B " | b"M4P " => M4A,
+        b"heic" | b"heix" | b"hevc" | b"heim" | b"heis" | b"mif1" | b"msf1" => HEIC,
+        b"avif" | b"avis" => AVIF,
+        b"crx " => CR3,
+        brand if brand.starts_with(b"3g") => THREE_GP,
+        _ => MP4,
+    }
+}
+
+/// ZIP-based document formats: ODF/EPUB store an uncompressed `mimetype` entry first, OOXML
+/// has `[Content_Types].xml` and a `word/`, `xl/` or `ppt/` tree near the start.
+fn zip_type(bytes: &[u8]) -> MagicType {
+    let name_len = bytes.get(26..28).map(|b| u16::from_le_bytes([b[0], b[1]]) as usize).unwrap_or(0);
+    let extra_len = bytes.get(28..30).map(|b| u16::from_le_bytes([b[0], b[1]]) as usize).unwrap_or(0);
+    let first_name = bytes.get(30..30 + name_len).unwrap_or_default();
+    if first_name == b"mimetype" {
+        let content = bytes.get(30 + name_len + extra_len..).unwrap_or_default();
+        let starts = |m: &[u8]| content.starts_with(m);
+        if starts(b"application/epub+zip") {
+            return EPUB;
+        }
+        if starts(b"application/vnd.oasis.opendocument.text") {
+            return ODT;
+        }
+        if starts(b"application/vnd.oasis.opendocument.spreadsheet") {
+            return ODS;
+        }
+        if starts(b"application/vnd.oasis.opendocument.presentation") {
+            return ODP;
+        }
+    }
+
+    // Callers may pass a whole mapped file; only the head holds the first entries.
+    let head = &bytes[..bytes.len().min(MAGIC_SNIFF_BYTES)];
+    let contains = |needle: &[u8]| memchr::memmem::find(head, needle).is_some();
+    if contains(b"[Content_Types].xml") || contains(b"_rels/.rels") {
+        if contains(b"word/") {
+            return DOCX;
+        }
+        if contains(b"xl/") {
+            return XLSX;
+        }
+        if contains(b"ppt/") {
+            return PPTX;
+        }
+    }
+    ZIP
+}
+
+/// Reads the start of `path` and detects its type.
+pub fn detect_file(path: &Path) -> Option<MagicType> {
+    let mut file = std::fs::File::open(path).ok()?;
+    let mut bytes = Vec::with_capacity(MAGIC_SNIFF_BYTES);
+    (&mut file).take(MAGIC_SNIFF_BYTES as u64).read_to_end(&mut bytes).ok()?;
+    if let Some(found) = detect_bytes(&bytes) {
+        return Some(found);
+    }
+    // ISO 9660's volume descriptor is the one signature past the sniff window.
+    let mut descriptor = [0u8; 5];
+    file.seek(SeekFrom::Start(ISO_DESCRIPTOR_OFFSET as u64)).ok()?;
+    file.read_exact(&mut descriptor).ok()?;
+    (&descriptor == b"CD001").then_some(ISO)
+}
@@ -1,3 +1,10 @@
 pub mod file_types;
-pub mod path_visibility;
+pub mod categories;
 pub mod text_like;
+pub mod thumbnail_cache;
+pub mod image_decode;
+pub mod audio_art;
+pub mod text_encoding;
+pub mod magic;
+pub mod mountinfo;
+pub mod exclusion;
@@ -0,0 +1,262 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::commands::settings::ConfigSection;
+
+/// The mount table of this process's namespace; pollable for changes (see `watch_mounts`).
+pub const MOUNTINFO_PATH: &str = "/proc/self/mountinfo";
+
+/// One line of `/proc/self/mountinfo`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountInfo {
+    pub id: u32,
+    pub parent_id: u32,
+    /// `major:minor` of the filesystem's device.
+    pub device_number: String,
+    /// Directory of the filesystem that is mounted here (not `/` for bind mounts).
+    pub root: String,
+    pub mount_point: PathBuf,
+    pub options: Vec<String>,
+    pub fs_type: String,
+    /// Device path, remote share or pseudo name such as `tmpfs`.
+    pub source: String,
+    pub super_options: Vec<String>,
+}
+
+impl MountInfo {
+    pub fn read_only(&self) -> bool {
+        self.options.iter().any(|o| o == "ro")
+    }
+}
+
+/// Mount points escape space, tab, newline and backslash as octal (`\040`).
+fn unescape_octal(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let code = bytes
+            .get(i + 1..i + 4)
+            .filter(|_| bytes[i] == b'\\')
+            .and_then(|digits| u8::from_str_radix(std::str::from_utf8(digits).ok()?, 8).ok());
+        match code {
+            Some(byte) => {
+                out.push(byte);
+                i += 4;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn parse_line(line: &str) -> Option<MountInfo> {
+    let (head, tail) = line.split_once(" - ")?;
+    let mut head = head.split(' ');
+    let mut tail = tail.split(' ');
+    Some(MountInfo {
+        id: head.next()?.parse().ok()?,
+        parent_id: head.next()?.parse().ok()?,
+        device_number: head.next()?.to_string(),
+        root: unescape_octal(head.next()?),
+        mount_point: PathBuf::from(unescape_octal(head.next()?)),
+        options: head.next()?.split(',').map(str::to_string).collect(),
+        fs_type: tail.next()?.to_string(),
+        source: unescape_octal(tail.next()?),
+        super_options: tail.next().unwrap_or_default().split(',').map(str::to_string).collect(),
+    })
+}
+
+/// Parses mountinfo content; malformed lines are skipped.
+pub fn parse_mountinfo(content: &str) -> Vec<MountInfo> {
+    content.lines().filter_map(parse_line).collect()
+}
+
+/// The current mount table; empty where there is no `/proc` (non-Linux).
+pub fn read_mounts() -> Vec<MountInfo> {
+    fs::read_to_string(MOUNTINFO_PATH)
+        .map(|content| parse_mountinfo(&content))
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MountKind {
+    Local,
+    Removable,
+    /// Remote shares, and FUSE filesystems other than `fuseblk`: most are remote or slow.
+    Network,
+    /// Kernel, in-memory and container filesystems with no user data of their own.
+    Virtual,
+    /// Filesystem images attached through a loop device (snaps, mounted ISOs).
+    Loop,
+}
+
+const NETWORK_FS: &[&str] = &[
+    "nfs", "nfs4", "cifs", "smb3", "smbfs", "afpfs", "webdav", "davfs", "ncpfs", "9p", "afs", "ceph",
+    "glusterfs", "lustre", "sshfs", "fuse",
+];
+
+const VIRTUAL_FS: &[&str] = &[
+    "proc", "sysfs", "tmpfs", "devtmpfs", "devpts", "devfs", "ramfs", "cgroup", "cgroup2", "securityfs",
+    "debugfs", "tracefs", "configfs", "fusectl", "mqueue", "hugetlbfs", "pstore", "bpf", "autofs",
+    "binfmt_misc", "efivarfs", "rpc_pipefs", "nsfs", "overlay", "selinuxfs", "nfsd",
+];
+
+/// Classifies a filesystem by its type alone; see [classify] for the full check.
+pub fn classify_fs(fs_type: &str) -> Option<MountKind> {
+    if NETWORK_FS.contains(&fs_type) || fs_type.starts_with("fuse.") {
+        Some(MountKind::Network)
+    } else if VIRTUAL_FS.contains(&fs_type) {
+        Some(MountKind::Virtual)
+    } else {
+        None
+    }
+}
+
+/// Whether the disk behind a block device (`/dev/sdb1` → `sdb`) reports itself removable.
+fn is_removable_device(source: &str) -> bool {
+    let Ok(device) = fs::canonicalize(source) else { return false };
+    let Some(name) = device.file_name() else { return false };
+    let Ok(mut sys) = fs::canonicalize(Path::new("/sys/class/block").join(name)) else { return false };
+    if sys.join("partition").exists() {
+        sys.pop();
+    }
+    fs::read_to_string(sys.join("removable")).is_ok_and(|flag| flag.trim() == "1")
+}
+
+pub fn classify(mount: &MountInfo) -> MountKind {
+    if let Some(kind) = classify_fs(&mount.fs_type) {
+        kind
+    } else if mount.source.starts_with("/dev/loop") {
+        MountKind::Loop
+    } else if is_removable_device(&mount.source) {
+        MountKind::Removable
+    } else {
+        MountKind::Local
+    }
+}
+
+/// Every mount point with its kind; from mountinfo on Linux, the disk list elsewhere.
+pub fn mount_kinds() -> HashMap<PathBuf, MountKind> {
+    let mounts = read_mounts();
+    if !mounts.is_empty() {
+        // Later entries are stacked on top of earlier ones and win
+        return mounts.iter().map(|m| (m.mount_point.clone(), classify(m))).collect();
+    }
+    sysinfo::Disks::new_with_refreshed_list()
+        .iter()
+        .map(|d| {
+            let kind = classify_fs(&d.file_system().to_string_lossy()).unwrap_or(if d.is_removable() {
+                MountKind::Removable
+            } else {
+                MountKind::Local
+            });
+            (d.mount_point().to_path_buf(), kind)
+        })
+        .collect()
+}
+
+/// Which mount points a walk may enter, from a section's `same_filesystem` and
+/// `skip_network_mounts`. Build one per walk; the mount table is read once.
+pub struct MountFilter {
+    boundaries: HashMap<PathBuf, MountKind>,
+    same_filesystem: bool,
+    skip_network_mounts: bool,
+}
+
+impl MountFilter {
+    pub fn new(section: &ConfigSection) -> Self {
+        let active = section.same_filesystem || section.skip_network_mounts;
+        Self {
+            boundaries: if active { mount_kinds() } else { HashMap::new() },
+            same_filesystem: section.same_filesystem,
+            skip_network_mounts: section.skip_network_mounts,
+        }
+    }
+
+    /// Whether a walk may descend into the directory `dir`. Only ask for folders below the
+    /// one a walk started from: starting folders are always walked, even on a network share.
+    pub fn allows(&self, dir: &Path) -> bool {
+        match self.boundaries.get(dir) {
+            None => true,
+            Some(kind) => !(self.same_filesystem || (self.skip_network_mounts && *kind == MountKind::Network)),
+        }
+    }
+}
+
+/// The mount `path` lives on: the deepest mount point above it, the later one when stacked.
+pub fn mount_for<'a>(mounts: &'a [MountInfo], path: &Path) -> Option<&'a MountInfo> {
+    mounts
+        .iter()
+        .enumerate()
+        .filter(|(_, m)| path.starts_with(&m.mount_point))
+        .max_by_key(|(index, m)| (m.mount_point.components().count(), *index))
+        .map(|(_, m)| m)
+}
+
+/// `/dev/disk/by-label` escapes unsafe characters as `\xHH`.
+fn unescape_hex(name: &str) -> String {
+    let mut out = Vec::with_capacity(name.len());
+    let bytes = name.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        let code = bytes
+            .get(i + 2..i + 4)
+            .filter(|_| bytes[i..].starts_with(b"\\x"))
+            .and_then(|digits| u8::from_str_radix(std::str::from_utf8(digits).ok()?, 16).ok());
+        match code {
+            Some(byte) => {
+                out.push(byte);
+                i += 4;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Name of the `/dev/disk/<dir>` link that points at `device`, if any.
+fn disk_link(dir: &str, device: &Path) -> Option<String> {
+    fs::read_dir(Path::new("/dev/disk").join(dir))
+        .ok()?
+        .flatten()
+        .find(|entry| fs::canonicalize(entry.path()).is_ok_and(|target| target == device))
+        .map(|entry| unescape_hex(&entry.file_name().to_string_lossy()))
+}
+
+/// Filesystem UUID and label of a block device, from the udev `/dev/disk/by-*` links.
+pub fn device_ids(source: &str) -> (Option<String>, Option<String>) {
+    let Ok(device) = fs::canonicalize(source) else { return (None, None) };
+    if !device.starts_with("/dev") {
+        return (None, None);
+    }
+    (disk_link("by-uuid", &device), disk_link("by-label", &device))
+}
+
+/// Total and free inodes of the filesystem at `path`; `None` where the filesystem doesn't
+/// have a fixed inode table (it reports 0).
+#[cfg(unix)]
+pub fn inode_usage(path: &Path) -> Option<(u64, u64)> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 || stat.f_files == 0 {
+        return None;
+    }
+    Some((stat.f_files as u64, stat.f_ffree as u64))
+}
+
+#[cfg(not(unix))]
+pub fn inode_usage(_path: &Path) -> Option<(u64, u64)> {
+    None
+}
@@ -0,0 +1,157 @@
+use serde::Serialize;
+
+/// How much of the file start is inspected for BOMs, UTF-8 validity and line endings.
+pub const SNIFF_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TextEncoding {
+    Utf8,
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+    /// Fallback for bytes that aren't valid UTF-8; every byte maps to one char.
+    Latin1,
+}
+
+impl TextEncoding {
+    pub fn bom_len(self) -> usize {
+        match self {
+            Self::Utf8Bom => 3,
+            Self::Utf16Le | Self::Utf16Be => 2,
+            _ => 0,
+        }
+    }
+
+    pub fn is_utf16(self) -> bool {
+        matches!(self, Self::Utf16Le | Self::Utf16Be)
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+    Cr,
+    Mixed,
+    /// No line break in the sniffed sample.
+    None,
+}
+
+/// Detects the encoding from a sample of the file start: BOM first, then UTF-8 validity,
+/// then BOM-less UTF-16 (NUL bytes in every other position), else Latin-1.
+pub fn detect_encoding(sample: &[u8]) -> TextEncoding {
+    if sample.starts_with(&[0xEF, 0xBB, 0xBF]) {
+        return TextEncoding::Utf8Bom;
+    }
+    if sample.starts_with(&[0xFF, 0xFE]) {
+        return TextEncoding::Utf16Le;
+    }
+    if sample.starts_with(&[0xFE, 0xFF]) {
+        return TextEncoding::Utf16Be;
+    }
+
+    let pairs = sample.len() / 2;
+    if pairs >= 4 {
+        let even_nuls = sample.iter().step_by(2).filter(|b| **b == 0).count();
+        let odd_nuls = sample.iter().skip(1).step_by(2).filter(|b| **b == 0).count();
+        if odd_nuls * 10 > pairs * 4 && even_nuls * 10 < pairs {
+            return TextEncoding::Utf16Le;
+        }
+        if even_nuls * 10 > pairs * 4 && odd_nuls * 10 < pairs {
+            return TextEncoding::Utf16Be;
+        }
+    }
+
+    match std::str::from_utf8(sample) {
+        Ok(_) => TextEncoding::Utf8,
+        // The sample may end mid-character; only a real invalid sequence counts.
+        Err(e) if e.error_len().is_none() => TextEncoding::Utf8,
+        Err(_) => TextEncoding::Latin1,
+    }
+}
+
+/// Line-ending style of the sample, ignoring a trailing CR that may be half of a CRLF.
+pub fn detect_line_ending(sample: &[u8], encoding: TextEncoding) -> LineEnding {
+    let units = code_units(sample, encoding);
+    let (mut lf, mut crlf, mut cr) = (0usize, 0usize, 0usize);
+    let mut i = 0;
+    while i < units.len() {
+        match units[i] {
+            0x0D if units.get(i + 1) == Some(&0x0A) => {
+                crlf += 1;
+                i += 1;
+            }
+            0x0D if i + 1 < units.len() => cr += 1,
+            0x0A => lf += 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    match (lf > 0, crlf > 0, cr > 0) {
+        (false, false, false) => LineEnding::None,
+        (true, false, false) => LineEnding::Lf,
+        (false, true, false) => LineEnding::Crlf,
+        (false, false, true) => LineEnding::Cr,
+        _ => LineEnding::Mixed,
+    }
+}
+
+fn code_units(bytes: &[u8], encoding: TextEncoding) -> Vec<u16> {
+    match encoding {
+        TextEncoding::Utf16Le => bytes.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect(),
+        TextEncoding::Utf16Be => bytes.chunks_exact(2).map(|c| u16::from_be_bytes([c[0], c[1]])).collect(),
+        _ => bytes.iter().map(|b| *b as u16).collect(),
+    }
+}
+
+/// Decodes without failing: invalid sequences become U+FFFD. A BOM, if present, must already
+/// be stripped by the caller.
+pub fn decode(bytes: &[u8], encoding: TextEncoding) -> String {
+    match encoding {
+        TextEncoding::Utf8 | TextEncoding::Utf8Bom => String::from_utf8_lossy(bytes).into_owned(),
+        TextEncoding::Utf16Le | TextEncoding::Utf16Be => char::decode_utf16(code_units(bytes, encoding))
+            .map(|r| r.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect(),
+        TextEncoding::Latin1 => bytes.iter().map(|b| *b as char).collect(),
+    }
+}
+
+/// Moves `offset` forward to the next character boundary (never past `bytes.len()`), so a
+/// range read starting mid-character doesn't begin with garbage.
+pub fn align_forward(bytes: &[u8], offset: usize, encoding: TextEncoding) -> usize {
+    let offset = offset.max(encoding.bom_len()).min(bytes.len());
+    match encoding {
+        TextEncoding::Utf8 | TextEncoding::Utf8Bom => {
+            let mut o = offset;
+            // At most 3 continuation bytes follow a lead byte.
+            while o < bytes.len() && o < offset + 3 && (bytes[o] & 0xC0) == 0x80 {
+                o += 1;
+            }
+            o
+        }
+        TextEncoding::Utf16Le | TextEncoding::Utf16Be => (offset + 1) & !1,
+        TextEncoding::Latin1 => offset,
+    }
+    .min(bytes.len())
+}
+
+/// Moves an exclusive `end` back so the range doesn't cut a character in half.
+pub fn align_backward(bytes: &[u8], end: usize, encoding: TextEncoding) -> usize {
+    let end = end.min(bytes.len());
+    match encoding {
+        TextEncoding::Utf8 | TextEncoding::Utf8Bom => {
+            if end == bytes.len() {
+                return end;
+            }
+            let mut e = end;
+            while e > 0 && end - e < 3 && (bytes[e] & 0xC0) == 0x80 {
+                e -= 1;
+            }
+            e
+        }
+        TextEncoding::Utf16Le | TextEncoding::Utf16Be => end & !1,
+        TextEncoding::Latin1 => end,
+    }
+}
@@ -0,0 +1,249 @@
+use lazy_static::lazy_static;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::UNIX_EPOCH;
+
+/// Disk limits shared by every thumbnail kind (images, video GIFs, ...).
+const MAX_CACHE_FILES: usize = 20_000;
+const MAX_CACHE_BYTES: u64 = 512 * 1024 * 1024; // 512 MB
+/// Memory tier is weighed by encoded bytes.
+const MEMORY_CACHE_BYTES: u64 = 64 * 1024 * 1024; // 64 MB
+/// zstd level 1 = fast, still good ratio for GIFs.
+const ZSTD_LEVEL: i32 = 1;
+
+/// Freedesktop thumbnail spec size classes, used as subdirectory names.
+const SIZE_CLASSES: &[(u32, &str)] = &[(128, "normal"), (256, "large"), (512, "x-large"), (u32::MAX, "xx-large")];
+
+lazy_static! {
+    /// In-memory LRU for same-session repeat requests (avoids disk I/O).
+    static ref MEMORY_CACHE: moka::sync::Cache<String, Arc<Thumb>> = moka::sync::Cache::builder()
+        .weigher(|_k: &String, v: &Arc<Thumb>| v.bytes.len().try_into().unwrap_or(u32::MAX))
+        .max_capacity(MEMORY_CACHE_BYTES)
+        .build();
+
+    /// Approximate (files, bytes) on disk; `None` until the first scan.
+    static ref DISK_USAGE: Mutex<Option<(usize, u64)>> = Mutex::new(None);
+}
+
+/// Makes temp file names unique when two threads store the same key.
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Thumbnail failure, serialized as `{ kind, message }` so the UI can tell "no preview for
+/// this format" apart from real errors.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+pub enum ThumbnailError {
+    NotFound(String),
+    /// Format can't be decoded here and no fallback renderer is installed.
+    Unsupported(String),
+    Decode(String),
+    Io(String),
+}
+
+impl std::fmt::Display for ThumbnailError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound(m) | Self::Unsupported(m) | Self::Decode(m) | Self::Io(m) => f.write_str(m),
+        }
+    }
+}
+
+/// An encoded thumbnail ready to hand to the frontend.
+#[derive(Debug, Clone)]
+pub struct Thumb {
+    pub bytes: Vec<u8>,
+    pub mime: &'static str,
+}
+
+impl Thumb {
+    pub fn to_data_url(&self) -> String {
+        use base64::{engine::general_purpose, Engine as _};
+        format!("data:{};base64,{}", self.mime, general_purpose::STANDARD.encode(&self.bytes))
+    }
+}
+
+/// Identifies one thumbnail: source path, requested size, and the file's size/mtime so edits
+/// invalidate it. `variant` separates kinds that can share a path (e.g. "image" vs "video").
+#[derive(Debug, Clone)]
+pub struct ThumbKey {
+    hash: String,
+    class: &'static str,
+}
+
+impl ThumbKey {
+    pub fn for_file(path: &Path, width: u32, height: u32, variant: &str) -> Result<Self, String> {
+        let meta = std::fs::metadata(path).map_err(|e| format!("Failed to read file metadata: {}", e))?;
+        let mtime_nanos = meta
+            .modified()
+            .map_err(|e| format!("Failed to get mtime: {}", e))?
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| format!("Invalid mtime: {}", e))?
+            .as_nanos() as u64;
+
+        let mut hasher = Sha256::new();
+        for part in [
+            path.to_string_lossy().as_bytes(),
+            variant.as_bytes(),
+            width.to_string().as_bytes(),
+            height.to_string().as_bytes(),
+            meta.len().to_string().as_bytes(),
+            mtime_nanos.to_string().as_bytes(),
+        ] {
+            hasher.update(part);
+            hasher.update([0]);
+        }
+
+        let edge = width.max(height);
+        let class = SIZE_CLASSES
+            .iter()
+            .find(|(max, _)| edge <= *max)
+            .map(|(_, name)| *name)
+            .unwrap_or("xx-large");
+
+        Ok(Self { hash: hex::encode(hasher.finalize()), class })
+    }
+}
+
+/// Two-tier (memory + disk) thumbnail cache. The disk layout mirrors the freedesktop spec's
+/// size-class folders: `<root>/{normal,large,x-large,xx-large}/<sha256>.<ext>`.
+#[derive(Debug, Clone)]
+pub struct ThumbnailCache {
+    root: PathBuf,
+}
+
+fn ext_for_mime(mime: &str) -> &'static str {
+    match mime {
+        "image/png" => "png",
+        // GIFs are zstd-compressed on disk to save space (fast encode/decode, minimal latency).
+        "image/gif" => "zst",
+        _ => "jpg",
+    }
+}
+
+fn mime_for_ext(ext: &str) -> Option<&'static str> {
+    match ext {
+        "png" => Some("image/png"),
+        "zst" => Some("image/gif"),
+        "jpg" => Some("image/jpeg"),
+        _ => None,
+    }
+}
+
+impl ThumbnailCache {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    fn entry_path(&self, key: &ThumbKey, ext: &str) -> PathBuf {
+        self.root.join(key.class).join(format!("{}.{}", key.hash, ext))
+    }
+
+    pub fn get(&self, key: &ThumbKey) -> Option<Arc<Thumb>> {
+        if let Some(thumb) = MEMORY_CACHE.get(&key.hash) {
+            return Some(thumb);
+        }
+        for ext in ["jpg", "png", "zst"] {
+            let path = self.entry_path(key, ext);
+            let Ok(raw) = std::fs::read(&path) else { continue };
+            // Eviction goes by mtime, so a disk hit marks the entry as recently used. Memory
+            // hits skip this: the entry was touched when it entered this session's memory tier.
+            let _ = filetime::set_file_mtime(&path, filetime::FileTime::now());
+            let bytes = if ext == "zst" {
+                zstd::decode_all(raw.as_slice()).ok()?
+            } else {
+                raw
+            };
+            let thumb = Arc::new(Thumb { bytes, mime: mime_for_ext(ext)? });
+            MEMORY_CACHE.insert(key.hash.clone(), thumb.clone());
+            return Some(thumb);
+        }
+        None
+    }
+
+    /// Stores a thumbnail in both tiers. Disk failures are non-fatal: the caller still has the bytes.
+    pub fn put(&self, key: &ThumbKey, thumb: Thumb) -> Arc<Thumb> {
+        let thumb = Arc::new(thumb);
+        MEMORY_CACHE.insert(key.hash.clone(), thumb.clone());
+
+        let ext = ext_for_mime(thumb.mime);
+        let on_disk = if ext == "zst" {
+            zstd::encode_all(thumb.bytes.as_slice(), ZSTD_LEVEL).unwrap_or_else(|_| thumb.bytes.clone())
+        } else {
+            thumb.bytes.clone()
+        };
+        let path = self.entry_path(key, ext);
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        // Readers never see a half-written file: write a private temp file, then rename it over
+        // the entry. The ".tmp" extension keeps it out of `cache_entries`.
+        let tmp = path.with_extension(format!("{}.{}.tmp", ext, TMP_COUNTER.fetch_add(1, Ordering::Relaxed)));
+        let replaced = std::fs::metadata(&path).ok().map(|m| m.len());
+        if std::fs::write(&tmp, &on_disk).is_ok() && std::fs::rename(&tmp, &path).is_ok() {
+            self.account(replaced, on_disk.len() as u64);
+        } else {
+            let _ = std::fs::remove_file(&tmp);
+        }
+        thumb
+    }
+
+    /// Tracks disk usage incrementally and only walks the cache when a limit is crossed.
+    /// `replaced` is the size of the entry the write overwrote, if any.
+    fn account(&self, replaced: Option<u64>, added: u64) {
+        let mut usage = DISK_USAGE.lock().unwrap();
+        let (files, bytes) = usage.get_or_insert_with(|| scan_usage(&self.root));
+        if replaced.is_none() {
+            *files += 1;
+        }
+        *bytes = bytes.saturating_sub(replaced.unwrap_or(0)) + added;
+        if *files > MAX_CACHE_FILES || *bytes > MAX_CACHE_BYTES {
+            *usage = Some(evict(&self.root));
+        }
+    }
+}
+
+fn cache_entries(root: &Path) -> Vec<(PathBuf, u64, u64)> {
+    SIZE_CLASSES
+        .iter()
+        .filter_map(|(_, class)| std::fs::read_dir(root.join(class)).ok())
+        .flatten()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let path = e.path();
+            mime_for_ext(path.extension()?.to_str()?)?;
+            let meta = e.metadata().ok()?;
+            let mtime = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?.as_nanos() as u64;
+            Some((path, mtime, meta.len()))
+        })
+        .collect()
+}
+
+fn scan_usage(root: &Path) -> (usize, u64) {
+    let entries = cache_entries(root);
+    (entries.len(), entries.iter().map(|(_, _, len)| len).sum())
+}
+
+/// Evict least recently used cache files (by mtime, refreshed on every disk hit) until 10% under both limits, so the walk doesn't rerun
+/// on every insert. Returns the new usage.
+fn evict(root: &Path) -> (usize, u64) {
+    let mut entries = cache_entries(root);
+    let mut count = entries.len();
+    let mut total: u64 = entries.iter().map(|(_, _, len)| len).sum();
+    let target_files = MAX_CACHE_FILES * 9 / 10;
+    let target_bytes = MAX_CACHE_BYTES / 10 * 9;
+
+    entries.sort_by_key(|(_, mtime, _)| *mtime);
+    for (path, _, len) in entries {
+        if count <= target_files && total <= target_bytes {
+            break;
+        }
+        if std::fs::remove_file(&path).is_ok() {
+            count -= 1;
+            total = total.saturating_sub(len);
+        }
+    }
+    (count, total)
+}
@@ -15,12 +15,32 @@ import {
     Eraser,
     CheckSquare,
     Square,
-    FileQuestion
+    FileQuestion,
+    File,
+    Link2Off,
+    Repeat
 } from "lucide-react";
 import { cn } from "@/lib/utils";
 import { open } from "@tauri-apps/plugin-dialog";
 import { useVirtualizer } from "@tanstack/react-virtual";
 import { invoke } from "@tauri-apps/api/core";
+import { CleanReason, CleanScanOptions } from "@/types/explorer";
+
+const SCAN_OPTIONS: { key: keyof CleanScanOptions, label: string }[] = [
+    { key: "empty_folders", label: "Empty folders" },
+    { key: "junk_as_empty", label: "Junk-only folders" },
+    { key: "zero_byte_files", label: "Zero-byte files" },
+    { key: "broken_symlinks", label: "Broken links" },
+    { key: "symlink_loops", label: "Link loops" },
+];
+
+const REASONS: Record<CleanReason, { label: string, icon: typeof File, className: string }> = {
+    empty_folder: { label: "Empty folder", icon: FileQuestion, className: "text-orange-500" },
+    junk_only_folder: { label: "Only junk files", icon: FileQuestion, className: "text-amber-500" },
+    zero_byte_file: { label: "Zero-byte file", icon: File, className: "text-sky-500" },
+    broken_symlink: { label: "Broken link", icon: Link2Off, className: "text-destructive" },
+    symlink_loop: { label: "Link loop", icon: Repeat, className: "text-destructive" },
+};
 
 interface CleanTabProps {
     tabId: string;
@@ -33,6 +53,8 @@ export const CleanTab = ({ tabId: _tabId }: CleanTabProps) => {
     const findings = useCleanStore((state) => state.findings);
     const selectedPaths = useCleanStore((state) => state.selectedPaths);
     const scanQueue = useCleanStore((state) => state.scanQueue);
+    const options = useCleanStore((state) => state.options);
+    const setScanOption = useCleanStore((state) => state.setScanOption);
 
     const startScan = useCleanStore((state) => state.startScan);
     const resetScan = useCleanStore((state) => state.resetScan);
@@ -145,6 +167,24 @@ export const CleanTab = ({ tabId: _tabId }: CleanTabProps) => {
                             Add Folder
                         </Button>
                     </div>
+
+                    <div className="flex flex-wrap items-center gap-1.5">
+                        {SCAN_OPTIONS.map(({ key, label }) => (
+                            <button
+                                key={key}
+                                disabled={scanning}
+                                onClick={() => setScanOption(key, !options[key])}
+                                className={cn(
+                                    "px-2.5 py-1 rounded-md border text-[11px] font-medium transition-colors",
+                                    options[key]
+                                        ? "bg-primary text-primary-foreground border-primary"
+                                        : "bg-background text-muted-foreground hover:text-foreground hover:border-primary/40"
+                                )}
+                            >
+                                {label}
+                            </button>
+                        ))}
+                    </div>
                 </div>
 
                 {scanning && progress && (
@@ -170,7 +210,7 @@ export const CleanTab = ({ tabId: _tabId }: CleanTabProps) => {
                                     {formatDuration(progress.elapsed_ms)}
                                 </span>
                             </div>
-                            <span>{findings.length} empty folders found</span>
+                            <span>{findings.length} items found</span>
                         </div>
                     </div>
                 )}
@@ -199,7 +239,7 @@ export const CleanTab = ({ tabId: _tabId }: CleanTabProps) => {
                                 </Button>
                                 <div className="h-6 w-[1px] bg-border mx-1" />
                                 <span className="text-xs font-bold text-muted-foreground">
-                                    {selectedPaths.size} folders selected
+                                    {selectedPaths.size} items selected
                                 </span>
                             </div>
                             <Button
@@ -210,7 +250,7 @@ export const CleanTab = ({ tabId: _tabId }: CleanTabProps) => {
                                 onClick={deleteSelected}
                             >
                                 <Trash2 className="w-3.5 h-3.5 mr-1" />
-                                Delete Empty Folders
+                                Delete Selected
                             </Button>
                         </div>
 
@@ -222,6 +262,7 @@ export const CleanTab = ({ tabId: _tabId }: CleanTabProps) => {
                                 {virtualizer.getVirtualItems().map((virtualRow) => {
                                     const folder = findings[virtualRow.index];
                                     if (!folder) return null;
+                                    const reason = REASONS[folder.reason] ?? REASONS.empty_folder;
 
                                     return (
                                         <div
@@ -247,10 +288,13 @@ export const CleanTab = ({ tabId: _tabId }: CleanTabProps) => {
                                                     onClick={(e: React.MouseEvent) => e.stopPropagation()}
                                                 />
                                                 <div className="p-1.5 bg-background rounded-md border shadow-sm group-hover:scale-110 transition-transform">
-                                                    <FileQuestion className="w-3.5 h-3.5 text-orange-500" />
+                                                    <reason.icon className={cn("w-3.5 h-3.5", reason.className)} />
                                                 </div>
                                                 <div className="flex-1 min-w-0">
-                                                    <div className="text-[11px] font-bold truncate group-hover:text-primary transition-colors">{folder.name}</div>
+                                                    <div className="text-[11px] font-bold truncate group-hover:text-primary transition-colors">
+                                                        {folder.name}
+                                                        <span className="ml-2 text-[9px] font-medium text-muted-foreground">{reason.label}</span>
+                                                    </div>
                                                     <div className="text-[9px] text-muted-foreground truncate opacity-60 font-mono tracking-tighter mt-0.5">{folder.path}</div>
                                                 </div>
                                                 <Button
@@ -163,7 +163,7 @@ export const FileContextMenu = ({ children, entry, tabId }: FileContextMenuProps
             toast.success("Extraction complete");
             setIsDeleteDialogOpen(true);
         } catch (error) {
-            toast.error(`Extraction failed: ${error}`);
+            toast.error(`Extraction failed: ${(error as { message?: string })?.message ?? error}`);
         }
     };
 
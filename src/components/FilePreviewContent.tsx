@@ -2,6 +2,7 @@ import { Music, FileQuestion, Loader2, FileText, Folder } from "lucide-react";
 import { useState, useEffect, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { cn } from "@/lib/utils";
+import { fileUrl } from "@/lib/mediaUrl";
 import { isVideoExtension, isImageExtension, isAudioExtension, isTextExtension, isDocumentExtension } from "@/lib/fileTypes";
 import { useSettingsStore } from "@/stores/settingsStore";
 import { usePreviewStore } from "@/stores/previewStore";
@@ -166,17 +167,8 @@ export const FilePreviewContent = ({ path, extension, name, is_dir, className, s
                     setContent(null);
                 })
                 .finally(() => setLoading(false));
-        } else if (isDocument && ext === "pdf") {
-            invoke<string>("get_file_base64_content", { path })
-                .then((c) => { setContent(c); setPreviewError(null); })
-                .catch((err: any) => {
-                    const msg = typeof err === "string" ? err : err?.message ?? "Failed to load file";
-                    setPreviewError(msg);
-                    setContent(null);
-                })
-                .finally(() => setLoading(false));
-        } else if (isImage || isVideo || isAudio) {
-            setContent(`vmedia://localhost/${encodeURIComponent(path)}`);
+        } else if ((isDocument && ext === "pdf") || isImage || isVideo || isAudio) {
+            setContent(fileUrl(path));
             setLoading(false);
         } else {
             setLoading(false);
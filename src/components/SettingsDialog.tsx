@@ -21,13 +21,19 @@ import {
     BookOpen, 
     Archive,
     Search,
-    Eraser
+    Eraser,
+    Tag,
+    Fingerprint,
+    HardDrive,
+    Globe
 } from "lucide-react";
 import { cn } from "@/lib/utils";
 import { useSettingsStore } from "@/stores/settingsStore";
 import { Input } from "@/components/ui/input";
 import { ScrollArea } from "@/components/ui/scroll-area";
-import { ConfigSection } from "@/types/explorer";
+import { CategoryDefinition, ConfigSection, ExclusionSettings } from "@/types/explorer";
+
+const BUILTIN_CATEGORY_IDS = ["image", "video", "audio", "document", "archive"];
 
 interface SettingsDialogProps {
     open: boolean;
@@ -43,7 +49,7 @@ const SETTINGS_TAB_LABELS: Record<"explorer" | "dedupe" | "search" | "clean" | "
 };
 
 export function SettingsDialog({ open, onOpenChange }: SettingsDialogProps) {
-    const { settings, updateSettings, updatePreviewSettings, addBlockedExtension, removeBlockedExtension, addBlockedName, removeBlockedName, updateTheme } = useSettingsStore();
+    const { settings, updateSettings, updatePreviewSettings, addBlockedExtension, removeBlockedExtension, addBlockedName, removeBlockedName, updateTheme, addCategory, removeCategory } = useSettingsStore();
     const [activeTab, setActiveTab] = useState<"explorer" | "dedupe" | "search" | "clean" | "theme">("explorer");
 
     if (!open) return null;
@@ -160,6 +166,9 @@ export function SettingsDialog({ open, onOpenChange }: SettingsDialogProps) {
                                     removeBlockedExtension={removeBlockedExtension}
                                     addBlockedName={addBlockedName}
                                     removeBlockedName={removeBlockedName}
+                                    customCategories={(settings.categories ?? []).filter(c => !BUILTIN_CATEGORY_IDS.includes(c.id))}
+                                    addCategory={addCategory}
+                                    removeCategory={removeCategory}
                                 />
                             )}
                         </div>
@@ -254,7 +263,10 @@ function ConfigSectionView({
     addBlockedExtension,
     removeBlockedExtension,
     addBlockedName,
-    removeBlockedName
+    removeBlockedName,
+    customCategories,
+    addCategory,
+    removeCategory
 }: {
     section: "explorer" | "dedupe" | "content_search" | "clean",
     settings: ConfigSection,
@@ -263,10 +275,15 @@ function ConfigSectionView({
     addBlockedExtension: any,
     removeBlockedExtension: any,
     addBlockedName: any,
-    removeBlockedName: any
+    removeBlockedName: any,
+    customCategories: CategoryDefinition[],
+    addCategory: (name: string, extensions: string[]) => Promise<void>,
+    removeCategory: (id: string) => Promise<void>
 }) {
     const [newExt, setNewExt] = useState("");
     const [newName, setNewName] = useState("");
+    const [newCategoryName, setNewCategoryName] = useState("");
+    const [newCategoryExts, setNewCategoryExts] = useState("");
 
     return (
         <div className="space-y-10">
@@ -371,6 +388,174 @@ function ConfigSectionView({
                         active={settings.preview_enabled.archive}
                         onClick={() => updatePreviewSettings(section, { archive: !settings.preview_enabled.archive })}
                     />
+                    {customCategories.map((category) => {
+                        const active = settings.preview_enabled[category.id] !== false;
+                        return (
+                            <PreviewToggle
+                                key={category.id}
+                                label={category.name}
+                                icon={<Tag className="w-4 h-4" />}
+                                active={active}
+                                onClick={() => updatePreviewSettings(section, { [category.id]: !active })}
+                            />
+                        );
+                    })}
+                </div>
+                <div className="p-4 rounded-xl bg-muted/20 border border-border/40">
+                    <div className="flex items-center justify-between mb-3">
+                        <div className="space-y-0.5">
+                            <span className="text-[13px] font-medium">Custom Categories</span>
+                            <p className="text-[10px] text-muted-foreground">Shared by every section; user categories take extensions over from the built-ins</p>
+                        </div>
+                        <div className="flex gap-1.5 focus-within:ring-2 focus-within:ring-primary/20 rounded-lg transition-all">
+                            <Input
+                                value={newCategoryName}
+                                onChange={(e) => setNewCategoryName(e.target.value)}
+                                placeholder="e.g. Code"
+                                className="h-8 w-24 border-border/50 bg-background/50 text-[11px]"
+                            />
+                            <Input
+                                value={newCategoryExts}
+                                onChange={(e) => setNewCategoryExts(e.target.value)}
+                                placeholder="rs, ts, py"
+                                className="h-8 w-28 border-border/50 bg-background/50 text-[11px]"
+                            />
+                            <button
+                                onClick={() => {
+                                    const extensions = newCategoryExts
+                                        .split(/[\s,]+/)
+                                        .map(ext => ext.toLowerCase().replace(/^\./, ""))
+                                        .filter(Boolean);
+                                    if (newCategoryName.trim() && extensions.length > 0) {
+                                        addCategory(newCategoryName, extensions);
+                                        setNewCategoryName("");
+                                        setNewCategoryExts("");
+                                    }
+                                }}
+                                className="h-8 w-8 flex items-center justify-center rounded-lg bg-primary text-primary-foreground hover:opacity-90 transition-opacity"
+                            >
+                                <Plus className="w-4 h-4" />
+                            </button>
+                        </div>
+                    </div>
+                    <div className="flex flex-wrap gap-1.5 min-h-[1.5rem]">
+                        {customCategories.length === 0 && (
+                            <p className="text-[10px] text-muted-foreground/50 italic py-1">No custom categories</p>
+                        )}
+                        {customCategories.map((category) => (
+                            <div
+                                key={category.id}
+                                className="flex items-center gap-1.5 pl-2 pr-1 py-1 rounded-md bg-muted/50 border border-border/50 text-[10px] group"
+                            >
+                                <span className="font-medium">{category.name}</span>
+                                <span className="text-muted-foreground">{category.extensions.join(", ")}</span>
+                                <button
+                                    onClick={() => removeCategory(category.id)}
+                                    className="p-0.5 rounded-sm hover:bg-destructive hover:text-destructive-foreground opacity-50 group-hover:opacity-100 transition-all"
+                                >
+                                    <X className="w-2.5 h-2.5" />
+                                </button>
+                            </div>
+                        ))}
+                    </div>
+                </div>
+            </section>
+
+            <section className="space-y-6">
+                <div>
+                    <h3 className="text-sm font-semibold mb-1 flex items-center gap-2 text-primary">
+                        <Fingerprint className="w-4 h-4" /> Content Detection
+                    </h3>
+                    <p className="text-[11px] text-muted-foreground italic">
+                        Reads the first few KB of each file, so files with missing or wrong extensions are filed by what they contain. Slower on large folders.
+                    </p>
+                </div>
+                <button
+                    type="button"
+                    onClick={() => updateSettings(section, { detect_content_type: !(settings.detect_content_type ?? false) })}
+                    className={cn(
+                        "flex items-center justify-between w-full p-4 rounded-xl border transition-all duration-200 group text-left",
+                        settings.detect_content_type
+                            ? "bg-primary/5 border-primary/30 ring-1 ring-primary/10 shadow-sm"
+                            : "bg-muted/30 border-border/50 hover:bg-muted/50"
+                    )}
+                >
+                    <div className="flex items-center gap-4">
+                        <div
+                            className={cn(
+                                "p-2.5 rounded-lg border transition-colors",
+                                settings.detect_content_type
+                                    ? "bg-primary text-primary-foreground border-primary"
+                                    : "bg-muted text-muted-foreground border-border"
+                            )}
+                        >
+                            <Fingerprint className="w-4 h-4" />
+                        </div>
+                        <div className="space-y-0.5 text-left">
+                            <span className="text-[13px] font-semibold">Detect file types from content</span>
+                            <p className="text-[11px] text-muted-foreground">Flag files whose extension doesn't match their content</p>
+                        </div>
+                    </div>
+                </button>
+            </section>
+
+            <section className="space-y-6">
+                <div>
+                    <h3 className="text-sm font-semibold mb-1 flex items-center gap-2 text-primary">
+                        <HardDrive className="w-4 h-4" /> Mounted Drives
+                    </h3>
+                    <p className="text-[11px] text-muted-foreground italic">
+                        Controls whether scans continue into other drives mounted inside the folders they walk. The folder a scan starts from is always included.
+                    </p>
+                </div>
+                <div className="space-y-3">
+                    {([
+                        {
+                            key: "same_filesystem",
+                            icon: HardDrive,
+                            label: "Stay on the same drive",
+                            description: "Don't descend into any other mounted filesystem",
+                            enabled: settings.same_filesystem ?? false,
+                            toggle: () => updateSettings(section, { same_filesystem: !(settings.same_filesystem ?? false) }),
+                        },
+                        {
+                            key: "skip_network_mounts",
+                            icon: Globe,
+                            label: "Skip network drives",
+                            description: "Leave out NFS, SMB, SSHFS and other remote shares",
+                            enabled: settings.skip_network_mounts ?? true,
+                            toggle: () => updateSettings(section, { skip_network_mounts: !(settings.skip_network_mounts ?? true) }),
+                        },
+                    ]).map(({ key, icon: Icon, label, description, enabled, toggle }) => (
+                        <button
+                            key={key}
+                            type="button"
+                            onClick={toggle}
+                            className={cn(
+                                "flex items-center justify-between w-full p-4 rounded-xl border transition-all duration-200 group text-left",
+                                enabled
+                                    ? "bg-primary/5 border-primary/30 ring-1 ring-primary/10 shadow-sm"
+                                    : "bg-muted/30 border-border/50 hover:bg-muted/50"
+                            )}
+                        >
+                            <div className="flex items-center gap-4">
+                                <div
+                                    className={cn(
+                                        "p-2.5 rounded-lg border transition-colors",
+                                        enabled
+                                            ? "bg-primary text-primary-foreground border-primary"
+                                            : "bg-muted text-muted-foreground border-border"
+                                    )}
+                                >
+                                    <Icon className="w-4 h-4" />
+                                </div>
+                                <div className="space-y-0.5 text-left">
+                                    <span className="text-[13px] font-semibold">{label}</span>
+                                    <p className="text-[11px] text-muted-foreground">{description}</p>
+                                </div>
+                            </div>
+                        </button>
+                    ))}
                 </div>
             </section>
 
@@ -462,12 +647,66 @@ function ConfigSectionView({
                         onRemove={(name) => removeBlockedName(section, name)}
                         placeholder="e.g. node_modules, .git"
                     />
+                    <PathExclusions />
                 </div>
             </section>
         </div>
     );
 }
 
+/** Path and glob rules from `exclusions`; shared by every section, unlike the block lists. */
+function PathExclusions() {
+    const { settings, addExclusion, removeExclusion } = useSettingsStore();
+    const [drafts, setDrafts] = useState<Record<keyof ExclusionSettings, string>>({
+        protected_paths: "",
+        excluded_paths: "",
+        excluded_globs: "",
+    });
+    const lists: { key: keyof ExclusionSettings, title: string, description: string, placeholder: string }[] = [
+        {
+            key: "excluded_paths",
+            title: "Excluded Folders",
+            description: "Every section skips these folders and everything in them",
+            placeholder: "/absolute/path",
+        },
+        {
+            key: "excluded_globs",
+            title: "Excluded Patterns",
+            description: "Globs skipped everywhere; without a / they match the name only",
+            placeholder: "e.g. *.bak, **/cache/**",
+        },
+        {
+            key: "protected_paths",
+            title: "Protected Folders",
+            description: "Never deleted, moved or renamed, nor any folder containing them",
+            placeholder: "/absolute/path",
+        },
+    ];
+
+    return (
+        <>
+            {lists.map(({ key, title, description, placeholder }) => (
+                <BlockedList
+                    key={key}
+                    title={title}
+                    description={description}
+                    items={settings.exclusions?.[key] ?? []}
+                    newItem={drafts[key]}
+                    setNewItem={(value) => setDrafts(d => ({ ...d, [key]: value }))}
+                    onAdd={() => {
+                        if (drafts[key].trim()) {
+                            addExclusion(key, drafts[key]);
+                            setDrafts(d => ({ ...d, [key]: "" }));
+                        }
+                    }}
+                    onRemove={(item) => removeExclusion(key, item)}
+                    placeholder={placeholder}
+                />
+            ))}
+        </>
+    );
+}
+
 function PreviewToggle({ label, icon, active, onClick }: { label: string, icon: any, active: boolean, onClick: () => void }) {
     return (
         <button
@@ -12,8 +12,9 @@ import { useExplorerStore } from "@/stores/explorerStore";
 import { useSettingsStore } from "@/stores/settingsStore";
 import { useSidebarStore } from "@/stores/sidebarStore";
 import { useCleanStore } from "@/stores/cleanStore";
-import { TreeNode } from "@/types/explorer";
+import { TreeNode, Volume } from "@/types/explorer";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import {
     ChevronRight,
     CopyCheck,
@@ -29,7 +30,8 @@ import {
     Star,
     Usb,
     Eraser,
-    Pencil
+    Pencil,
+    ArrowUpFromLine
 } from "lucide-react";
 import React, { useEffect, useRef, useState } from "react";
 import { toast } from "sonner";
@@ -38,7 +40,7 @@ import { motion } from "framer-motion";
 import { homeDir } from "@tauri-apps/api/path";
 
 export const Sidebar = () => {
-    const { volumes, favorites, isCollapsed, refreshVolumes } = useSidebarStore();
+    const { volumes, favorites, isCollapsed, refreshVolumes, ejectVolume } = useSidebarStore();
     const { settings } = useSettingsStore();
     const [homePath, setHomePath] = useState<string>("");
 
@@ -47,6 +49,24 @@ export const Sidebar = () => {
         homeDir().then(setHomePath);
     }, [refreshVolumes, settings.explorer.show_system_files]);
 
+    // Mount events come from the backend's mount table watcher (Linux)
+    useEffect(() => {
+        const unlisteners = [
+            listen<Volume>("volume-mounted", () => refreshVolumes()),
+            listen<Volume>("volume-unmounted", () => refreshVolumes()),
+        ];
+        return () => unlisteners.forEach(p => p.then(unlisten => unlisten()));
+    }, [refreshVolumes]);
+
+    const handleEject = async (vol: Volume) => {
+        try {
+            await ejectVolume(vol.mount_point);
+            toast.success(`${vol.label ?? vol.name} can be removed safely`);
+        } catch (error) {
+            toast.error(`Eject failed: ${error}`);
+        }
+    };
+
     // Filter volumes
     const externalDrives = volumes.filter(v => v.is_removable && v.mount_point !== "/");
 
@@ -124,6 +144,7 @@ export const Sidebar = () => {
                                         depth={0}
                                         icon={<HardDrive className="w-4 h-4 text-orange-400" />}
                                         subLabel={formatSpace(vol.available_space)}
+                                        onEject={() => handleEject(vol)}
                                     />
                                 ))}
                             </div>
@@ -160,9 +181,10 @@ interface TreeItemProps {
     depth: number;
     icon?: React.ReactNode;
     subLabel?: string;
+    onEject?: () => void;
 }
 
-const TreeItem = ({ node, depth, icon, subLabel }: TreeItemProps) => {
+const TreeItem = ({ node, depth, icon, subLabel, onEject }: TreeItemProps) => {
     const { expandedPaths, toggleExpand, treeNodes, handlePathClick, refreshVolumes } = useSidebarStore();
     const isExpanded = expandedPaths.has(node.path);
     const children = treeNodes[node.path] || [];
@@ -287,6 +309,16 @@ const TreeItem = ({ node, depth, icon, subLabel }: TreeItemProps) => {
                             <span className="text-[12px] font-medium truncate tracking-tight">{node.name}</span>
                             {subLabel && <span className="text-[9px] text-muted-foreground leading-none">{subLabel} free</span>}
                         </div>
+
+                        {onEject && (
+                            <button
+                                className="p-0.5 rounded-sm opacity-0 group-hover:opacity-100 hover:bg-muted-foreground/10 transition-opacity"
+                                onClick={(e) => { e.stopPropagation(); onEject(); }}
+                                title="Eject"
+                            >
+                                <ArrowUpFromLine className="w-3 h-3 text-muted-foreground" />
+                            </button>
+                        )}
                     </div>
 
                     {isExpanded && node.has_children && (
@@ -1,4 +1,7 @@
 import { useEffect, useState } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { invoke } from "@tauri-apps/api/core";
+import type { AppSettings, SettingsReport } from "@/types/explorer";
 import { FilePanel } from "@/panels/FilePanel";
 import { useExplorerStore } from "@/stores/explorerStore";
 import { SearchResultsPanel } from "@/components/SearchResultsPanel";
@@ -26,7 +29,7 @@ import { useFavouritesStore } from "@/stores/favouritesStore";
 import { FavouritesManagerModal } from "@/components/FavouritesManagerModal";
 import { ThemeApplier } from "@/components/ThemeApplier";
 import { CleanTab } from "@/components/CleanTab";
-import { useSettingsStore } from "@/stores/settingsStore";
+import { notifySettingsReport, useSettingsStore } from "@/stores/settingsStore";
 import { ActivityBar } from "@/components/ActivityBar";
 import { PanelLeft, PanelRight } from "lucide-react";
 
@@ -55,12 +58,34 @@ export const MainLayout = () => {
     const favouritesCount = useFavouritesStore((s) => s.items.length);
 
 
-    const { loadSettings } = useSettingsStore();
+    const { loadSettings, applyExternalSettings } = useSettingsStore();
 
     useEffect(() => {
-        loadSettings();
+        // The startup load's report is fetched once it's done; later ones (saves, external
+        // edits) arrive as events.
+        let loaded = false;
+        const unlisten = listen<SettingsReport>("settings-validation", (event) => {
+            if (loaded) notifySettingsReport(event.payload);
+        });
+        loadSettings().then(async () => {
+            loaded = true;
+            const report = await invoke<SettingsReport | null>("get_settings_report");
+            if (report) notifySettingsReport(report);
+        });
+        return () => {
+            unlisten.then((fn) => fn());
+        };
     }, [loadSettings]);
 
+    useEffect(() => {
+        const unlisten = listen<AppSettings>("settings-changed", (event) => {
+            applyExternalSettings(event.payload);
+        });
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, [applyExternalSettings]);
+
     useEffect(() => {
         const handleKeyDown = (e: KeyboardEvent) => {
             if (!(e.metaKey || e.ctrlKey)) return;
@@ -0,0 +1,11 @@
+import { convertFileSrc } from "@tauri-apps/api/core";
+
+// `sdm://localhost/` on macOS/Linux, `http://sdm.localhost/` on Windows.
+const SDM_BASE = convertFileSrc("", "sdm");
+
+/** Raw file bytes over the `sdm://` scheme; `<video>`/`<audio>` stream them with Range requests. */
+export const fileUrl = (path: string) => `${SDM_BASE}file/${encodeURIComponent(path)}`;
+
+/** Cached thumbnail for an image, video, audio or document file. */
+export const thumbnailUrl = (path: string, width: number, height: number) =>
+    `${SDM_BASE}thumb/${encodeURIComponent(path)}?w=${width}&h=${height}`;
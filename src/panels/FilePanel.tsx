@@ -22,6 +22,7 @@ import { cn } from "@/lib/utils";
 import {
     LayoutList,
     LayoutGrid,
+    Pin,
     ArrowLeft,
     ArrowRight,
     ArrowUp,
@@ -51,6 +52,7 @@ export const FilePanel = ({ tabId }: FilePanelProps) => {
 
     const setActiveTab = useExplorerStore((state) => state.setActiveTab);
     const setViewMode = useExplorerStore((state) => state.setViewMode);
+    const toggleFolderOverride = useExplorerStore((state) => state.toggleFolderOverride);
     const goBack = useExplorerStore((state) => state.goBack);
     const goForward = useExplorerStore((state) => state.goForward);
     const goParent = useExplorerStore((state) => state.goParent);
@@ -376,6 +378,15 @@ export const FilePanel = ({ tabId }: FilePanelProps) => {
                         <Trash2 className="w-3 h-3" />
                     </Button>
                     <div className="w-px h-4 bg-border mx-0.5 shrink-0" />
+                    <Button variant="ghost" size="icon" className="h-6 w-6 rounded-lg"
+                        onClick={(e) => { e.stopPropagation(); toggleFolderOverride(tab.id); }}
+                        title={tab.folderView?.has_own_override
+                            ? "Forget this folder's view"
+                            : tab.folderView
+                                ? `View inherited from ${tab.folderView.override_source}; click to pin it here`
+                                : "Remember this view for this folder and its subfolders"}>
+                        <Pin className={cn("w-3 h-3", tab.folderView?.has_own_override ? "text-primary" : tab.folderView ? "text-primary/60" : "opacity-40")} />
+                    </Button>
                     <Button variant="ghost" size="icon" className="h-6 w-6 rounded-lg"
                         onClick={(e) => { e.stopPropagation(); setViewMode(tab.id, "list"); }} title="List view">
                         <LayoutList className={cn("w-3 h-3", tab.viewMode === "list" ? "text-primary" : "opacity-40")} />
@@ -2,7 +2,7 @@ import React from "react";
 import { FileEntry } from "@/types/explorer";
 import {
     File, Folder, ImageIcon, Video, Music, FileText, FileSearch,
-    Archive, Check,
+    Archive, Check, AlertTriangle,
 } from "lucide-react";
 import { cn } from "@/lib/utils";
 import { useExplorerStore } from "@/stores/explorerStore";
@@ -162,6 +162,11 @@ export const FileRow = React.memo(({ entry, selected, isActive: _isActive, onCli
                     {entry.name}
                 </span>
                 {!entry.is_dir && <ExtBadge ext={ext} />}
+                {entry.detected_type?.expected_extension && (
+                    <span title={`Content is ${entry.detected_type.content_name}; expected .${entry.detected_type.expected_extension}`}>
+                        <AlertTriangle className="w-3 h-3 shrink-0 text-amber-500" />
+                    </span>
+                )}
             </div>
 
             {/* Size */}
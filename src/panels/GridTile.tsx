@@ -6,6 +6,7 @@ import { useSettingsStore } from "@/stores/settingsStore";
 import { createDragGhost } from "@/lib/dragUtils";
 import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { thumbnailUrl } from "@/lib/mediaUrl";
 import { isVideoExtension, isAudioExtension, isArchiveExtension } from "@/lib/fileTypes";
 
 interface GridTileProps {
@@ -69,14 +70,8 @@ export const GridTile = ({ entry, selected, isActive, onClick, style, onToggleSe
     const isVideo = !entry.is_dir && isVideoExtension(entry.extension);
 
     useEffect(() => {
-        if (isImage) {
-            invoke<string>("get_thumbnail", { path: entry.path, width: gridWidth, height: gridHeight })
-                .then(setThumbnail).catch(() => { });
-        } else if (isVideo) {
-            invoke<string>("get_video_thumbnail", { path: entry.path, width: gridWidth, height: gridHeight })
-                .then(setThumbnail).catch(() => { });
-        }
-    }, [entry.path, entry.extension, entry.is_dir, isImage, isVideo, gridWidth, gridHeight]);
+        setThumbnail(isImage || isVideo ? thumbnailUrl(entry.path, gridWidth, gridHeight) : null);
+    }, [entry.path, isImage, isVideo, gridWidth, gridHeight]);
 
     return (
         <div
@@ -130,7 +125,7 @@ export const GridTile = ({ entry, selected, isActive, onClick, style, onToggleSe
                 style={{ width: gridWidth, height: gridHeight, minWidth: gridWidth, minHeight: gridHeight }}
             >
                 {thumbnail ? (
-                    <img src={thumbnail} className="w-full h-full object-cover" alt="" />
+                    <img src={thumbnail} className="w-full h-full object-cover" alt="" onError={() => setThumbnail(null)} />
                 ) : (
                     getIconFallback(entry)
                 )}
@@ -2,7 +2,15 @@ import { create } from "zustand";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { toast } from "sonner";
-import { EmptyFolder } from "@/types/explorer";
+import { CleanFinding, CleanScanOptions, DeleteOutcome, DeleteStatus } from "@/types/explorer";
+
+const STATUS_LABELS: Record<Exclude<DeleteStatus, "deleted">, string> = {
+    not_empty: "changed since the scan",
+    unsupported: "not deletable",
+    protected: "protected",
+    missing: "already gone",
+    failed: "failed",
+};
 
 export interface CleanProgressEvent {
     scanned_folders: number;
@@ -15,13 +23,17 @@ export interface CleanProgressEvent {
 interface CleanStore {
     scanning: boolean;
     progress: CleanProgressEvent | null;
-    findings: EmptyFolder[];
+    findings: CleanFinding[];
     selectedPaths: Set<string>;
     scanQueue: string[];
+    /** Folders the current findings came from; bounds parent cleanup on delete. */
+    scanRoots: string[];
+    options: CleanScanOptions;
 
     // Actions
     addToQueue: (path: string) => void;
     removeFromQueue: (path: string) => void;
+    setScanOption: (option: keyof CleanScanOptions, value: boolean) => void;
     startScan: () => Promise<void>;
     resetScan: () => void;
     toggleSelection: (path: string) => void;
@@ -36,6 +48,14 @@ export const useCleanStore = create<CleanStore>((set, get) => ({
     findings: [],
     selectedPaths: new Set(),
     scanQueue: [],
+    scanRoots: [],
+    options: {
+        empty_folders: true,
+        zero_byte_files: false,
+        broken_symlinks: false,
+        symlink_loops: false,
+        junk_as_empty: false,
+    },
 
     addToQueue: (path) => set(state => ({
         scanQueue: state.scanQueue.includes(path) ? state.scanQueue : [...state.scanQueue, path]
@@ -45,8 +65,12 @@ export const useCleanStore = create<CleanStore>((set, get) => ({
         scanQueue: state.scanQueue.filter(p => p !== path)
     })),
 
+    setScanOption: (option, value) => set(state => ({
+        options: { ...state.options, [option]: value }
+    })),
+
     startScan: async () => {
-        const { scanQueue } = get();
+        const { scanQueue, options } = get();
         if (scanQueue.length === 0) {
             toast.error("Add at least one folder to scan");
             return;
@@ -57,6 +81,7 @@ export const useCleanStore = create<CleanStore>((set, get) => ({
             findings: [],
             progress: null,
             selectedPaths: new Set(),
+            scanRoots: [...scanQueue],
         });
 
         const unlistenProgress = await listen<CleanProgressEvent>("clean-progress", (event) => {
@@ -65,9 +90,10 @@ export const useCleanStore = create<CleanStore>((set, get) => ({
 
         try {
             const settings = (await import("./settingsStore")).useSettingsStore.getState().settings;
-            const results = await invoke<EmptyFolder[]>("find_empty_folders", {
+            const results = await invoke<CleanFinding[]>("find_empty_folders", {
                 paths: scanQueue,
-                settings: settings.clean
+                settings: settings.clean,
+                options,
             });
             set({ findings: results, scanning: false });
         } catch (error) {
@@ -105,18 +131,45 @@ export const useCleanStore = create<CleanStore>((set, get) => ({
     },
 
     deleteSelected: async () => {
-        const { selectedPaths } = get();
+        const { selectedPaths, options, scanRoots } = get();
         if (selectedPaths.size === 0) return;
 
-        const confirm = await window.confirm(`Are you sure you want to delete ${selectedPaths.size} empty folders? This will also recursively clean any parents that become empty.`);
+        const confirm = await window.confirm(`Are you sure you want to delete ${selectedPaths.size} selected items? This will also recursively clean any parents that become empty.`);
         if (!confirm) return;
 
         try {
-            await invoke("delete_empty_folders", { paths: Array.from(selectedPaths) });
-            toast.success(`${selectedPaths.size} empty folders deleted`);
-            // Refresh scan after deletion to show new state (or just clear findings)
-            set({ findings: [], selectedPaths: new Set() });
-            // Ideally we'd re-trigger startScan() but we'll let the user decide.
+            const outcomes = await invoke<DeleteOutcome[]>("delete_empty_folders", {
+                paths: Array.from(selectedPaths),
+                options: { roots: scanRoots, junk_as_empty: options.junk_as_empty },
+            });
+
+            const counts = new Map<DeleteStatus, number>();
+            outcomes.forEach(o => counts.set(o.status, (counts.get(o.status) ?? 0) + 1));
+            const deleted = counts.get("deleted") ?? 0;
+            const parents = outcomes.reduce((n, o) => n + o.removed_parents.length, 0);
+            const skipped = (Object.keys(STATUS_LABELS) as (keyof typeof STATUS_LABELS)[])
+                .filter(status => counts.has(status))
+                .map(status => `${counts.get(status)} ${STATUS_LABELS[status]}`);
+
+            const summary = `${deleted} item${deleted === 1 ? "" : "s"} deleted` +
+                (parents > 0 ? `, plus ${parents} emptied parent folder${parents === 1 ? "" : "s"}` : "");
+            if (skipped.length === 0) {
+                toast.success(summary);
+            } else {
+                const failure = outcomes.find(o => o.status === "failed")?.error;
+                toast.warning(summary, {
+                    description: `Skipped: ${skipped.join(", ")}${failure ? ` — ${failure}` : ""}`,
+                });
+            }
+
+            // Gone paths (deleted, already missing or removed as a parent) leave the list
+            const gone = new Set(outcomes
+                .filter(o => o.status === "deleted" || o.status === "missing")
+                .flatMap(o => [o.path, ...o.removed_parents]));
+            set(state => ({
+                findings: state.findings.filter(f => !gone.has(f.path)),
+                selectedPaths: new Set([...state.selectedPaths].filter(p => !gone.has(p))),
+            }));
         } catch (error) {
             toast.error(`Delete failed: ${error}`);
         }
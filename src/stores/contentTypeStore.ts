@@ -2,10 +2,13 @@ import { create } from "zustand";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { useSettingsStore } from "./settingsStore";
+import { TypeDetection } from "@/types/explorer";
 
 export interface ContentGroup {
     category: String;
     paths: string[];
+    /** Types of each entry in `paths`, when content detection is on. */
+    detected_types?: TypeDetection[];
 }
 
 export interface ProgressEvent {
@@ -1,6 +1,6 @@
 import { create } from "zustand";
 import { invoke } from "@tauri-apps/api/core";
-import { DirectoryResponse, SortField, SortOrder, Tab } from "@/types/explorer";
+import { DirectoryResponse, FolderOverride, ResolvedFolderOverride, SortField, SortOrder, Tab, ViewMode } from "@/types/explorer";
 
 interface ExplorerStore {
     tabs: Tab[];
@@ -22,7 +22,9 @@ interface ExplorerStore {
     clearSelection: (tabId: string) => void;
     setSort: (tabId: string, sortBy: SortField, order: SortOrder) => Promise<void>;
     refresh: (tabId: string) => Promise<void>;
-    setViewMode: (tabId: string, mode: "list" | "grid") => void;
+    setViewMode: (tabId: string, mode: ViewMode) => Promise<void>;
+    /** Saves the tab's sort and view mode as an override for its folder and subfolders, or removes it. */
+    toggleFolderOverride: (tabId: string) => Promise<void>;
     goBack: (tabId: string) => void;
     goForward: (tabId: string) => void;
     goParent: (tabId: string) => void;
@@ -46,11 +48,26 @@ const initialPanelState: Omit<Tab, "id" | "title" | "type"> = {
     selection: new Set(),
     isExpanded: true, // Tabs are always expanded
     viewMode: "list",
+    folderView: null,
     history: [],
     currentIndex: -1,
     lastSelectedPath: null,
 };
 
+/** Merges `patch` into the override for the tab's folder, when one applies there. */
+async function updateFolderOverride(tab: Tab | undefined, patch: FolderOverride) {
+    if (!tab?.folderView) return;
+    try {
+        const resolved: ResolvedFolderOverride = await invoke("get_folder_override", { path: tab.path });
+        await invoke("set_folder_override", {
+            path: tab.path,
+            folderOverride: { ...resolved.own, ...patch },
+        });
+    } catch (error) {
+        console.error("Failed to update folder override:", error);
+    }
+}
+
 export const useExplorerStore = create<ExplorerStore>((set, get) => ({
     tabs: [],
     activeTabId: null,
@@ -213,6 +230,10 @@ export const useExplorerStore = create<ExplorerStore>((set, get) => ({
                     entries: response.entries,
                     total: response.total,
                     has_more: response.has_more,
+                    sortBy: response.view.sort_by,
+                    order: response.view.order,
+                    viewMode: response.view.view_mode ?? t.viewMode,
+                    folderView: response.view.override_source ? response.view : null,
                     loading: false,
                     history: nextHistory,
                     currentIndex: nextIndex,
@@ -265,6 +286,8 @@ export const useExplorerStore = create<ExplorerStore>((set, get) => ({
     },
 
     setSort: async (tabId, sortBy, order) => {
+        // In a folder with an override, the override decides the order, so it's updated instead
+        await updateFolderOverride(get().tabs.find(t => t.id === tabId), { sort_by: sortBy, order });
         set(state => ({
             tabs: state.tabs.map(t => t.id === tabId ? { ...t, sortBy, order, loading: true } : t)
         }));
@@ -338,12 +361,27 @@ export const useExplorerStore = create<ExplorerStore>((set, get) => ({
         }));
     },
 
-    setViewMode: (tabId, mode) => {
+    setViewMode: async (tabId, mode) => {
+        await updateFolderOverride(get().tabs.find(t => t.id === tabId), { view_mode: mode });
         set(state => ({
             tabs: state.tabs.map(t => t.id === tabId ? { ...t, viewMode: mode } : t)
         }));
     },
 
+    toggleFolderOverride: async (tabId) => {
+        const tab = get().tabs.find(t => t.id === tabId);
+        if (!tab) return;
+        try {
+            const folderOverride: FolderOverride | null = tab.folderView?.has_own_override
+                ? null
+                : { sort_by: tab.sortBy, order: tab.order, view_mode: tab.viewMode, inherit: true };
+            await invoke("set_folder_override", { path: tab.path, folderOverride });
+        } catch (error) {
+            console.error("Failed to update folder override:", error);
+        }
+        await get().refresh(tabId);
+    },
+
     goBack: (tabId) => {
         const tab = get().tabs.find(t => t.id === tabId);
         if (tab && tab.currentIndex > 0) {
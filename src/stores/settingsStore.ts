@@ -1,6 +1,7 @@
 import { create } from "zustand";
 import { invoke } from "@tauri-apps/api/core";
-import { AppSettings, ConfigSection, PreviewSettings } from "@/types/explorer";
+import { toast } from "sonner";
+import { AppSettings, ConfigSection, ExclusionSettings, PreviewSettings, SettingsReport } from "@/types/explorer";
 import { useExplorerStore } from "./explorerStore";
 
 const GRID_THUMB_WIDTH_KEY = "sdm-grid-thumb-width";
@@ -33,8 +34,34 @@ const DEFAULT_CONFIG: ConfigSection = {
     ],
     blocked_names: ["LICENSE", "README", "package-lock.json", "pnpm-lock.yaml", "yarn.lock", ".gitignore", ".DS_Store"],
     include_plain_text_in_duplicate_scan: false,
+    detect_content_type: false,
+    same_filesystem: false,
+    skip_network_mounts: true,
 };
 
+const EMPTY_EXCLUSIONS: ExclusionSettings = {
+    protected_paths: [],
+    excluded_paths: [],
+    excluded_globs: [],
+};
+
+/** Tells the user when loading or saving settings lost values, or kept a backup of the file. */
+export function notifySettingsReport(report: SettingsReport) {
+    const backup = report.backup_path ? `The original file was saved to ${report.backup_path}` : undefined;
+    if (report.unreadable) {
+        toast.error("Settings file was unreadable and has been reset", {
+            description: [report.unreadable, backup].filter(Boolean).join(" — "),
+        });
+    } else if (report.reset_fields.length > 0) {
+        const fields = report.reset_fields.map((r) => `${r.field}: ${r.reason}`).join("; ");
+        toast.warning(`${report.reset_fields.length} invalid setting(s) reset to defaults`, {
+            description: [fields, backup].filter(Boolean).join(" — "),
+        });
+    } else if (backup) {
+        toast.warning("Settings were saved over a file from a newer version", { description: backup });
+    }
+}
+
 interface SettingsState {
     settings: AppSettings;
     loading: boolean;
@@ -51,6 +78,12 @@ interface SettingsState {
     removeBlockedName: (section: "explorer" | "dedupe" | "content_search" | "clean", name: string) => Promise<void>;
     updateTheme: (values: Partial<AppSettings["theme"]>) => Promise<void>;
     resetSettings: () => Promise<void>;
+    /** Applies settings pushed by the backend ("settings-changed") without saving them again. */
+    applyExternalSettings: (settings: AppSettings) => void;
+    addCategory: (name: string, extensions: string[]) => Promise<void>;
+    removeCategory: (id: string) => Promise<void>;
+    addExclusion: (list: keyof ExclusionSettings, value: string) => Promise<void>;
+    removeExclusion: (list: keyof ExclusionSettings, value: string) => Promise<void>;
 }
 
 export const useSettingsStore = create<SettingsState>((set, get) => ({
@@ -101,54 +134,16 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
     loadSettings: async () => {
         set({ loading: true });
         try {
-            const backendSettings = await invoke<AppSettings>("load_settings");
+            const settings = await invoke<AppSettings>("load_settings");
             const grid = getGridThumbFromStorage();
 
-            // Source of Truth: Backend (Disk) merged with Frontend (localStorage)
-            let resultSettings = backendSettings;
-
+            // The backend has already migrated and validated the file, so it is the source of
+            // truth; localStorage only mirrors it.
             if (typeof window !== "undefined") {
-                const stored = localStorage.getItem(SETTINGS_STORAGE_KEY);
-                if (stored) {
-                    try {
-                        const parsed = JSON.parse(stored) as AppSettings;
-                        
-                        // Intelligent merge helper to preserve new default categories
-                        const mergeSection = (sectionKey: "explorer" | "dedupe" | "content_search" | "clean") => {
-                            const defaultSection = DEFAULT_CONFIG;
-                            const backendSection = backendSettings[sectionKey];
-                            const storedSection = parsed[sectionKey];
-
-                            if (!storedSection || typeof storedSection !== 'object') return backendSection;
-                            
-                            return {
-                                ...defaultSection, // Start with defaults
-                                ...backendSection, // Layer backend values
-                                ...storedSection, // Layer stored values
-                                preview_enabled: {
-                                    ...defaultSection.preview_enabled,
-                                    ...(backendSection?.preview_enabled || {}),
-                                    ...(storedSection.preview_enabled || {})
-                                }
-                            };
-                        };
-
-                        resultSettings = {
-                            ...backendSettings,
-                            explorer: mergeSection("explorer"),
-                            dedupe: mergeSection("dedupe"),
-                            content_search: mergeSection("content_search"),
-                            clean: mergeSection("clean"),
-                            theme: { ...backendSettings.theme, ...(parsed.theme || {}) },
-                            setup_completed: typeof parsed.setup_completed === 'boolean' ? parsed.setup_completed : backendSettings.setup_completed,
-                        };
-                    } catch (e) {
-                        console.error("Failed to parse stored settings, falling back to backend defaults:", e);
-                    }
-                }
+                localStorage.setItem(SETTINGS_STORAGE_KEY, JSON.stringify(settings));
             }
 
-            set({ settings: resultSettings, loading: false, grid_thumbnail_width: grid.width, grid_thumbnail_height: grid.height });
+            set({ settings, loading: false, grid_thumbnail_width: grid.width, grid_thumbnail_height: grid.height });
         } catch (err) {
             console.error("Failed to load settings from backend:", err);
             const grid = getGridThumbFromStorage();
@@ -156,6 +151,84 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
         }
     },
 
+    applyExternalSettings: (settings) => {
+        if (JSON.stringify(settings) === JSON.stringify(get().settings)) return;
+        const explorerChanged = JSON.stringify(settings.explorer) !== JSON.stringify(get().settings.explorer);
+
+        set({ settings });
+
+        if (typeof window !== "undefined") {
+            localStorage.setItem(SETTINGS_STORAGE_KEY, JSON.stringify(settings));
+        }
+
+        if (explorerChanged) {
+            const explorer = useExplorerStore.getState();
+            explorer.refresh("left");
+            explorer.refresh("right");
+        }
+    },
+
+    addCategory: async (name, extensions) => {
+        const id = name.toLowerCase().split(/[^\p{L}\p{N}]+/u).filter(Boolean).join("-");
+        const categories = get().settings.categories ?? [];
+        if (!id || categories.some(c => c.id === id)) return;
+        const updatedSettings = {
+            ...get().settings,
+            categories: [...categories, { id, name: name.trim(), extensions }],
+        };
+        set({ settings: updatedSettings });
+
+        try {
+            await invoke("save_settings", { settings: updatedSettings });
+        } catch (err) {
+            console.error("Failed to save settings:", err);
+        }
+    },
+
+    removeCategory: async (id) => {
+        const categories = get().settings.categories ?? [];
+        const updatedSettings = { ...get().settings, categories: categories.filter(c => c.id !== id) };
+        set({ settings: updatedSettings });
+
+        try {
+            await invoke("save_settings", { settings: updatedSettings });
+        } catch (err) {
+            console.error("Failed to save settings:", err);
+        }
+    },
+
+    addExclusion: async (list, value) => {
+        const exclusions = { ...EMPTY_EXCLUSIONS, ...get().settings.exclusions };
+        const entry = value.trim();
+        if (!entry || exclusions[list].includes(entry)) return;
+        const updatedSettings = {
+            ...get().settings,
+            exclusions: { ...exclusions, [list]: [...exclusions[list], entry] },
+        };
+        set({ settings: updatedSettings });
+
+        try {
+            await invoke("save_settings", { settings: updatedSettings });
+        } catch (err) {
+            console.error("Failed to save settings:", err);
+        }
+    },
+
+    removeExclusion: async (list, value) => {
+        const exclusions = { ...EMPTY_EXCLUSIONS, ...get().settings.exclusions };
+        const updatedSettings = {
+            ...get().settings,
+            exclusions: { ...exclusions, [list]: exclusions[list].filter(entry => entry !== value) },
+        };
+        set({ settings: updatedSettings });
+
+        try {
+            await invoke("save_settings", { settings: updatedSettings });
+        } catch (err) {
+            console.error("Failed to save settings:", err);
+        }
+    },
+
     updateGridThumbnailSize: (width, height) => {
         const w = Number.isFinite(width) && width > 0 ? Math.round(width) : GRID_THUMB_DEFAULT;
         const h = Number.isFinite(height) && height > 0 ? Math.round(height) : GRID_THUMB_DEFAULT;
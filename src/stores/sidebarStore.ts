@@ -13,6 +13,8 @@ interface SidebarState {
 
     // Actions
     refreshVolumes: () => Promise<void>;
+    /** Unmounts a volume; rejects while running jobs still use it. */
+    ejectVolume: (mountPoint: string) => Promise<void>;
     toggleSidebar: () => void;
     toggleRightSidebar: () => void;
     addFavorite: (path: string) => void;
@@ -43,6 +45,11 @@ export const useSidebarStore = create<SidebarState>((set, get) => ({
         }
     },
 
+    ejectVolume: async (mountPoint) => {
+        await invoke("eject_volume", { mountPoint });
+        await get().refreshVolumes();
+    },
+
     toggleSidebar: () => set((state) => ({ isCollapsed: !state.isCollapsed })),
 
     toggleRightSidebar: () => set((state) => ({ isRightSidebarOpen: !state.isRightSidebarOpen })),
@@ -6,16 +6,64 @@ export interface FileEntry {
     size: number | null;
     modified: number | null;
     extension: string | null;
+    /** Present when the section has `detect_content_type` on. */
+    detected_type?: TypeDetection;
+}
+
+/** The category a file's extension implies next to the one its content does. */
+export interface TypeDetection {
+    extension_category: string | null;
+    /** null when no signature matched, which includes plain text. */
+    content_mime: string | null;
+    content_name: string | null;
+    content_category: string | null;
+    /** Set only when the file's extension doesn't fit its content. */
+    expected_extension: string | null;
+}
+
+export interface ExtensionMismatch {
+    path: string;
+    extension: string;
+    detected: TypeDetection;
 }
 
 export interface DirectoryResponse {
     entries: FileEntry[];
     total: number;
     has_more: boolean;
+    view: FolderView;
 }
 
 export type SortField = "name" | "size" | "modified";
 export type SortOrder = "asc" | "desc";
+export type ViewMode = "list" | "grid";
+
+/** How a listing was sorted and should be shown, after folder overrides. */
+export interface FolderView {
+    sort_by: SortField;
+    order: SortOrder;
+    view_mode: ViewMode | null;
+    /** Nearest folder whose override applies here. */
+    override_source: string | null;
+    has_own_override: boolean;
+}
+
+/** Per-folder settings; unset fields fall through to inherited overrides, then the explorer settings. */
+export interface FolderOverride {
+    sort_by?: SortField;
+    order?: SortOrder;
+    show_hidden_files?: boolean;
+    blocked_extensions?: string[];
+    view_mode?: ViewMode;
+    /** Also applies to subfolders. */
+    inherit?: boolean;
+}
+
+export interface ResolvedFolderOverride {
+    own: FolderOverride | null;
+    effective: FolderOverride;
+    source: string | null;
+}
 
 export interface PanelState {
     path: string;
@@ -27,7 +75,9 @@ export interface PanelState {
     order: SortOrder;
     selection: Set<string>;
     isExpanded: boolean;
-    viewMode: "list" | "grid";
+    viewMode: ViewMode;
+    /** Set when a folder override applies to `path`. */
+    folderView: FolderView | null;
     history: string[];
     currentIndex: number;
     lastSelectedPath: string | null;
@@ -48,6 +98,9 @@ export interface SearchResult {
     preview?: string;
 }
 
+/** local, removable, network, virtual (proc, tmpfs, …) or loop (disk images). */
+export type MountKind = "local" | "removable" | "network" | "virtual" | "loop";
+
 export interface Volume {
     name: string;
     mount_point: string;
@@ -55,6 +108,16 @@ export interface Volume {
     available_space: number;
     is_removable: boolean;
     is_system: boolean;
+    kind: MountKind;
+    /** e.g. "ext4", "apfs", "ntfs". */
+    fs_type: string;
+    /** Backing device or share, e.g. "/dev/sdb1". */
+    device: string | null;
+    read_only: boolean;
+    uuid: string | null;
+    label: string | null;
+    inodes_total: number | null;
+    inodes_free: number | null;
 }
 
 export interface PreviewSettings {
@@ -63,6 +126,24 @@ export interface PreviewSettings {
     audio: boolean;
     document: boolean;
     archive: boolean;
+    /** Toggles for user-defined categories, keyed by category id (on unless set to false). */
+    [category: string]: boolean;
+}
+
+export interface MagicSignature {
+    offset?: number;
+    /** Bytes as hex, e.g. "25 50 44 46". */
+    hex: string;
+}
+
+export interface CategoryDefinition {
+    /** Built-ins: image, video, audio, document, archive. */
+    id: string;
+    name: string;
+    extensions: string[];
+    /** Matched against the sniffed type for extensions no category claims, e.g. "image/*". */
+    mime_types?: string[];
+    signatures?: MagicSignature[];
 }
 
 export interface ConfigSection {
@@ -76,6 +157,35 @@ export interface ConfigSection {
      * (low space impact). Enable to include them in duplicate scans.
      */
     include_plain_text_in_duplicate_scan?: boolean;
+    /** Also categorize files by their content; listings and scans then report both types. */
+    detect_content_type?: boolean;
+    /** Walks don't descend into other mounted filesystems. */
+    same_filesystem?: boolean;
+    /** Walks skip network shares mounted below their start folder (default true). */
+    skip_network_mounts?: boolean;
+}
+
+/** User additions to the built-in exclusion rules; shared by every section. */
+export interface ExclusionSettings {
+    /** Never deleted, moved or renamed, nor is any folder containing them. */
+    protected_paths: string[];
+    /** Folders every scan and search skips. */
+    excluded_paths: string[];
+    /** Without a "/" they match the file name ("node_modules", "*.bak"), otherwise the whole path. */
+    excluded_globs: string[];
+}
+
+export interface ExclusionRule {
+    kind: "system" | "protected" | "excluded_path" | "excluded_glob";
+    source: "platform" | "user";
+    pattern: string;
+}
+
+/** Result of `explain_exclusion`; null means no rule applies. */
+export interface ExclusionReport {
+    path: string;
+    excluded_by: ExclusionRule | null;
+    protected_by: ExclusionRule | null;
 }
 
 export interface ThemeSettings {
@@ -84,12 +194,36 @@ export interface ThemeSettings {
 }
 
 export interface AppSettings {
+    /** Settings file format version; filled in by the backend on load and save. */
+    schema_version?: number;
     explorer: ConfigSection;
     dedupe: ConfigSection;
     content_search: ConfigSection;
     clean: ConfigSection;
     theme: ThemeSettings;
     setup_completed: boolean;
+    categories?: CategoryDefinition[];
+    exclusions?: ExclusionSettings;
+    /** Profile last switched to with `switch_profile`. */
+    active_profile?: string | null;
+}
+
+export interface SettingsFieldReset {
+    /** Dotted path, e.g. `theme.custom_color` or `artifact_rules[2]`. */
+    field: string;
+    reason: string;
+}
+
+/** Emitted on "settings-validation" and returned by `get_settings_report`. */
+export interface SettingsReport {
+    found_version: number | null;
+    migrated: boolean;
+    /** Parse error when the file wasn't valid JSON and was replaced with defaults. */
+    unreadable: string | null;
+    /** Copy of the original file, kept whenever values were discarded. */
+    backup_path: string | null;
+    reset_fields: SettingsFieldReset[];
+    normalized_fields: string[];
 }
 
 export interface TreeNode {
@@ -106,8 +240,34 @@ export interface DuplicateGroup {
     modified_times: number[];
 }
 
-export interface EmptyFolder {
+export type CleanReason = "empty_folder" | "junk_only_folder" | "zero_byte_file" | "broken_symlink" | "symlink_loop";
+
+/** A path reported by `find_empty_folders`. */
+export interface CleanFinding {
     path: string;
     name: string;
     parent_path: string;
+    is_dir: boolean;
+    reason: CleanReason;
+}
+
+export type DeleteStatus = "deleted" | "not_empty" | "unsupported" | "protected" | "missing" | "failed";
+
+/** Result of `delete_empty_folders` for one path. */
+export interface DeleteOutcome {
+    path: string;
+    status: DeleteStatus;
+    error: string | null;
+    /** Ancestors that became empty and were removed as well. */
+    removed_parents: string[];
+}
+
+/** Which kinds of findings a clean scan reports. */
+export interface CleanScanOptions {
+    empty_folders: boolean;
+    zero_byte_files: boolean;
+    broken_symlinks: boolean;
+    symlink_loops: boolean;
+    /** Count folders holding only .DS_Store, Thumbs.db and the like as empty. */
+    junk_as_empty: boolean;
 }